@@ -27,6 +27,13 @@ impl<'a> Action<'a> {
         self.insert(Name(b"D")).start()
     }
 
+    /// Write the `/D` attribute to set the destination of this GoTo-type action
+    /// to a [destination](Chunk::destination) written as an indirect object.
+    pub fn destination_ref(&mut self, id: Ref) -> &mut Self {
+        self.pair(Name(b"D"), id);
+        self
+    }
+
     /// Write the `/D` attribute to set the destination of this GoTo-type action
     /// to a named destination.
     pub fn destination_named(&mut self, name: Name) -> &mut Self {
@@ -51,6 +58,12 @@ impl<'a> Action<'a> {
         self
     }
 
+    /// Start writing the `/Win` dictionary to set Windows-specific launch
+    /// parameters. Only permissible for the subtype `Launch`.
+    pub fn win_launch(&mut self) -> WinLaunchParams<'_> {
+        self.insert(Name(b"Win")).start()
+    }
+
     /// Write the `/URI` attribute to set where this link action goes.
     pub fn uri(&mut self, uri: Str) -> &mut Self {
         self.pair(Name(b"URI"), uri);
@@ -115,6 +128,106 @@ impl<'a> Action<'a> {
     pub fn rendition(&mut self) -> Rendition<'_> {
         self.insert(Name(b"R")).start()
     }
+
+    /// Start writing the `/State` array to set the optional content group
+    /// states to apply. Only permissible for the subtype `SetOCGState`.
+    pub fn ocg_state(&mut self) -> OcgState<'_> {
+        self.insert(Name(b"State")).start()
+    }
+
+    /// Write the `/PreserveRB` attribute to set whether radio-button
+    /// relationships between optional content groups should be preserved when
+    /// setting states. Only permissible for the subtype `SetOCGState`.
+    pub fn preserve_rb(&mut self, preserve: bool) -> &mut Self {
+        self.pair(Name(b"PreserveRB"), preserve);
+        self
+    }
+
+    /// Start writing the `/Trans` dictionary to set the transition to use for
+    /// updating the display. Only permissible for the subtype `Trans`.
+    pub fn transition(&mut self) -> Transition<'_> {
+        self.insert(Name(b"Trans")).start()
+    }
+
+    /// Write the `/TA` attribute to reference the 3D annotation whose view
+    /// shall be changed. Only permissible for the subtype `GoTo3DView`.
+    pub fn target_annotation(&mut self, id: Ref) -> &mut Self {
+        self.pair(Name(b"TA"), id);
+        self
+    }
+
+    /// Write the `/V` attribute to reference the 3D view dictionary to use.
+    /// Only permissible for the subtype `GoTo3DView`.
+    pub fn view_3d(&mut self, id: Ref) -> &mut Self {
+        self.pair(Name(b"V"), id);
+        self
+    }
+
+    /// Write the `/V` attribute to select a 3D view by its index in the 3D
+    /// stream's view array. Only permissible for the subtype `GoTo3DView`.
+    pub fn view_3d_index(&mut self, index: i32) -> &mut Self {
+        self.pair(Name(b"V"), index);
+        self
+    }
+
+    /// Write the `/Sound` attribute to reference the [sound stream](Sound) to
+    /// play. Required for the subtype `Sound`.
+    pub fn sound(&mut self, id: Ref) -> &mut Self {
+        self.pair(Name(b"Sound"), id);
+        self
+    }
+
+    /// Write the `/Volume` attribute to set the volume at which to play the
+    /// sound, in the range from `-1.0` to `1.0`. Only permissible for the
+    /// subtype `Sound`.
+    pub fn volume(&mut self, volume: f32) -> &mut Self {
+        self.pair(Name(b"Volume"), volume);
+        self
+    }
+
+    /// Write the `/Synchronous` attribute to set whether the sound shall be
+    /// played synchronously, blocking further interaction until it is
+    /// finished. Only permissible for the subtype `Sound`.
+    pub fn synchronous(&mut self, synchronous: bool) -> &mut Self {
+        self.pair(Name(b"Synchronous"), synchronous);
+        self
+    }
+
+    /// Write the `/Repeat` attribute to set whether the sound shall be
+    /// repeated indefinitely. Only permissible for the subtype `Sound`.
+    pub fn repeat(&mut self, repeat: bool) -> &mut Self {
+        self.pair(Name(b"Repeat"), repeat);
+        self
+    }
+
+    /// Write the `/Mix` attribute to set whether the sound shall be mixed with
+    /// any other sound already playing instead of stopping it. Only
+    /// permissible for the subtype `Sound`.
+    pub fn mix(&mut self, mix: bool) -> &mut Self {
+        self.pair(Name(b"Mix"), mix);
+        self
+    }
+
+    /// Write the `/Annotation` attribute to reference the movie annotation
+    /// identifying the movie to play. Only permissible for the subtype `Movie`.
+    pub fn movie_annotation(&mut self, id: Ref) -> &mut Self {
+        self.pair(Name(b"Annotation"), id);
+        self
+    }
+
+    /// Write the `/T` attribute to identify the movie annotation to play by its
+    /// title. Only permissible for the subtype `Movie`.
+    pub fn movie_title(&mut self, title: TextStr) -> &mut Self {
+        self.pair(Name(b"T"), title);
+        self
+    }
+
+    /// Write the `/Operation` attribute to set the operation to perform on the
+    /// movie. Only permissible for the subtype `Movie`.
+    pub fn movie_operation(&mut self, op: MovieOperation) -> &mut Self {
+        self.pair(Name(b"Operation"), op.to_name());
+        self
+    }
 }
 
 deref!('a, Action<'a> => Dict<'a>, dict);
@@ -178,6 +291,101 @@ impl<'a> Fields<'a> {
 
 deref!('a, Fields<'a> => Array<'a>, array);
 
+/// Writer for an _optional content state array_. PDF 1.5+.
+///
+/// The array consists of state names followed by the optional content groups
+/// the state shall be applied to.
+///
+/// This struct is created by [`Action::ocg_state`].
+pub struct OcgState<'a> {
+    array: Array<'a>,
+}
+
+writer!(OcgState: |obj| Self { array: obj.array() });
+
+impl<'a> OcgState<'a> {
+    /// Turn the given optional content groups on.
+    pub fn on(&mut self, groups: impl IntoIterator<Item = Ref>) -> &mut Self {
+        self.array.item(Name(b"ON")).items(groups);
+        self
+    }
+
+    /// Turn the given optional content groups off.
+    pub fn off(&mut self, groups: impl IntoIterator<Item = Ref>) -> &mut Self {
+        self.array.item(Name(b"OFF")).items(groups);
+        self
+    }
+
+    /// Toggle the state of the given optional content groups.
+    pub fn toggle(&mut self, groups: impl IntoIterator<Item = Ref>) -> &mut Self {
+        self.array.item(Name(b"Toggle")).items(groups);
+        self
+    }
+}
+
+deref!('a, OcgState<'a> => Array<'a>, array);
+
+/// Writer for a _Windows launch parameters dictionary_.
+///
+/// This struct is created by [`Action::win_launch`].
+pub struct WinLaunchParams<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(WinLaunchParams: |obj| Self { dict: obj.dict() });
+
+impl<'a> WinLaunchParams<'a> {
+    /// Write the `/F` attribute to set the file name of the application to
+    /// launch or the document to open or print. Required.
+    pub fn file(&mut self, file: Str) -> &mut Self {
+        self.pair(Name(b"F"), file);
+        self
+    }
+
+    /// Write the `/D` attribute to set the default directory in standard DOS
+    /// syntax.
+    pub fn directory(&mut self, dir: Str) -> &mut Self {
+        self.pair(Name(b"D"), dir);
+        self
+    }
+
+    /// Write the `/O` attribute to set the operation to perform on a
+    /// document. Not permissible if the file is an application.
+    ///
+    /// _Default value_: `Open`.
+    pub fn operation(&mut self, op: WinLaunchOperation) -> &mut Self {
+        self.pair(Name(b"O"), op.to_str());
+        self
+    }
+
+    /// Write the `/P` attribute to set the parameters passed to the
+    /// application. Only permissible if the file is an application.
+    pub fn parameters(&mut self, params: Str) -> &mut Self {
+        self.pair(Name(b"P"), params);
+        self
+    }
+}
+
+deref!('a, WinLaunchParams<'a> => Dict<'a>, dict);
+
+/// What a Windows [launch action](WinLaunchParams) does with a document.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum WinLaunchOperation {
+    /// Open the document.
+    Open,
+    /// Print the document.
+    Print,
+}
+
+impl WinLaunchOperation {
+    pub(crate) fn to_str(self) -> Str<'static> {
+        match self {
+            Self::Open => Str(b"open"),
+            Self::Print => Str(b"print"),
+        }
+    }
+}
+
 /// What kind of action to perform when clicking a link annotation.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum ActionType {
@@ -203,6 +411,16 @@ pub enum ActionType {
     JavaScript,
     /// A rendition action to control the playing of multimedia content. PDF 1.5+.
     Rendition,
+    /// Set the states of optional content groups. PDF 1.5+.
+    SetOcgState,
+    /// Update the display of a document using a transition. PDF 1.5+.
+    Trans,
+    /// Set the current view of a 3D annotation. PDF 1.6+.
+    GoTo3DView,
+    /// Play a sound. PDF 1.2+. Deprecated.
+    Sound,
+    /// Play a movie. PDF 1.2+. Deprecated.
+    Movie,
 }
 
 impl ActionType {
@@ -217,6 +435,35 @@ impl ActionType {
             Self::ImportData => Name(b"ImportData"),
             Self::JavaScript => Name(b"JavaScript"),
             Self::Rendition => Name(b"Rendition"),
+            Self::SetOcgState => Name(b"SetOCGState"),
+            Self::Trans => Name(b"Trans"),
+            Self::GoTo3DView => Name(b"GoTo3DView"),
+            Self::Sound => Name(b"Sound"),
+            Self::Movie => Name(b"Movie"),
+        }
+    }
+}
+
+/// The operation to perform when a movie action is triggered.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum MovieOperation {
+    /// Start playing the movie.
+    Play,
+    /// Stop playing the movie.
+    Stop,
+    /// Pause a playing movie.
+    Pause,
+    /// Resume a paused movie.
+    Resume,
+}
+
+impl MovieOperation {
+    pub(crate) fn to_name(self) -> Name<'static> {
+        match self {
+            Self::Play => Name(b"Play"),
+            Self::Stop => Name(b"Stop"),
+            Self::Pause => Name(b"Pause"),
+            Self::Resume => Name(b"Resume"),
         }
     }
 }
@@ -1,4 +1,5 @@
 use super::*;
+use crate::types::{ActionType, BlendMode, Color, DashPattern};
 
 /// Writer for an _annotation dictionary_.
 ///
@@ -138,13 +139,81 @@ impl<'a> Annotation<'a> {
         self
     }
 
+    /// Write the `/C` attribute using a typed color. This sets the annotations
+    /// background color and its popup title bar color. PDF 1.1+.
+    pub fn color(&mut self, color: Color) -> &mut Self {
+        color.write(self.insert(Name(b"C")));
+        self
+    }
+
+    /// Write the `/C` attribute from raw color components. This sets the
+    /// annotations background color and its popup title bar color. PDF 1.1+.
+    ///
+    /// The number of components determines the color space: Zero components
+    /// mean transparent, one is `DeviceGray`, three are `DeviceRGB` and four
+    /// are `DeviceCMYK`.
+    ///
+    /// Panics if the number of components is not 0, 1, 3 or 4.
+    pub fn color_components(&mut self, components: &[f32]) -> &mut Self {
+        assert!(
+            matches!(components.len(), 0 | 1 | 3 | 4),
+            "color must have 0, 1, 3 or 4 components (has {})",
+            components.len(),
+        );
+        self.insert(Name(b"C")).array().items(components.iter().copied());
+        self
+    }
+
+    /// Write the `/IC` attribute. This sets the interior color of square,
+    /// circle, line, polygon and polyline annotations. PDF 1.4+.
+    pub fn interior_color(&mut self, color: Color) -> &mut Self {
+        color.write(self.insert(Name(b"IC")));
+        self
+    }
+
+    /// Write the `/CA` attribute to set the opacity of the annotation's
+    /// appearance. Since PDF 2.0, this only applies to stroking operations.
+    /// PDF 1.4+.
+    pub fn stroking_alpha(&mut self, alpha: f32) -> &mut Self {
+        self.pair(Name(b"CA"), alpha);
+        self
+    }
+
+    /// Write the `/ca` attribute to set the opacity of non-stroking operations
+    /// in the annotation's appearance. PDF 2.0+.
+    pub fn non_stroking_alpha(&mut self, alpha: f32) -> &mut Self {
+        self.pair(Name(b"ca"), alpha);
+        self
+    }
+
+    /// Write the `/BM` attribute to set the blend mode used when compositing
+    /// the annotation's appearance onto the page. PDF 2.0+.
+    pub fn blend_mode(&mut self, mode: BlendMode) -> &mut Self {
+        self.pair(Name(b"BM"), mode.to_name());
+        self
+    }
+
     /// Write the `/StructParent` attribute to indicate the [structure tree
     /// element][StructElement] this annotation belongs to. PDF 1.3+.
+    ///
+    /// The key must map to the structure element in the [parent
+    /// tree](crate::writers::StructTreeRoot::parent_tree). Tagged documents
+    /// must link all link annotations to the structure tree this way.
     pub fn struct_parent(&mut self, key: i32) -> &mut Self {
         self.pair(Name(b"StructParent"), key);
         self
     }
 
+    /// Write the `/Lang` attribute to set the natural language of the
+    /// annotation's text, for example its contents. PDF 2.0+.
+    ///
+    /// Structure elements can be given languages, alternate descriptions and
+    /// expansions through [`StructElement`].
+    pub fn lang(&mut self, lang: TextStr) -> &mut Self {
+        self.pair(Name(b"Lang"), lang);
+        self
+    }
+
     /// Start writing the `/A` dictionary. Only permissible for the subtypes
     /// `Link` and `Widget`.
     pub fn action(&mut self) -> Action<'_> {
@@ -179,16 +248,36 @@ impl<'a> Annotation<'a> {
         self
     }
 
-    /// Write the `/QuadPoints` attribute, specifying the region in which the
-    /// link should be activated. PDF 1.6+.
-    pub fn quad_points(
+    /// Write the `/QuadPoints` attribute, specifying the regions covered by a
+    /// link or text markup annotation. PDF 1.6+ for links.
+    ///
+    /// Rectangles can be passed directly and are converted with
+    /// [`Rect::to_quad`].
+    pub fn quad_points<Q: Into<Quad>>(
         &mut self,
-        coordinates: impl IntoIterator<Item = f32>,
+        quads: impl IntoIterator<Item = Q>,
     ) -> &mut Self {
-        self.insert(Name(b"QuadPoints")).array().items(coordinates);
+        self.insert(Name(b"QuadPoints"))
+            .array()
+            .items(quads.into_iter().flat_map(|quad| quad.into().to_array()));
         self
     }
 
+    /// Write the `/Rect` and `/QuadPoints` attributes of a text markup
+    /// annotation that covers the given line boxes, e.g. of a highlight
+    /// spanning multiple lines.
+    ///
+    /// The boxes may be specified by any two opposite corners. The `/Rect` is
+    /// their union and each box becomes one quadrilateral.
+    ///
+    /// Panics if `lines` is empty.
+    pub fn line_boxes(&mut self, lines: &[Rect]) -> &mut Self {
+        let (first, rest) = lines.split_first().expect("no line boxes given");
+        let rect = rest.iter().fold(first.union(*first), |acc, &line| acc.union(line));
+        self.rect(rect);
+        self.quad_points(lines.iter().map(|&line| line.union(line)))
+    }
+
     /// Write the `/L` attribute. This defines the start and end point of a
     /// line annotation
     pub fn line_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32) -> &mut Self {
@@ -201,6 +290,38 @@ impl<'a> Annotation<'a> {
         self.insert(Name(b"FS")).start()
     }
 
+    /// Write the `/Sound` attribute to reference the [sound](Sound) to play
+    /// when the annotation is activated. Required for and only permissible for
+    /// the subtype `Sound`.
+    pub fn sound(&mut self, id: Ref) -> &mut Self {
+        self.pair(Name(b"Sound"), id);
+        self
+    }
+
+    /// Write the `/RD` attribute to set the differences between the `/Rect`
+    /// and the actual boundaries of the drawn annotation, e.g. to leave space
+    /// for a border or a cloudy effect. Only permissible for the subtypes
+    /// `Square`, `Circle` and `Caret`. PDF 1.5+.
+    pub fn rect_differences(
+        &mut self,
+        left: f32,
+        top: f32,
+        right: f32,
+        bottom: f32,
+    ) -> &mut Self {
+        self.insert(Name(b"RD")).array().items([left, top, right, bottom]);
+        self
+    }
+
+    /// Write the `/Sy` attribute to set the symbol drawn with the caret. Only
+    /// permissible for the subtype `Caret`. PDF 1.5+.
+    ///
+    /// _Default value_: `None`.
+    pub fn caret_symbol(&mut self, symbol: CaretSymbol) -> &mut Self {
+        self.pair(Name(b"Sy"), symbol.to_name());
+        self
+    }
+
     /// Write the `/Name` attribute. Refer to the specification to see which
     /// names are allowed for which annotation types.
     pub fn icon(&mut self, icon: AnnotationIcon) -> &mut Self {
@@ -208,6 +329,31 @@ impl<'a> Annotation<'a> {
         self
     }
 
+    /// Write the `/Open` attribute. This specifies whether a text annotation
+    /// shall initially be displayed open. PDF 1.0+.
+    pub fn open(&mut self, open: bool) -> &mut Self {
+        self.pair(Name(b"Open"), open);
+        self
+    }
+
+    /// Write the `/State` attribute. This is the state of a text annotation
+    /// that is in reply to another annotation, for example `Accepted`,
+    /// `Rejected`, `Cancelled`, `Completed` or `None` for the `Review` state
+    /// model and `Marked` or `Unmarked` for the `Marked` state model.
+    /// PDF 1.5+.
+    pub fn state(&mut self, state: TextStr) -> &mut Self {
+        self.pair(Name(b"State"), state);
+        self
+    }
+
+    /// Write the `/StateModel` attribute. This is the state model of the
+    /// `/State` attribute, usually `Marked` or `Review`. Required if `/State`
+    /// is present. PDF 1.5+.
+    pub fn state_model(&mut self, model: TextStr) -> &mut Self {
+        self.pair(Name(b"StateModel"), model);
+        self
+    }
+
     /// Start writing the `/MK` dictionary. Only permissible for the subtype
     /// `Widget`.
     pub fn appearance_characteristics(&mut self) -> AppearanceCharacteristics<'_> {
@@ -220,10 +366,114 @@ impl<'a> Annotation<'a> {
         self.pair(Name(b"Parent"), id);
         self
     }
+
+    /// Start writing the `/FixedPrint` dictionary. Only permissible for the
+    /// subtype `Watermark`. PDF 1.6+.
+    pub fn fixed_print(&mut self) -> FixedPrint<'_> {
+        self.insert(Name(b"FixedPrint")).start()
+    }
+
+    /// Start writing the `/ExData` dictionary to link the annotation to 3D
+    /// content. PDF 1.7+.
+    pub fn external_data(&mut self) -> ExData<'_> {
+        self.insert(Name(b"ExData")).start()
+    }
+
+    /// Start writing the `/RichMediaContent` dictionary. Required for and only
+    /// permissible for the subtype `RichMedia`. PDF 2.0+.
+    pub fn rich_media_content(&mut self) -> RichMediaContent<'_> {
+        self.insert(Name(b"RichMediaContent")).start()
+    }
+
+    /// Start writing the `/RichMediaSettings` dictionary. Only permissible for
+    /// the subtype `RichMedia`. PDF 2.0+.
+    pub fn rich_media_settings(&mut self) -> RichMediaSettings<'_> {
+        self.insert(Name(b"RichMediaSettings")).start()
+    }
 }
 
 deref!('a, Annotation<'a> => Dict<'a>, dict);
 
+/// A lightweight description of a simple annotation.
+///
+/// Use this with [`Chunk::extend_annotations`] to write many similar
+/// annotations, like the links of a large document, without driving an
+/// [`Annotation`] writer for each of them.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AnnotationSpec<'a> {
+    /// The `/Subtype` of the annotation.
+    pub subtype: AnnotationType,
+    /// The `/Rect` of the annotation.
+    pub rect: Rect,
+    /// A URI to open with a URI action when the annotation is activated.
+    pub uri: Option<Str<'a>>,
+    /// A [destination](Chunk::destination) to go to when the annotation is
+    /// activated. Only permissible for links without a URI.
+    pub destination: Option<Ref>,
+    /// The `/C` color of the annotation.
+    pub color: Option<Color<'a>>,
+    /// The `/F` flags of the annotation.
+    pub flags: Option<AnnotationFlags>,
+}
+
+impl<'a> AnnotationSpec<'a> {
+    /// Describe an annotation with the given subtype and rectangle.
+    pub fn new(subtype: AnnotationType, rect: Rect) -> Self {
+        Self {
+            subtype,
+            rect,
+            uri: None,
+            destination: None,
+            color: None,
+            flags: None,
+        }
+    }
+
+    /// Describe a link annotation that opens a URI.
+    pub fn link(rect: Rect, uri: Str<'a>) -> Self {
+        Self {
+            uri: Some(uri),
+            ..Self::new(AnnotationType::Link, rect)
+        }
+    }
+
+    /// Write the described entries into an annotation.
+    pub(crate) fn write(&self, annotation: &mut Annotation) {
+        annotation.subtype(self.subtype);
+        annotation.rect(self.rect);
+        if let Some(flags) = self.flags {
+            annotation.flags(flags);
+        }
+        if let Some(color) = self.color {
+            annotation.color(color);
+        }
+        if let Some(id) = self.destination {
+            annotation.pair(Name(b"Dest"), id);
+        }
+        if let Some(uri) = self.uri {
+            annotation.action().action_type(ActionType::Uri).uri(uri);
+        }
+    }
+}
+
+/// The symbol drawn along with a caret annotation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CaretSymbol {
+    /// A new paragraph symbol (¶) for paragraph insertions.
+    Paragraph,
+    /// No symbol.
+    None,
+}
+
+impl CaretSymbol {
+    pub(crate) fn to_name(self) -> Name<'static> {
+        match self {
+            Self::Paragraph => Name(b"P"),
+            Self::None => Name(b"None"),
+        }
+    }
+}
+
 /// Kind of the annotation to produce.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum AnnotationType {
@@ -245,12 +495,28 @@ pub enum AnnotationType {
     Squiggly,
     /// Strike out the text on the page. PDF 1.3+.
     StrikeOut,
+    /// A caret that marks where text should be inserted. PDF 1.5+.
+    Caret,
     /// A reference to another file. PDF 1.3+.
     FileAttachment,
+    /// A sound that is played when the annotation is activated. PDF 1.2+.
+    /// Deprecated in PDF 2.0.
+    Sound,
     /// A widget annotation. PDF 1.2+.
     Widget,
     /// A screen annotation. PDF 1.5+.
     Screen,
+    /// A printer's mark annotation. PDF 1.4+.
+    PrinterMark,
+    /// A trap network annotation. PDF 1.3+.
+    TrapNet,
+    /// A watermark annotation. PDF 1.6+.
+    Watermark,
+    /// A rich media annotation with 3D, video, sound or Flash content. PDF
+    /// 2.0+.
+    RichMedia,
+    /// A projection of a 3D measurement or comment onto the page. PDF 2.0+.
+    Projection,
 }
 
 impl AnnotationType {
@@ -265,9 +531,16 @@ impl AnnotationType {
             Self::Underline => Name(b"Underline"),
             Self::Squiggly => Name(b"Squiggly"),
             Self::StrikeOut => Name(b"StrikeOut"),
+            Self::Caret => Name(b"Caret"),
             Self::FileAttachment => Name(b"FileAttachment"),
+            Self::Sound => Name(b"Sound"),
             Self::Widget => Name(b"Widget"),
             Self::Screen => Name(b"Screen"),
+            Self::PrinterMark => Name(b"PrinterMark"),
+            Self::TrapNet => Name(b"TrapNet"),
+            Self::Watermark => Name(b"Watermark"),
+            Self::RichMedia => Name(b"RichMedia"),
+            Self::Projection => Name(b"Projection"),
         }
     }
 }
@@ -297,6 +570,10 @@ pub enum AnnotationIcon<'a> {
     Paperclip,
     /// For use with file attachment annotations.
     Tag,
+    /// Loudspeaker. For use with sound annotations.
+    Speaker,
+    /// Microphone. For use with sound annotations.
+    Mic,
     /// A custom icon name.
     Custom(Name<'a>),
 }
@@ -315,6 +592,8 @@ impl<'a> AnnotationIcon<'a> {
             Self::PushPin => Name(b"PushPin"),
             Self::Paperclip => Name(b"Paperclip"),
             Self::Tag => Name(b"Tag"),
+            Self::Speaker => Name(b"Speaker"),
+            Self::Mic => Name(b"Mic"),
             Self::Custom(name) => name,
         }
     }
@@ -322,6 +601,7 @@ impl<'a> AnnotationIcon<'a> {
 
 bitflags::bitflags! {
     /// Bitflags describing various characteristics of annotations.
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
     pub struct AnnotationFlags: u32 {
         /// This will hide the annotation if the viewer does not recognize its
         /// subtype. Otherwise, it will be rendered as specified in its appearance
@@ -400,6 +680,13 @@ impl<'a> AppearanceCharacteristics<'a> {
         self
     }
 
+    /// Write the `/BC` attribute using a typed color. This sets the widget
+    /// annotation's border color.
+    pub fn border_color(&mut self, color: Color) -> &mut Self {
+        color.write(self.insert(Name(b"BC")));
+        self
+    }
+
     /// Write the `/BG` attribute forcing a transparent color. This sets the
     /// widget annotation's background color.
     pub fn background_color_transparent(&mut self) -> &mut Self {
@@ -428,6 +715,13 @@ impl<'a> AppearanceCharacteristics<'a> {
         self
     }
 
+    /// Write the `/BG` attribute using a typed color. This sets the widget
+    /// annotation's background color.
+    pub fn background_color(&mut self, color: Color) -> &mut Self {
+        color.write(self.insert(Name(b"BG")));
+        self
+    }
+
     /// Write the `/CA` attribute. This sets the widget annotation's normal
     /// caption. Only permissible for button fields.
     pub fn normal_caption(&mut self, caption: TextStr) -> &mut Self {
@@ -711,8 +1005,11 @@ impl<'a> BorderStyle<'a> {
 
     /// Write the `/D` attribute to set the repeating lengths of dashes and gaps
     /// in between.
-    pub fn dashes(&mut self, dash_pattern: impl IntoIterator<Item = f32>) -> &mut Self {
-        self.insert(Name(b"D")).array().items(dash_pattern);
+    ///
+    /// Border dash patterns always start at the beginning, so the phase is
+    /// ignored.
+    pub fn dashes(&mut self, pattern: DashPattern) -> &mut Self {
+        self.insert(Name(b"D")).array().items(pattern.array.iter().copied());
         self
     }
 }
@@ -746,6 +1043,248 @@ impl BorderType {
     }
 }
 
+/// Writer for a _fixed print dictionary_. PDF 1.6+.
+///
+/// This struct is created by [`Annotation::fixed_print`]. It specifies how a
+/// watermark annotation shall be drawn relative to the dimensions of the
+/// target media when printing.
+pub struct FixedPrint<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(FixedPrint: |obj| {
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Type"), Name(b"FixedPrint"));
+    Self { dict }
+});
+
+impl<'a> FixedPrint<'a> {
+    /// Write the `/Matrix` attribute to map the annotation's appearance onto
+    /// the target media.
+    pub fn matrix(&mut self, matrix: [f32; 6]) -> &mut Self {
+        self.insert(Name(b"Matrix")).array().items(matrix);
+        self
+    }
+
+    /// Write the `/H` attribute. This is the amount to translate the
+    /// annotation horizontally, as a fraction of the target media width.
+    pub fn horizontal(&mut self, translation: f32) -> &mut Self {
+        self.pair(Name(b"H"), translation);
+        self
+    }
+
+    /// Write the `/V` attribute. This is the amount to translate the
+    /// annotation vertically, as a fraction of the target media height.
+    pub fn vertical(&mut self, translation: f32) -> &mut Self {
+        self.pair(Name(b"V"), translation);
+        self
+    }
+}
+
+deref!('a, FixedPrint<'a> => Dict<'a>, dict);
+
+/// Writer for an _external data dictionary_. PDF 1.7+.
+///
+/// This struct is created by [`Annotation::external_data`]. It links a markup
+/// annotation to a view of a 3D annotation or a projection annotation to a 3D
+/// measurement.
+pub struct ExData<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(ExData: |obj| {
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Type"), Name(b"ExData"));
+    Self { dict }
+});
+
+impl<'a> ExData<'a> {
+    /// Write the `/Subtype` attribute to set the kind of external data.
+    /// Required.
+    pub fn subtype(&mut self, kind: ExDataType) -> &mut Self {
+        self.pair(Name(b"Subtype"), kind.to_name());
+        self
+    }
+
+    /// Write the `/3DA` attribute to reference the 3D annotation the markup
+    /// annotation comments on. Required for the subtype `Markup3D`.
+    pub fn annotation_3d(&mut self, id: Ref) -> &mut Self {
+        self.pair(Name(b"3DA"), id);
+        self
+    }
+
+    /// Write the `/3DV` attribute to reference the 3D view the markup
+    /// annotation comments on. Only permissible for the subtype `Markup3D`.
+    pub fn view_3d(&mut self, id: Ref) -> &mut Self {
+        self.pair(Name(b"3DV"), id);
+        self
+    }
+
+    /// Write the `/MD5` attribute to set a checksum of the 3D artwork the
+    /// markup annotation comments on. Only permissible for the subtype
+    /// `Markup3D`.
+    pub fn md5(&mut self, checksum: Str) -> &mut Self {
+        self.pair(Name(b"MD5"), checksum);
+        self
+    }
+
+    /// Write the `/M3DREF` attribute to reference the [3D
+    /// measurement](Measure3D) that the projection annotation displays.
+    /// Required for the subtype `Measure3D`. PDF 2.0+.
+    pub fn measurement(&mut self, id: Ref) -> &mut Self {
+        self.pair(Name(b"M3DREF"), id);
+        self
+    }
+}
+
+deref!('a, ExData<'a> => Dict<'a>, dict);
+
+/// The kind of external data an annotation links to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ExDataType {
+    /// A markup annotation commenting on a 3D view.
+    Markup3D,
+    /// A 3D measurement displayed by a projection annotation. PDF 2.0+.
+    Measure3D,
+}
+
+impl ExDataType {
+    pub(crate) fn to_name(self) -> Name<'static> {
+        match self {
+            Self::Markup3D => Name(b"Markup3D"),
+            Self::Measure3D => Name(b"3DM"),
+        }
+    }
+}
+
+/// Writer for a _3D measurement dictionary_. PDF 2.0+.
+///
+/// This struct is created by [`Chunk::measure_3d`]. It is referenced from the
+/// [external data](ExData::measurement) of a projection annotation.
+pub struct Measure3D<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(Measure3D: |obj| {
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Type"), Name(b"3DMeasure"));
+    Self { dict }
+});
+
+impl<'a> Measure3D<'a> {
+    /// Write the `/Subtype` attribute to set the kind of measurement.
+    /// Required.
+    pub fn subtype(&mut self, kind: Measure3DType) -> &mut Self {
+        self.pair(Name(b"Subtype"), kind.to_name());
+        self
+    }
+
+    /// Write the `/TRL` attribute to set the name of the measurement as shown
+    /// in the model tree.
+    pub fn label(&mut self, label: TextStr) -> &mut Self {
+        self.pair(Name(b"TRL"), label);
+        self
+    }
+
+    /// Write the `/A1` attribute to set the first anchor point in world
+    /// coordinates.
+    pub fn first_anchor(&mut self, point: [f32; 3]) -> &mut Self {
+        self.insert(Name(b"A1")).array().items(point);
+        self
+    }
+
+    /// Write the `/A2` attribute to set the second anchor point in world
+    /// coordinates. Not permissible for the subtype `Comment`.
+    pub fn second_anchor(&mut self, point: [f32; 3]) -> &mut Self {
+        self.insert(Name(b"A2")).array().items(point);
+        self
+    }
+
+    /// Write the `/TP` attribute to set the position of the measurement text
+    /// in world coordinates.
+    pub fn text_position(&mut self, point: [f32; 3]) -> &mut Self {
+        self.insert(Name(b"TP")).array().items(point);
+        self
+    }
+
+    /// Write the `/TS` attribute to set the size of the measurement text in
+    /// world coordinates.
+    pub fn text_size(&mut self, size: f32) -> &mut Self {
+        self.pair(Name(b"TS"), size);
+        self
+    }
+
+    /// Write the `/C` attribute to set the color of the measurement in the
+    /// DeviceRGB color space.
+    pub fn color(&mut self, r: f32, g: f32, b: f32) -> &mut Self {
+        self.insert(Name(b"C")).array().items([r, g, b]);
+        self
+    }
+
+    /// Write the `/V` attribute to set the measured value. Not permissible for
+    /// the subtype `Comment`.
+    pub fn value(&mut self, value: f32) -> &mut Self {
+        self.pair(Name(b"V"), value);
+        self
+    }
+
+    /// Write the `/U` attribute to set the units of the measured value.
+    pub fn units(&mut self, units: TextStr) -> &mut Self {
+        self.pair(Name(b"U"), units);
+        self
+    }
+
+    /// Write the `/P` attribute to set the number of decimal places shown for
+    /// the measured value.
+    pub fn precision(&mut self, digits: i32) -> &mut Self {
+        self.pair(Name(b"P"), digits);
+        self
+    }
+
+    /// Write the `/UT` attribute to set user-provided text, e.g. the text of
+    /// a 3D comment note.
+    pub fn user_text(&mut self, text: TextStr) -> &mut Self {
+        self.pair(Name(b"UT"), text);
+        self
+    }
+
+    /// Write the `/S` attribute to reference the projection annotation that
+    /// displays the measurement.
+    pub fn annotation(&mut self, id: Ref) -> &mut Self {
+        self.pair(Name(b"S"), id);
+        self
+    }
+}
+
+deref!('a, Measure3D<'a> => Dict<'a>, dict);
+
+/// The kind of a 3D measurement.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Measure3DType {
+    /// The distance between two points.
+    Linear,
+    /// The perpendicular distance between two lines or planes.
+    Perpendicular,
+    /// The angle between two lines.
+    Angular,
+    /// The radius of a circular feature.
+    Radial,
+    /// A comment note attached to a point.
+    Comment,
+}
+
+impl Measure3DType {
+    pub(crate) fn to_name(self) -> Name<'static> {
+        match self {
+            Self::Linear => Name(b"LD3"),
+            Self::Perpendicular => Name(b"PD3"),
+            Self::Angular => Name(b"AD3"),
+            Self::Radial => Name(b"RD3"),
+            Self::Comment => Name(b"3DC"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -771,4 +1310,277 @@ mod tests {
             b"endobj\n\n",
         );
     }
+
+    #[test]
+    fn test_extend_annotations() {
+        let mut c = Chunk::new();
+        let rect = Rect::new(0.0, 0.0, 10.0, 10.0);
+        c.extend_annotations([
+            (Ref::new(1), AnnotationSpec::link(rect, Str(b"https://typst.app"))),
+            (
+                Ref::new(2),
+                AnnotationSpec {
+                    destination: Some(Ref::new(3)),
+                    flags: Some(AnnotationFlags::PRINT),
+                    ..AnnotationSpec::new(AnnotationType::Link, rect)
+                },
+            ),
+        ]);
+        test!(
+            c.as_bytes(),
+            b"1 0 obj",
+            b"<<",
+            b"  /Type /Annot",
+            b"  /Subtype /Link",
+            b"  /Rect [0 0 10 10]",
+            b"  /A <<",
+            b"    /Type /Action",
+            b"    /S /URI",
+            b"    /URI (https://typst.app)",
+            b"  >>",
+            b">>",
+            b"endobj",
+            b"",
+            b"2 0 obj",
+            b"<<",
+            b"  /Type /Annot",
+            b"  /Subtype /Link",
+            b"  /Rect [0 0 10 10]",
+            b"  /F 4",
+            b"  /Dest 3 0 R",
+            b">>",
+            b"endobj\n\n",
+        );
+    }
+
+    #[test]
+    fn test_line_boxes() {
+        test_obj!(
+            |obj| {
+                obj.start::<Annotation>()
+                    .subtype(AnnotationType::Squiggly)
+                    .line_boxes(&[
+                        Rect::new(50.0, 100.0, 200.0, 112.0),
+                        Rect::new(200.0, 84.0, 10.0, 96.0),
+                    ]);
+            },
+            b"<<",
+            b"  /Type /Annot",
+            b"  /Subtype /Squiggly",
+            b"  /Rect [10 84 200 112]",
+            b"  /QuadPoints [50 100 200 100 200 112 50 112 10 84 200 84 200 96 10 96]",
+            b">>",
+        );
+    }
+
+    #[test]
+    fn test_caret() {
+        test_obj!(
+            |obj| {
+                obj.start::<Annotation>()
+                    .subtype(AnnotationType::Caret)
+                    .rect(Rect::new(10.0, 10.0, 20.0, 24.0))
+                    .rect_differences(1.0, 2.0, 1.0, 2.0)
+                    .caret_symbol(CaretSymbol::Paragraph);
+            },
+            b"<<",
+            b"  /Type /Annot",
+            b"  /Subtype /Caret",
+            b"  /Rect [10 10 20 24]",
+            b"  /RD [1 2 1 2]",
+            b"  /Sy /P",
+            b">>",
+        );
+    }
+
+    #[test]
+    fn test_sound() {
+        let mut c = Chunk::new();
+        c.annotation(Ref::new(1))
+            .subtype(AnnotationType::Sound)
+            .icon(AnnotationIcon::Speaker)
+            .sound(Ref::new(2));
+        c.sound(Ref::new(2), &[0x80, 0x7f])
+            .rate(8000.0)
+            .bits_per_sample(8)
+            .encoding(crate::types::SoundEncoding::Raw);
+        test!(
+            c.as_bytes(),
+            b"1 0 obj",
+            b"<<",
+            b"  /Type /Annot",
+            b"  /Subtype /Sound",
+            b"  /Name /Speaker",
+            b"  /Sound 2 0 R",
+            b">>",
+            b"endobj\n",
+            b"2 0 obj",
+            b"<<",
+            b"  /Length 2",
+            b"  /Type /Sound",
+            b"  /R 8000",
+            b"  /B 8",
+            b"  /E /Raw",
+            b">>",
+            b"stream",
+            b"\x80\x7f",
+            b"endstream",
+            b"endobj\n\n",
+        );
+    }
+
+    #[test]
+    fn test_struct_parent() {
+        test_obj!(
+            |obj| {
+                obj.start::<Annotation>()
+                    .subtype(AnnotationType::Link)
+                    .struct_parent(3)
+                    .lang(TextStr("en-US"));
+            },
+            b"<<",
+            b"  /Type /Annot",
+            b"  /Subtype /Link",
+            b"  /StructParent 3",
+            b"  /Lang (en-US)",
+            b">>",
+        );
+    }
+
+    #[test]
+    fn test_projection() {
+        test!(
+            crate::tests::slice(|w| {
+                w.annotation(Ref::new(1))
+                    .subtype(AnnotationType::Projection)
+                    .external_data()
+                    .subtype(ExDataType::Measure3D)
+                    .measurement(Ref::new(2));
+                w.measure_3d(Ref::new(2))
+                    .subtype(Measure3DType::Linear)
+                    .first_anchor([0.0, 0.0, 0.0])
+                    .second_anchor([3.0, 4.0, 0.0])
+                    .value(5.0)
+                    .units(TextStr("mm"))
+                    .annotation(Ref::new(1));
+            }),
+            b"1 0 obj",
+            b"<<",
+            b"  /Type /Annot",
+            b"  /Subtype /Projection",
+            b"  /ExData <<",
+            b"    /Type /ExData",
+            b"    /Subtype /3DM",
+            b"    /M3DREF 2 0 R",
+            b"  >>",
+            b">>",
+            b"endobj\n",
+            b"2 0 obj",
+            b"<<",
+            b"  /Type /3DMeasure",
+            b"  /Subtype /LD3",
+            b"  /A1 [0 0 0]",
+            b"  /A2 [3 4 0]",
+            b"  /V 5",
+            b"  /U (mm)",
+            b"  /S 1 0 R",
+            b">>",
+            b"endobj\n\n",
+        );
+    }
+
+    #[test]
+    fn test_compositing() {
+        test_obj!(
+            |obj| {
+                obj.start::<Annotation>()
+                    .subtype(AnnotationType::Highlight)
+                    .stroking_alpha(0.5)
+                    .non_stroking_alpha(0.25)
+                    .blend_mode(BlendMode::Multiply);
+            },
+            b"<<",
+            b"  /Type /Annot",
+            b"  /Subtype /Highlight",
+            b"  /CA 0.5",
+            b"  /ca 0.25",
+            b"  /BM /Multiply",
+            b">>",
+        );
+    }
+
+    #[test]
+    fn test_quad_points() {
+        test_obj!(
+            |obj| {
+                obj.start::<Annotation>().quad_points([
+                    Rect::new(0.0, 0.0, 2.0, 1.0),
+                    Rect::new(0.0, 1.0, 3.0, 2.0),
+                ]);
+            },
+            b"<<",
+            b"  /Type /Annot",
+            b"  /QuadPoints [0 0 2 0 2 1 0 1 0 1 3 1 3 2 0 2]",
+            b">>",
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "color must have 0, 1, 3 or 4 components (has 2)")]
+    fn test_color_components_count() {
+        crate::tests::slice_obj(|obj| {
+            obj.start::<Annotation>().color_components(&[0.0, 1.0]);
+        });
+    }
+
+    #[test]
+    fn test_converted_colors() {
+        let white = Color::Lab { space: Name(b"L0"), l: 100.0, a: 0.0, b: 0.0 };
+        assert!(white.to_rgb().iter().all(|&c| (c - 1.0).abs() < 0.001));
+        let red = Color::Named {
+            space: Name(b"S0"),
+            tint: 0.5,
+            rgb: [1.0, 0.0, 0.0],
+        };
+        assert_eq!(red.to_cmyk(), [0.0, 0.5, 0.5, 0.0]);
+        test_obj!(
+            |obj| {
+                let mut annot = obj.start::<Annotation>();
+                annot.color(red);
+                annot.interior_color(Color::Indexed {
+                    space: Name(b"I0"),
+                    index: 2,
+                    rgb: [0.0, 0.0, 1.0],
+                });
+            },
+            b"<<",
+            b"  /Type /Annot",
+            b"  /C [1 0.5 0.5]",
+            b"  /IC [0 0 1]",
+            b">>",
+        );
+    }
+
+    #[test]
+    fn test_watermark_fixed_print() {
+        test_obj!(
+            |obj| {
+                let mut annot = obj.start::<Annotation>();
+                annot.subtype(AnnotationType::Watermark);
+                annot
+                    .fixed_print()
+                    .matrix([1.0, 0.0, 0.0, 1.0, 0.0, 0.0])
+                    .horizontal(0.5);
+            },
+            b"<<",
+            b"  /Type /Annot",
+            b"  /Subtype /Watermark",
+            b"  /FixedPrint <<",
+            b"    /Type /FixedPrint",
+            b"    /Matrix [1 0 0 1 0 0]",
+            b"    /H 0.5",
+            b"  >>",
+            b">>",
+        );
+    }
 }
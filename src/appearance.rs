@@ -0,0 +1,396 @@
+use super::*;
+use crate::types::{AnnotationType, BlendMode, ButtonStyle, Color};
+
+/// Write a text markup annotation along with a generated appearance stream.
+pub(crate) fn write_markup<'a>(
+    chunk: &'a mut Chunk,
+    id: Ref,
+    appearance_id: Ref,
+    subtype: AnnotationType,
+    quads: &[Quad],
+    color: Color,
+) -> Annotation<'a> {
+    assert!(!quads.is_empty(), "markup annotation must have at least one quad");
+
+    let highlight = subtype == AnnotationType::Highlight;
+    let mut content = Content::new();
+    if highlight {
+        content.set_parameters(Name(b"GS0"));
+        content.set_fill_device_color(color);
+    } else {
+        content.set_stroke_device_color(color);
+    }
+
+    let mut bbox = quads[0].bbox();
+    for quad in quads {
+        let Rect { x1, y1, x2, y2 } = quad.bbox();
+        let height = y2 - y1;
+        bbox = bbox.union(quad.bbox());
+        match subtype {
+            AnnotationType::Highlight => {
+                content
+                    .move_to(quad.x1, quad.y1)
+                    .line_to(quad.x2, quad.y2)
+                    .line_to(quad.x3, quad.y3)
+                    .line_to(quad.x4, quad.y4)
+                    .close_path()
+                    .fill_nonzero();
+            }
+            AnnotationType::Underline => {
+                let width = height / 16.0;
+                content.set_line_width(width);
+                content.move_to(x1, y1 + width).line_to(x2, y1 + width).stroke();
+            }
+            AnnotationType::StrikeOut => {
+                let y = y1 + height / 2.0;
+                content.set_line_width(height / 16.0);
+                content.move_to(x1, y).line_to(x2, y).stroke();
+            }
+            AnnotationType::Squiggly => {
+                let width = height / 24.0;
+                let step = height / 8.0;
+                content.set_line_width(width);
+                content.move_to(x1, y1 + width);
+                let mut x = x1;
+                let mut up = true;
+                while x < x2 {
+                    x = (x + step).min(x2);
+                    content.line_to(x, y1 + width + if up { step } else { 0.0 });
+                    up = !up;
+                }
+                content.stroke();
+            }
+            _ => panic!("{subtype:?} is not a text markup annotation"),
+        }
+    }
+
+    let content = content.finish();
+    let mut form = chunk.form_xobject(appearance_id, &content);
+    form.bbox(bbox);
+    if highlight {
+        form.resources()
+            .ext_g_states()
+            .insert(Name(b"GS0"))
+            .dict()
+            .pair(Name(b"BM"), BlendMode::Multiply.to_name());
+    }
+    form.finish();
+
+    let mut annotation = chunk.annotation(id);
+    annotation.subtype(subtype);
+    annotation.rect(bbox);
+    annotation.quad_points(quads.iter().copied());
+    annotation.color(color);
+    annotation.appearance().normal().stream(appearance_id);
+    annotation
+}
+
+/// Write a square or circle annotation along with a generated appearance
+/// stream.
+pub(crate) fn write_shape<'a>(
+    chunk: &'a mut Chunk,
+    id: Ref,
+    appearance_id: Ref,
+    subtype: AnnotationType,
+    rect: Rect,
+    color: Color,
+    width: f32,
+) -> Annotation<'a> {
+    let rect = rect.union(rect);
+    let Rect { x1, y1, x2, y2 } = rect.expand(-width / 2.0);
+
+    let mut content = Content::new();
+    content.set_line_width(width);
+    content.set_stroke_device_color(color);
+    match subtype {
+        AnnotationType::Square => {
+            content.rect(x1, y1, x2 - x1, y2 - y1);
+        }
+        AnnotationType::Circle => {
+            let (cx, cy) = ((x1 + x2) / 2.0, (y1 + y2) / 2.0);
+            let (rx, ry) = ((x2 - x1) / 2.0, (y2 - y1) / 2.0);
+            content.ellipse(cx, cy, rx, ry);
+        }
+        _ => panic!("{subtype:?} is not a square or circle annotation"),
+    }
+    content.stroke();
+
+    chunk.form_xobject(appearance_id, &content.finish()).bbox(rect);
+
+    let mut annotation = chunk.annotation(id);
+    annotation.subtype(subtype);
+    annotation.rect(rect);
+    annotation.color(color);
+    annotation.border_style().width(width);
+    annotation.appearance().normal().stream(appearance_id);
+    annotation
+}
+
+/// Write the on and off appearance streams of a check box or radio button.
+pub(crate) fn write_button(
+    chunk: &mut Chunk,
+    on_id: Ref,
+    off_id: Ref,
+    style: ButtonStyle,
+    rect: Rect,
+    color: Color,
+) {
+    let rect = rect.union(rect);
+    let (width, height) = (rect.x2 - rect.x1, rect.y2 - rect.y1);
+    let bbox = Rect::new(0.0, 0.0, width, height);
+    let size = width.min(height);
+
+    let mut content = Content::new();
+    content.set_fill_device_color(color);
+    match style {
+        ButtonStyle::Check => {
+            // The check mark is 0.756 em wide and about 0.7 em high.
+            let font_size = size * 0.8;
+            let x = (width - 0.756 * font_size) / 2.0;
+            let y = (height - 0.7 * font_size) / 2.0;
+            content.begin_text();
+            content.set_font(Name(b"ZaDb"), font_size);
+            content.next_line(x, y);
+            content.show(Str(b"4"));
+            content.end_text();
+        }
+        ButtonStyle::Circle => {
+            let radius = size / 4.0;
+            content.ellipse(width / 2.0, height / 2.0, radius, radius);
+            content.fill_nonzero();
+        }
+    }
+
+    let content = content.finish();
+    let mut form = chunk.form_xobject(on_id, &content);
+    form.bbox(bbox);
+    if style == ButtonStyle::Check {
+        form.resources()
+            .fonts()
+            .insert(Name(b"ZaDb"))
+            .start::<Type1Font>()
+            .base_font(Name(b"ZapfDingbats"));
+    }
+    form.finish();
+
+    chunk.form_xobject(off_id, &[]).bbox(bbox);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markup_appearance() {
+        let mut c = Chunk::new();
+        let quad = Rect::new(10.0, 10.0, 50.0, 26.0).to_quad();
+        c.markup_annotation(
+            Ref::new(1),
+            Ref::new(2),
+            AnnotationType::Highlight,
+            &[quad],
+            Color::Rgb(1.0, 1.0, 0.0),
+        );
+        c.markup_annotation(
+            Ref::new(3),
+            Ref::new(4),
+            AnnotationType::Underline,
+            &[quad],
+            Color::Gray(0.0),
+        );
+        test!(
+            c.as_bytes(),
+            b"2 0 obj",
+            b"<<",
+            b"  /Length 52",
+            b"  /Type /XObject",
+            b"  /Subtype /Form",
+            b"  /BBox [10 10 50 26]",
+            b"  /Resources <<",
+            b"    /ExtGState <<",
+            b"      /GS0 <<",
+            b"        /BM /Multiply",
+            b"      >>",
+            b"    >>",
+            b"  >>",
+            b">>",
+            b"stream",
+            b"/GS0 gs\n1 1 0 rg\n10 10 m\n50 10 l\n50 26 l\n10 26 l\nh\nf",
+            b"endstream",
+            b"endobj\n",
+            b"1 0 obj",
+            b"<<",
+            b"  /Type /Annot",
+            b"  /Subtype /Highlight",
+            b"  /Rect [10 10 50 26]",
+            b"  /QuadPoints [10 10 50 10 50 26 10 26]",
+            b"  /C [1 1 0]",
+            b"  /AP <<",
+            b"    /N 2 0 R",
+            b"  >>",
+            b">>",
+            b"endobj\n",
+            b"4 0 obj",
+            b"<<",
+            b"  /Length 25",
+            b"  /Type /XObject",
+            b"  /Subtype /Form",
+            b"  /BBox [10 10 50 26]",
+            b">>",
+            b"stream",
+            b"0 G\n1 w\n10 11 m\n50 11 l\nS",
+            b"endstream",
+            b"endobj\n",
+            b"3 0 obj",
+            b"<<",
+            b"  /Type /Annot",
+            b"  /Subtype /Underline",
+            b"  /Rect [10 10 50 26]",
+            b"  /QuadPoints [10 10 50 10 50 26 10 26]",
+            b"  /C [0]",
+            b"  /AP <<",
+            b"    /N 4 0 R",
+            b"  >>",
+            b">>",
+            b"endobj\n\n",
+        );
+    }
+
+    #[test]
+    fn test_shape_appearance() {
+        let mut c = Chunk::new();
+        c.shape_annotation(
+            Ref::new(1),
+            Ref::new(2),
+            AnnotationType::Square,
+            Rect::new(30.0, 30.0, 10.0, 10.0),
+            Color::Gray(0.5),
+            2.0,
+        );
+        test!(
+            c.as_bytes(),
+            b"2 0 obj",
+            b"<<",
+            b"  /Length 26",
+            b"  /Type /XObject",
+            b"  /Subtype /Form",
+            b"  /BBox [10 10 30 30]",
+            b">>",
+            b"stream",
+            b"2 w\n0.5 G\n11 11 18 18 re\nS",
+            b"endstream",
+            b"endobj\n",
+            b"1 0 obj",
+            b"<<",
+            b"  /Type /Annot",
+            b"  /Subtype /Square",
+            b"  /Rect [10 10 30 30]",
+            b"  /C [0.5]",
+            b"  /BS <<",
+            b"    /Type /Border",
+            b"    /W 2",
+            b"  >>",
+            b"  /AP <<",
+            b"    /N 2 0 R",
+            b"  >>",
+            b">>",
+            b"endobj\n\n",
+        );
+    }
+
+    #[test]
+    fn test_button_appearances() {
+        let mut c = Chunk::new();
+        c.button_appearances(
+            Ref::new(1),
+            Ref::new(2),
+            ButtonStyle::Check,
+            Rect::new(0.0, 0.0, 20.0, 20.0),
+            Color::Gray(0.0),
+        );
+        c.button_appearances(
+            Ref::new(3),
+            Ref::new(4),
+            ButtonStyle::Circle,
+            Rect::new(0.0, 0.0, 8.0, 12.0),
+            Color::Gray(0.0),
+        );
+        test!(
+            c.as_bytes(),
+            b"1 0 obj",
+            b"<<",
+            b"  /Length 45",
+            b"  /Type /XObject",
+            b"  /Subtype /Form",
+            b"  /BBox [0 0 20 20]",
+            b"  /Resources <<",
+            b"    /Font <<",
+            b"      /ZaDb <<",
+            b"        /Type /Font",
+            b"        /Subtype /Type1",
+            b"        /BaseFont /ZapfDingbats",
+            b"      >>",
+            b"    >>",
+            b"  >>",
+            b">>",
+            b"stream",
+            b"0 g\nBT\n/ZaDb 16 Tf\n3.9520001 4.4 Td\n(4) Tj\nET",
+            b"endstream",
+            b"endobj\n",
+            b"2 0 obj",
+            b"<<",
+            b"  /Length 0",
+            b"  /Type /XObject",
+            b"  /Subtype /Form",
+            b"  /BBox [0 0 20 20]",
+            b">>",
+            b"stream",
+            b"",
+            b"endstream",
+            b"endobj\n",
+            b"3 0 obj",
+            b"<<",
+            b"  /Length 133",
+            b"  /Type /XObject",
+            b"  /Subtype /Form",
+            b"  /BBox [0 0 8 12]",
+            b">>",
+            b"stream",
+            b"0 g",
+            b"6 6 m",
+            b"6 7.1045694 5.1045694 8 4 8 c",
+            b"2.8954306 8 2 7.1045694 2 6 c",
+            b"2 4.8954306 2.8954306 4 4 4 c",
+            b"5.1045694 4 6 4.8954306 6 6 c",
+            b"h",
+            b"f",
+            b"endstream",
+            b"endobj\n",
+            b"4 0 obj",
+            b"<<",
+            b"  /Length 0",
+            b"  /Type /XObject",
+            b"  /Subtype /Form",
+            b"  /BBox [0 0 8 12]",
+            b">>",
+            b"stream",
+            b"",
+            b"endstream",
+            b"endobj\n\n",
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Link is not a text markup annotation")]
+    fn test_markup_appearance_wrong_type() {
+        let quad = Rect::new(0.0, 0.0, 1.0, 1.0).to_quad();
+        let mut c = Chunk::new();
+        c.markup_annotation(
+            Ref::new(1),
+            Ref::new(2),
+            AnnotationType::Link,
+            &[quad],
+            Color::Gray(0.0),
+        );
+    }
+}
@@ -214,6 +214,12 @@ impl WritingMode {
             Self::TtbRtl => Name(b"TbRl"),
         }
     }
+
+    /// Whether glyphs are laid out from top to bottom, as selected by the
+    /// `/WMode` of character maps.
+    pub(crate) fn is_vertical(self) -> bool {
+        self == Self::TtbRtl
+    }
 }
 
 /// Layout border style.
@@ -795,6 +801,13 @@ impl<'a> TableAttributes<'a> {
         self.dict.pair(Name(b"Summary"), summary);
         self
     }
+
+    /// Write the `/Short` attribute to set a short form of a table header
+    /// cell's content, e.g. for screen readers. PDF 2.0+.
+    pub fn short(&mut self, short: TextStr) -> &mut Self {
+        self.dict.pair(Name(b"Short"), short);
+        self
+    }
 }
 
 deref!('a, TableAttributes<'a> => Dict<'a>, dict);
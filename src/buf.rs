@@ -1,3 +1,7 @@
+use core::fmt::{self, Write};
+
+use alloc::vec::Vec;
+
 use super::Primitive;
 
 /// Additional methods for byte buffers.
@@ -9,6 +13,7 @@ pub trait BufExt {
     fn push_hex(&mut self, value: u8);
     fn push_hex_u16(&mut self, value: u16);
     fn push_octal(&mut self, value: u8);
+    fn push_fmt(&mut self, args: fmt::Arguments);
 }
 
 impl BufExt for Vec<u8> {
@@ -36,13 +41,13 @@ impl BufExt for Vec<u8> {
     /// Like `push_float`, but forces the decimal point.
     #[inline]
     fn push_decimal(&mut self, value: f32) {
-        if value == 0.0 || (value.abs() > 1e-6 && value.abs() < 1e12) {
+        let abs = if value < 0.0 { -value } else { value };
+        if value == 0.0 || (abs > 1e-6 && abs < 1e12) {
             self.extend(ryu::Buffer::new().format(value).as_bytes());
         } else {
             #[inline(never)]
             fn write_extreme(buf: &mut Vec<u8>, value: f32) {
-                use std::io::Write;
-                write!(buf, "{}", value).unwrap();
+                buf.push_fmt(format_args!("{}", value));
             }
 
             write_extreme(self, value);
@@ -79,4 +84,18 @@ impl BufExt for Vec<u8> {
         self.push(octal((value >> 3) & 7));
         self.push(octal(value & 7));
     }
+
+    #[inline]
+    fn push_fmt(&mut self, args: fmt::Arguments) {
+        struct Adapter<'a>(&'a mut Vec<u8>);
+
+        impl Write for Adapter<'_> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0.extend(s.as_bytes());
+                Ok(())
+            }
+        }
+
+        Adapter(self).write_fmt(args).unwrap();
+    }
 }
@@ -0,0 +1,324 @@
+use super::*;
+
+/// Writer for a _web capture information dictionary_. PDF 1.3+.
+///
+/// This struct is created by [`Catalog::spider_info`]. It records how the
+/// document was created by capturing content from the web.
+pub struct WebCaptureInfo<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(WebCaptureInfo: |obj| Self { dict: obj.dict() });
+
+impl<'a> WebCaptureInfo<'a> {
+    /// Write the `/V` attribute to set the version of the web capture
+    /// extension. Required.
+    ///
+    /// The only version defined in PDF 1.7 and PDF 2.0 is `1.0`.
+    pub fn version(&mut self, version: f32) -> &mut Self {
+        self.pair(Name(b"V"), version);
+        self
+    }
+
+    /// Write the `/C` attribute to reference the [commands](WebCaptureCommand)
+    /// that were executed to capture the content.
+    pub fn commands(&mut self, commands: impl IntoIterator<Item = Ref>) -> &mut Self {
+        self.insert(Name(b"C")).array().items(commands);
+        self
+    }
+}
+
+deref!('a, WebCaptureInfo<'a> => Dict<'a>, dict);
+
+/// Writer for a _web capture command dictionary_. PDF 1.3+.
+///
+/// This struct is created by [`Chunk::web_capture_command`].
+pub struct WebCaptureCommand<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(WebCaptureCommand: |obj| Self { dict: obj.dict() });
+
+impl<'a> WebCaptureCommand<'a> {
+    /// Write the `/URL` attribute to set the initial URL from which content
+    /// was captured. Required.
+    pub fn url(&mut self, url: Str) -> &mut Self {
+        self.pair(Name(b"URL"), url);
+        self
+    }
+
+    /// Write the `/L` attribute to set how many levels of links were followed
+    /// from the initial URL.
+    ///
+    /// _Default value_: 1.
+    pub fn levels(&mut self, levels: i32) -> &mut Self {
+        self.pair(Name(b"L"), levels);
+        self
+    }
+
+    /// Write the `/F` attribute to set flags controlling how links were
+    /// followed.
+    pub fn flags(&mut self, flags: WebCaptureFlags) -> &mut Self {
+        self.pair(Name(b"F"), flags.bits() as i32);
+        self
+    }
+
+    /// Write the `/P` attribute to set the data that was posted to the URL.
+    pub fn post_data(&mut self, data: Str) -> &mut Self {
+        self.pair(Name(b"P"), data);
+        self
+    }
+
+    /// Write the `/CT` attribute to set the content type of the
+    /// [posted data](Self::post_data).
+    ///
+    /// _Default value_: `application/x-www-form-urlencoded`.
+    pub fn content_type(&mut self, content_type: Str) -> &mut Self {
+        self.pair(Name(b"CT"), content_type);
+        self
+    }
+
+    /// Write the `/H` attribute to set additional HTTP request headers, with
+    /// each header terminated by a carriage return and line feed.
+    pub fn headers(&mut self, headers: Str) -> &mut Self {
+        self.pair(Name(b"H"), headers);
+        self
+    }
+}
+
+deref!('a, WebCaptureCommand<'a> => Dict<'a>, dict);
+
+bitflags::bitflags! {
+    /// Flags for a [web capture command](WebCaptureCommand).
+    pub struct WebCaptureFlags: u32 {
+        /// Only follow links to the same site as the initial URL.
+        const SAME_SITE = 1 << 1;
+        /// Only follow links to the same path as the initial URL.
+        const SAME_PATH = 1 << 2;
+        /// Submit the [posted data](WebCaptureCommand::post_data) with the
+        /// initial request.
+        const SUBMIT = 1 << 3;
+    }
+}
+
+/// Writer for a _web capture content set dictionary_. PDF 1.3+.
+///
+/// This struct is created by [`Chunk::content_set`]. Content sets are named in
+/// the [`/IDS`](Names::capture_ids) and [`/URLS`](Names::capture_urls) name
+/// trees.
+pub struct ContentSet<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(ContentSet: |obj| {
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Type"), Name(b"SpiderContentSet"));
+    Self { dict }
+});
+
+impl<'a> ContentSet<'a> {
+    /// Write the `/S` attribute to set whether the set contains pages or
+    /// images. Required.
+    pub fn subtype(&mut self, subtype: ContentSetType) -> &mut Self {
+        self.pair(Name(b"S"), subtype.to_name());
+        self
+    }
+
+    /// Write the `/ID` attribute to set the digital identifier of the content,
+    /// an MD5 hash of the source data. Required.
+    pub fn id(&mut self, id: Str) -> &mut Self {
+        self.pair(Name(b"ID"), id);
+        self
+    }
+
+    /// Write the `/O` attribute to reference the pages or image XObjects
+    /// that belong to the set. Required.
+    pub fn objects(&mut self, objects: impl IntoIterator<Item = Ref>) -> &mut Self {
+        self.insert(Name(b"O")).array().items(objects);
+        self
+    }
+
+    /// Start writing the `/SI` dictionary to describe where the content came
+    /// from. Required.
+    pub fn source_info(&mut self) -> SourceInfo<'_> {
+        self.insert(Name(b"SI")).start()
+    }
+
+    /// Start writing the `/SI` attribute as an array, if the content was
+    /// retrieved from multiple sources. Required.
+    pub fn source_infos(&mut self) -> TypedArray<'_, SourceInfo<'_>> {
+        self.insert(Name(b"SI")).array().typed()
+    }
+
+    /// Write the `/CT` attribute to set the content type of the source data.
+    pub fn content_type(&mut self, content_type: Str) -> &mut Self {
+        self.pair(Name(b"CT"), content_type);
+        self
+    }
+
+    /// Write the `/TS` attribute to set when the content was converted.
+    pub fn timestamp(&mut self, date: Date) -> &mut Self {
+        self.pair(Name(b"TS"), date);
+        self
+    }
+
+    /// Write the `/T` attribute to set the title of the page set. Only
+    /// permissible for page sets.
+    pub fn title(&mut self, title: TextStr) -> &mut Self {
+        self.pair(Name(b"T"), title);
+        self
+    }
+
+    /// Write the `/TID` attribute to set the identifier of the source's text
+    /// only, to detect changes that do not affect the text. Only permissible
+    /// for page sets.
+    pub fn text_id(&mut self, id: Str) -> &mut Self {
+        self.pair(Name(b"TID"), id);
+        self
+    }
+
+    /// Write the `/R` attribute to set how many times each image in the
+    /// [objects](Self::objects) is referenced by the captured pages. Required
+    /// for and only permissible for image sets.
+    pub fn reference_counts(
+        &mut self,
+        counts: impl IntoIterator<Item = i32>,
+    ) -> &mut Self {
+        self.insert(Name(b"R")).array().items(counts);
+        self
+    }
+}
+
+deref!('a, ContentSet<'a> => Dict<'a>, dict);
+
+/// What a [content set](ContentSet) contains.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ContentSetType {
+    /// Pages converted from the same source.
+    Page,
+    /// Image XObjects converted from the same source.
+    Image,
+}
+
+impl ContentSetType {
+    pub(crate) fn to_name(self) -> Name<'static> {
+        match self {
+            Self::Page => Name(b"SPS"),
+            Self::Image => Name(b"SIS"),
+        }
+    }
+}
+
+/// Writer for a _web capture source information dictionary_. PDF 1.3+.
+///
+/// This struct is created by [`ContentSet::source_info`] and
+/// [`ContentSet::source_infos`].
+pub struct SourceInfo<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(SourceInfo: |obj| Self { dict: obj.dict() });
+
+impl<'a> SourceInfo<'a> {
+    /// Write the `/AU` attribute to set the URL of the source. Required.
+    pub fn url(&mut self, url: Str) -> &mut Self {
+        self.pair(Name(b"AU"), url);
+        self
+    }
+
+    /// Write the `/TS` attribute to set when the source was last modified.
+    pub fn timestamp(&mut self, date: Date) -> &mut Self {
+        self.pair(Name(b"TS"), date);
+        self
+    }
+
+    /// Write the `/E` attribute to set when the source expires.
+    pub fn expiration(&mut self, date: Date) -> &mut Self {
+        self.pair(Name(b"E"), date);
+        self
+    }
+
+    /// Write the `/S` attribute to set how the source was requested.
+    ///
+    /// _Default value_: `None`.
+    pub fn submission(&mut self, submission: SubmissionType) -> &mut Self {
+        self.pair(Name(b"S"), submission.to_int());
+        self
+    }
+
+    /// Write the `/C` attribute to reference the [command](WebCaptureCommand)
+    /// that retrieved the source.
+    pub fn command(&mut self, id: Ref) -> &mut Self {
+        self.pair(Name(b"C"), id);
+        self
+    }
+}
+
+deref!('a, SourceInfo<'a> => Dict<'a>, dict);
+
+/// How a web capture [source](SourceInfo) was requested.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum SubmissionType {
+    /// Not through a form.
+    None,
+    /// Through a form submitted with HTTP `GET`.
+    Get,
+    /// Through a form submitted with HTTP `POST`.
+    Post,
+}
+
+impl SubmissionType {
+    pub(crate) fn to_int(self) -> i32 {
+        match self {
+            Self::None => 0,
+            Self::Get => 1,
+            Self::Post => 2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_web_capture() {
+        let mut c = Chunk::new();
+        c.web_capture_command(Ref::new(1))
+            .url(Str(b"https://example.com"))
+            .levels(2)
+            .flags(WebCaptureFlags::SAME_SITE);
+        let mut set = c.content_set(Ref::new(2));
+        set.subtype(ContentSetType::Page)
+            .id(Str(b"0123456789abcdef"))
+            .objects([Ref::new(3)]);
+        set.source_info()
+            .url(Str(b"https://example.com"))
+            .submission(SubmissionType::None)
+            .command(Ref::new(1));
+        set.finish();
+        test!(
+            c.as_bytes(),
+            b"1 0 obj",
+            b"<<",
+            b"  /URL (https://example.com)",
+            b"  /L 2",
+            b"  /F 2",
+            b">>",
+            b"endobj\n",
+            b"2 0 obj",
+            b"<<",
+            b"  /Type /SpiderContentSet",
+            b"  /S /SPS",
+            b"  /ID (0123456789abcdef)",
+            b"  /O [3 0 R]",
+            b"  /SI <<",
+            b"    /AU (https://example.com)",
+            b"    /S 0",
+            b"    /C 1 0 R",
+            b"  >>",
+            b">>",
+            b"endobj\n\n",
+        );
+    }
+}
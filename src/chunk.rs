@@ -1,5 +1,12 @@
 use super::*;
 
+#[cfg(feature = "std")]
+use std::io::{self, Read};
+
+use crate::types::{
+    ActionType, AnnotationType, ButtonStyle, Color, StandardFont, ThresholdHalftoneType,
+};
+
 /// A builder for a collection of indirect PDF objects.
 ///
 /// This type holds written top-level indirect PDF objects. Typically, you won't
@@ -13,6 +20,7 @@ use super::*;
 pub struct Chunk {
     pub(crate) buf: Vec<u8>,
     pub(crate) offsets: Vec<(Ref, usize)>,
+    pub(crate) observer: Option<Observing>,
 }
 
 impl Chunk {
@@ -24,7 +32,11 @@ impl Chunk {
 
     /// Create a new chunk with the specified initial capacity.
     pub fn with_capacity(capacity: usize) -> Self {
-        Self { buf: Vec::with_capacity(capacity), offsets: vec![] }
+        Self {
+            buf: Vec::with_capacity(capacity),
+            offsets: vec![],
+            observer: None,
+        }
     }
 
     /// The number of bytes that were written so far.
@@ -34,17 +46,103 @@ impl Chunk {
         self.buf.len()
     }
 
+    /// The number of bytes the chunk can hold without reallocating.
+    ///
+    /// Together with [`len`](Self::len), this can be used to monitor the
+    /// growth of the buffer.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    /// Reserve space for at least `additional` more bytes.
+    pub fn reserve(&mut self, additional: usize) {
+        self.buf.reserve(additional);
+    }
+
+    /// Reserve space for at least `n` more indirect objects in the table of
+    /// object offsets.
+    ///
+    /// This only avoids reallocations when writing a large number of small
+    /// objects. Use [`reserve`](Self::reserve) for the bytes of the objects.
+    pub fn expected_objects(&mut self, n: usize) {
+        self.offsets.reserve(n);
+    }
+
     /// The bytes already written so far.
     pub fn as_bytes(&self) -> &[u8] {
         self.buf.as_slice()
     }
 
+    /// Compute size statistics for the objects in this chunk.
+    ///
+    /// This lists the size of each object and can summarize the stream bytes
+    /// per filter and the number of objects per type.
+    pub fn stats(&self) -> Stats {
+        Stats::new(self)
+    }
+
+    /// Serialize the annotations in this chunk as an XFDF file.
+    ///
+    /// The `pages` are the ids of the pages in document order. Annotations
+    /// are assigned to pages through the pages' `/Annots` arrays if they are
+    /// part of this chunk and otherwise through the annotations' `/P` entry.
+    /// Widget and popup annotations are skipped. Requires the `xfdf` feature.
+    #[cfg(feature = "xfdf")]
+    pub fn xfdf(&self, pages: &[Ref]) -> String {
+        xfdf::write_xfdf(self, pages)
+    }
+
+    /// Write a comment between objects.
+    ///
+    /// Each line of the text is written as a separate `%` comment line. This
+    /// is useful for labeling objects or adding debug information to make
+    /// generated files easier to inspect. Comments are ignored by PDF readers
+    /// and are dropped when [renumbering](Self::renumber) the chunk.
+    pub fn comment(&mut self, text: &str) {
+        for line in text.lines().flat_map(|line| line.split('\r')) {
+            self.buf.extend(b"% ");
+            self.buf.extend(line.as_bytes());
+            self.buf.push(b'\n');
+        }
+    }
+
+    /// Register an observer that is notified about every indirect object
+    /// written into this chunk from now on, including objects added through
+    /// [`extend`](Self::extend). Replaces any previous observer.
+    pub fn set_observer(&mut self, observer: impl Observer + 'static) {
+        self.take_observer();
+        self.observer = Some(Observing::new(Box::new(observer)));
+    }
+
+    /// Remove the observer, after reporting the last object as finished.
+    pub fn take_observer(&mut self) -> Option<Box<dyn Observer>> {
+        let mut observing = self.observer.take()?;
+        observing.finish_current(&self.buf, self.buf.len());
+        Some(observing.into_inner())
+    }
+
     /// Add all objects from another chunk to this one.
     pub fn extend(&mut self, other: &Chunk) {
         let base = self.len();
         self.buf.extend_from_slice(&other.buf);
-        self.offsets
-            .extend(other.offsets.iter().map(|&(id, offset)| (id, base + offset)));
+        if self.observer.is_none() {
+            self.offsets
+                .extend(other.offsets.iter().map(|&(id, offset)| (id, base + offset)));
+            return;
+        }
+
+        for &(id, offset) in &other.offsets {
+            self.record(id, base + offset);
+        }
+    }
+
+    /// Record that an indirect object starts at the given offset.
+    pub(crate) fn record(&mut self, id: Ref, offset: usize) {
+        self.offsets.push((id, offset));
+        if let Some(observing) = &mut self.observer {
+            observing.start(&self.buf, id, offset);
+        }
     }
 
     /// Renumbers the IDs of indirect objects and all indirect references in the
@@ -82,6 +180,10 @@ impl Chunk {
     /// });
     /// ```
     ///
+    /// Only object numbers are renumbered: The closure receives references
+    /// with generation zero and the generation numbers in the chunk are kept
+    /// as they are.
+    ///
     /// If a chunk references indirect objects that are not defined within it,
     /// the closure is still called with those references. Allocating new IDs
     /// for them will probably not make sense, so it's up to you to either not
@@ -111,10 +213,34 @@ impl Chunk {
 impl Chunk {
     /// Start writing an indirectly referenceable object.
     pub fn indirect(&mut self, id: Ref) -> Obj<'_> {
-        self.offsets.push((id, self.buf.len()));
+        self.record(id, self.buf.len());
         Obj::indirect(&mut self.buf, id)
     }
 
+    /// Start writing an indirectly referenceable array.
+    ///
+    /// This is useful for arrays that are shared between multiple objects,
+    /// like the widths of a font or a destination.
+    pub fn indirect_array(&mut self, id: Ref) -> Array<'_> {
+        self.indirect(id).array()
+    }
+
+    /// Start writing an indirectly referenceable array with items of type `T`.
+    pub fn indirect_typed_array<T>(&mut self, id: Ref) -> TypedArray<'_, T> {
+        self.indirect_array(id).typed()
+    }
+
+    /// Start writing an indirectly referenceable dictionary.
+    pub fn indirect_dict(&mut self, id: Ref) -> Dict<'_> {
+        self.indirect(id).dict()
+    }
+
+    /// Start writing an indirectly referenceable dictionary with values of
+    /// type `T`.
+    pub fn indirect_typed_dict<T>(&mut self, id: Ref) -> TypedDict<'_, T> {
+        self.indirect_dict(id).typed()
+    }
+
     /// Start writing an indirectly referenceable stream.
     ///
     /// The stream data and the `/Length` field are written automatically. You
@@ -167,6 +293,51 @@ impl Chunk {
     pub fn stream<'a>(&'a mut self, id: Ref, data: &'a [u8]) -> Stream<'a> {
         Stream::start(self.indirect(id), data)
     }
+
+    /// Start writing an indirectly referenceable stream with data from a
+    /// reader.
+    ///
+    /// The data is read in pieces directly into the chunk's buffer, so you
+    /// don't need to hold the whole stream payload in memory. The `len_hint`
+    /// is used to reserve space upfront and does not need to be exact.
+    ///
+    /// ```
+    /// use pdf_writer::{Pdf, Ref};
+    ///
+    /// # fn main() -> std::io::Result<()> {
+    /// let file = std::fs::File::open("README.md")?;
+    /// let len = file.metadata()?.len() as usize;
+    ///
+    /// let mut pdf = Pdf::new();
+    /// pdf.stream_from_reader(Ref::new(1), file, len)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// If reading fails, nothing is written and the error is returned.
+    ///
+    /// Panics if the stream length exceeds `i32::MAX`. Requires the `std`
+    /// feature.
+    #[cfg(feature = "std")]
+    pub fn stream_from_reader<R: Read>(
+        &mut self,
+        id: Ref,
+        mut reader: R,
+        len_hint: usize,
+    ) -> io::Result<Stream<'_>> {
+        let start = self.buf.len();
+        self.buf.reserve(len_hint);
+        let len = match reader.read_to_end(&mut self.buf) {
+            Ok(len) => len,
+            Err(err) => {
+                self.buf.truncate(start);
+                return Err(err);
+            }
+        };
+
+        self.record(id, start);
+        Ok(Stream::start_preloaded(Obj::indirect(&mut self.buf, id), start, len))
+    }
 }
 
 /// Document structure.
@@ -181,6 +352,16 @@ impl Chunk {
         self.indirect(id).start()
     }
 
+    /// Start writing a template page. PDF 1.3+.
+    ///
+    /// Template pages are invisible pages outside of the page tree that must be
+    /// named in the [`/Templates`](Names::templates) name tree. Interactive
+    /// forms can spawn visible copies of them, e.g. through JavaScript. A
+    /// template page has no `/Parent`.
+    pub fn template_page(&mut self, id: Ref) -> Page<'_> {
+        Page::start_template(self.indirect(id))
+    }
+
     /// Start writing an outline.
     pub fn outline(&mut self, id: Ref) -> Outline<'_> {
         self.indirect(id).start()
@@ -191,12 +372,62 @@ impl Chunk {
         self.indirect(id).start()
     }
 
-    /// Start writing a destination for use in a name tree.
+    /// Write a complete document outline from a tree of table of contents
+    /// entries.
+    ///
+    /// The outline dictionary is written with the given `id`, which should be
+    /// referenced through [`Catalog::outlines`]. The ids for the outline items
+    /// are taken from `alloc`. The links between the items, their counts and
+    /// their destinations are all filled in automatically.
+    pub fn toc(&mut self, id: Ref, alloc: &mut Ref, entries: &[TocEntry]) {
+        crate::toc::write_toc(self, id, alloc, entries);
+    }
+
+    /// Write the structure elements for a tagged table.
+    ///
+    /// The `Table` element is written with the given `id` and `parent`. The
+    /// ids for the `TR`, `TH` and `TD` elements are taken from `alloc`. Spans,
+    /// header scopes and the `/Headers` references of all cells are filled in
+    /// automatically.
+    ///
+    /// Returns the `/ID`s of the header cells along with their references,
+    /// sorted for insertion into the [ID tree](StructTreeRoot::id_tree).
+    pub fn tag_table(
+        &mut self,
+        id: Ref,
+        parent: Ref,
+        alloc: &mut Ref,
+        table: &TableTagger,
+    ) -> Vec<(String, Ref)> {
+        crate::table::write_table(self, id, parent, alloc, table)
+    }
+
+    /// Start writing a destination as its own indirect object.
+    ///
+    /// Such a destination can be the value of a [name
+    /// tree](Names::destinations) entry or be shared between outline items and
+    /// actions through [`OutlineItem::dest_ref`] and
+    /// [`Action::destination_ref`].
     pub fn destination(&mut self, id: Ref) -> Destination<'_> {
         self.indirect(id).start()
     }
 
     /// Start writing a named destination dictionary.
+    ///
+    /// This is the pre-PDF 1.2 way of defining named destinations and is
+    /// referenced through [`Catalog::destinations`]. Newer files should prefer
+    /// the [`Names::destinations`] name tree, but some consumers only read this
+    /// dictionary.
+    ///
+    /// ```
+    /// # use pdf_writer::{Pdf, Name, Ref};
+    /// let mut pdf = Pdf::new();
+    /// pdf.catalog(Ref::new(1)).destinations(Ref::new(2));
+    /// pdf.destinations(Ref::new(2))
+    ///     .insert(Name(b"intro"))
+    ///     .page(Ref::new(3))
+    ///     .xyz(0.0, 842.0, None);
+    /// ```
     pub fn destinations(&mut self, id: Ref) -> TypedDict<'_, Destination> {
         self.indirect(id).dict().typed()
     }
@@ -236,6 +467,71 @@ impl Chunk {
         ImageXObject::start(self.stream(id, samples))
     }
 
+    /// Start writing a page thumbnail image stream.
+    ///
+    /// This is an image XObject whose color space is restricted to the ones
+    /// allowed for thumbnails. It is referenced through [`Page::thumbnail`].
+    pub fn thumbnail<'a>(&'a mut self, id: Ref, samples: &'a [u8]) -> Thumbnail<'a> {
+        Thumbnail::start(self.stream(id, samples))
+    }
+
+    /// Start writing an RGB image XObject together with a grayscale soft mask
+    /// holding its alpha channel.
+    ///
+    /// The `rgba` samples are split into their color and alpha components,
+    /// which are written uncompressed with 8 bits per component. The mask is
+    /// written as an image XObject with the id `mask_id`. The returned image
+    /// already has its `/Width`, `/Height`, `/ColorSpace`,
+    /// `/BitsPerComponent` and `/SMask` entries set.
+    ///
+    /// If you want to compress the samples, split the channels yourself and
+    /// write both images with [`image_xobject`](Self::image_xobject) instead.
+    ///
+    /// Panics if the length of `rgba` is not `4 * width * height`.
+    pub fn rgba_image(
+        &mut self,
+        id: Ref,
+        mask_id: Ref,
+        width: i32,
+        height: i32,
+        rgba: &[u8],
+    ) -> ImageXObject<'_> {
+        let pixels = usize::try_from(width)
+            .ok()
+            .zip(usize::try_from(height).ok())
+            .and_then(|(w, h)| w.checked_mul(h));
+        assert!(
+            pixels.and_then(|p| p.checked_mul(4)) == Some(rgba.len()),
+            "RGBA data must have 4 bytes per pixel",
+        );
+
+        let alpha: Vec<u8> = rgba.chunks_exact(4).map(|px| px[3]).collect();
+        let mut mask = self.image_xobject(mask_id, &alpha);
+        mask.width(width);
+        mask.height(height);
+        mask.color_space().device_gray();
+        mask.bits_per_component(8);
+        mask.finish();
+
+        let start = self.buf.len();
+        self.buf.reserve(rgba.len() / 4 * 3);
+        for px in rgba.chunks_exact(4) {
+            self.buf.extend(&px[..3]);
+        }
+
+        self.record(id, start);
+        let len = self.buf.len() - start;
+        let stream =
+            Stream::start_preloaded(Obj::indirect(&mut self.buf, id), start, len);
+        let mut image = ImageXObject::start(stream);
+        image.width(width);
+        image.height(height);
+        image.color_space().device_rgb();
+        image.bits_per_component(8);
+        image.s_mask(mask_id);
+        image
+    }
+
     /// Start writing a form XObject stream.
     ///
     /// These can be used as transparency groups.
@@ -253,6 +549,36 @@ impl Chunk {
     pub fn ext_graphics(&mut self, id: Ref) -> ExtGraphicsState<'_> {
         self.indirect(id).start()
     }
+
+    /// Start writing a type 1 halftone dictionary.
+    pub fn screen_halftone(&mut self, id: Ref) -> ScreenHalftone<'_> {
+        self.indirect(id).start()
+    }
+
+    /// Start writing a type 5 halftone dictionary.
+    pub fn composite_halftone(&mut self, id: Ref) -> CompositeHalftone<'_> {
+        self.indirect(id).start()
+    }
+
+    /// Start writing a threshold halftone stream.
+    pub fn threshold_halftone<'a>(
+        &'a mut self,
+        id: Ref,
+        thresholds: &'a [u8],
+        kind: ThresholdHalftoneType,
+    ) -> ThresholdHalftone<'a> {
+        ThresholdHalftone::start(self.stream(id, thresholds), kind)
+    }
+
+    /// Start writing an optional content group dictionary. PDF 1.5+.
+    pub fn optional_content_group(&mut self, id: Ref) -> OptionalContentGroup<'_> {
+        self.indirect(id).start()
+    }
+
+    /// Start writing an optional content membership dictionary. PDF 1.5+.
+    pub fn oc_membership(&mut self, id: Ref) -> OcMembership<'_> {
+        self.indirect(id).start()
+    }
 }
 
 /// Fonts.
@@ -262,6 +588,18 @@ impl Chunk {
         self.indirect(id).start()
     }
 
+    /// Start writing a Type-1 font dictionary for one of the standard 14 fonts.
+    ///
+    /// The returned writer already has its `/BaseFont` set. Since these fonts
+    /// are provided by the viewer, no widths or font descriptor are needed. An
+    /// `/Encoding` may be added, but not for [symbolic
+    /// fonts](types::StandardFont::is_symbolic).
+    pub fn standard_font(&mut self, id: Ref, font: StandardFont) -> Type1Font<'_> {
+        let mut writer = self.type1_font(id);
+        writer.base_font(font.to_name());
+        writer
+    }
+
     /// Start writing a Type-3 font.
     pub fn type3_font(&mut self, id: Ref) -> Type3Font<'_> {
         self.indirect(id).start()
@@ -282,10 +620,16 @@ impl Chunk {
         self.indirect(id).start()
     }
 
+    /// Start writing an embedded font file stream.
+    pub fn font_file<'a>(&'a mut self, id: Ref, data: &'a [u8]) -> FontFile<'a> {
+        FontFile::start(self.stream(id, data))
+    }
+
     /// Start writing a character map stream.
     ///
     /// If you want to use this for a `/ToUnicode` CMap, you can create the
-    /// bytes using a [`UnicodeCmap`](types::UnicodeCmap) builder.
+    /// bytes using a [`UnicodeCmap`](types::UnicodeCmap) builder. CMaps for
+    /// custom CID encodings can be built with a [`CidCmap`](types::CidCmap).
     pub fn cmap<'a>(&'a mut self, id: Ref, cmap: &'a [u8]) -> Cmap<'a> {
         Cmap::start(self.stream(id, cmap))
     }
@@ -392,6 +736,217 @@ impl Chunk {
         self.indirect(id).start()
     }
 
+    /// Write many simple annotations from their descriptions.
+    ///
+    /// The ids still need to be listed in the [annotations](Page::annotations)
+    /// of the respective pages.
+    pub fn extend_annotations<'b>(
+        &mut self,
+        annotations: impl IntoIterator<Item = (Ref, AnnotationSpec<'b>)>,
+    ) {
+        let annotations = annotations.into_iter();
+        let (len, _) = annotations.size_hint();
+        self.offsets.reserve(len);
+        self.buf.reserve(len * 128);
+        for (id, spec) in annotations {
+            spec.write(&mut self.annotation(id));
+        }
+    }
+
+    /// Start writing an array of annotation references.
+    ///
+    /// Pages can refer to such an array through
+    /// [`Page::annotations_ref`]. An incremental update can then add
+    /// annotations by rewriting only the array instead of the whole page.
+    pub fn annotations_array(&mut self, id: Ref) -> TypedArray<'_, Ref> {
+        self.indirect(id).array().typed()
+    }
+
+    /// Start writing a web capture command dictionary. PDF 1.3+.
+    pub fn web_capture_command(&mut self, id: Ref) -> WebCaptureCommand<'_> {
+        self.indirect(id).start()
+    }
+
+    /// Start writing a web capture content set dictionary. PDF 1.3+.
+    pub fn content_set(&mut self, id: Ref) -> ContentSet<'_> {
+        self.indirect(id).start()
+    }
+
+    /// Start writing a sound stream. PDF 1.2+.
+    pub fn sound<'a>(&'a mut self, id: Ref, samples: &'a [u8]) -> Sound<'a> {
+        Sound::start(self.stream(id, samples))
+    }
+
+    /// Start writing a 3D measurement dictionary. PDF 2.0+.
+    pub fn measure_3d(&mut self, id: Ref) -> Measure3D<'_> {
+        self.indirect(id).start()
+    }
+
+    /// Start writing a borderless link annotation that opens a URI.
+    ///
+    /// The returned annotation already has its `/Subtype`, `/Rect`, `/Border`
+    /// and `/A` entries set.
+    pub fn uri_link(&mut self, id: Ref, rect: Rect, uri: Str) -> Annotation<'_> {
+        let mut annotation = self.annotation(id);
+        annotation.subtype(AnnotationType::Link);
+        annotation.rect(rect);
+        annotation.border(0.0, 0.0, 0.0, None);
+        annotation.action().action_type(ActionType::Uri).uri(uri);
+        annotation
+    }
+
+    /// Start writing a borderless link annotation that jumps to a page in the
+    /// document.
+    ///
+    /// If `top` is `None`, the whole page is fit into the window. Otherwise,
+    /// the page is fit to the window's width and scrolled to the given
+    /// vertical position. The returned annotation already has its `/Subtype`,
+    /// `/Rect`, `/Border` and `/A` entries set.
+    pub fn goto_link(
+        &mut self,
+        id: Ref,
+        rect: Rect,
+        page: Ref,
+        top: Option<f32>,
+    ) -> Annotation<'_> {
+        let mut annotation = self.annotation(id);
+        annotation.subtype(AnnotationType::Link);
+        annotation.rect(rect);
+        annotation.border(0.0, 0.0, 0.0, None);
+
+        let mut action = annotation.action();
+        action.action_type(ActionType::GoTo);
+        let destination = action.destination().page(page);
+        match top {
+            Some(top) => destination.fit_horizontal(top),
+            None => destination.fit(),
+        }
+        action.finish();
+        annotation
+    }
+
+    /// Start writing an annotation dictionary together with its normal
+    /// appearance stream.
+    ///
+    /// The closure draws the appearance, which is then written as a form
+    /// XObject with the id `appearance_id` and a `/BBox` equal to `rect`. The
+    /// returned annotation already has its `/Rect` and `/AP` entries set.
+    pub fn annotation_with_appearance<F>(
+        &mut self,
+        id: Ref,
+        appearance_id: Ref,
+        rect: Rect,
+        draw: F,
+    ) -> Annotation<'_>
+    where
+        F: FnOnce(&mut Content),
+    {
+        let mut content = Content::new();
+        draw(&mut content);
+        self.form_xobject(appearance_id, &content.finish()).bbox(rect);
+
+        let mut annotation = self.annotation(id);
+        annotation.rect(rect);
+        annotation.appearance().normal().stream(appearance_id);
+        annotation
+    }
+
+    /// Start writing a text markup annotation together with a generated
+    /// normal appearance stream.
+    ///
+    /// The `subtype` must be `Highlight`, `Underline`, `StrikeOut` or
+    /// `Squiggly`. The appearance is drawn for each of the `quads` in the given
+    /// color and written as a form XObject with the id `appearance_id`. The
+    /// returned annotation already has its `/Subtype`, `/Rect`, `/QuadPoints`,
+    /// `/C` and `/AP` entries set.
+    ///
+    /// Panics if the subtype is not a text markup type or if `quads` is empty.
+    pub fn markup_annotation(
+        &mut self,
+        id: Ref,
+        appearance_id: Ref,
+        subtype: AnnotationType,
+        quads: &[Quad],
+        color: Color,
+    ) -> Annotation<'_> {
+        crate::appearance::write_markup(self, id, appearance_id, subtype, quads, color)
+    }
+
+    /// Start writing a square or circle annotation together with a generated
+    /// normal appearance stream.
+    ///
+    /// The `subtype` must be `Square` or `Circle`. The shape is stroked inside
+    /// of `rect` with the given color and line width and written as a form
+    /// XObject with the id `appearance_id`. The returned annotation already has
+    /// its `/Subtype`, `/Rect`, `/C`, `/BS` and `/AP` entries set.
+    ///
+    /// Panics if the subtype is neither `Square` nor `Circle`.
+    pub fn shape_annotation(
+        &mut self,
+        id: Ref,
+        appearance_id: Ref,
+        subtype: AnnotationType,
+        rect: Rect,
+        color: Color,
+        width: f32,
+    ) -> Annotation<'_> {
+        crate::appearance::write_shape(
+            self,
+            id,
+            appearance_id,
+            subtype,
+            rect,
+            color,
+            width,
+        )
+    }
+
+    /// Write the on and off appearance streams of a check box or radio button
+    /// widget.
+    ///
+    /// The on appearance draws the `style`'s symbol centered in `rect` with
+    /// the given color and is written as a form XObject with the id `on_id`.
+    /// The off appearance with the id `off_id` is empty. Both must be entered
+    /// in the widget's normal appearance dictionary under the names of its
+    /// states, of which `/AS` selects the current one:
+    ///
+    /// ```
+    /// use pdf_writer::{Pdf, Rect, Ref, Name};
+    /// use pdf_writer::types::{ButtonStyle, Color};
+    ///
+    /// let mut pdf = Pdf::new();
+    /// let rect = Rect::new(10.0, 10.0, 22.0, 22.0);
+    /// pdf.button_appearances(
+    ///     Ref::new(2),
+    ///     Ref::new(3),
+    ///     ButtonStyle::Check,
+    ///     rect,
+    ///     Color::Gray(0.0),
+    /// );
+    ///
+    /// let mut annot = pdf.annotation(Ref::new(1));
+    /// annot.rect(rect).appearance_state(Name(b"Yes"));
+    /// let mut appearance = annot.appearance();
+    /// let mut states = appearance.normal().streams();
+    /// states.pair(Name(b"Yes"), Ref::new(2));
+    /// states.pair(Name(b"Off"), Ref::new(3));
+    /// ```
+    pub fn button_appearances(
+        &mut self,
+        on_id: Ref,
+        off_id: Ref,
+        style: ButtonStyle,
+        rect: Rect,
+        color: Color,
+    ) {
+        crate::appearance::write_button(self, on_id, off_id, style, rect, color);
+    }
+
+    /// Start writing a signature dictionary. PDF 1.3+.
+    pub fn signature(&mut self, id: Ref) -> Signature<'_> {
+        self.indirect(id).start()
+    }
+
     /// Start writing a form field dictionary.
     pub fn form_field(&mut self, id: Ref) -> Field<'_> {
         self.indirect(id).start()
@@ -404,10 +959,27 @@ impl Debug for Chunk {
     }
 }
 
+/// The length of an indirect object up to and including the `endobj` keyword,
+/// given its bytes up to where the next object starts.
+pub(crate) fn object_len(bytes: &[u8]) -> usize {
+    memchr::memmem::rfind(bytes, b"endobj").map_or(bytes.len(), |pos| pos + 6)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_chunk_capacity() {
+        let mut c = Chunk::with_capacity(16);
+        c.reserve(1000);
+        c.expected_objects(10);
+        assert!(c.capacity() >= 1000);
+        assert!(c.offsets.capacity() >= 10);
+        c.indirect(Ref::new(1)).primitive(1);
+        assert_eq!(c.len(), 18);
+    }
+
     #[test]
     fn test_chunk() {
         let mut w = Pdf::new();
@@ -436,4 +1008,340 @@ mod tests {
             b"startxref\n160\n%%EOF",
         );
     }
+
+    #[test]
+    fn test_indirect_collections() {
+        let mut c = Chunk::new();
+        c.indirect_typed_array(Ref::new(1)).items([250.0, 333.0]);
+        c.indirect_typed_dict::<Ref>(Ref::new(2))
+            .pair(Name(b"Widths"), Ref::new(1));
+        test!(
+            c.as_bytes(),
+            b"1 0 obj",
+            b"[250 333]",
+            b"endobj",
+            b"",
+            b"2 0 obj",
+            b"<<",
+            b"  /Widths 1 0 R",
+            b">>",
+            b"endobj\n\n",
+        );
+    }
+
+    #[test]
+    fn test_indirect_destination() {
+        let mut c = Chunk::new();
+        c.destination(Ref::new(1)).page(Ref::new(2)).xyz(0.0, 842.0, None);
+        c.outline_item(Ref::new(3)).dest_ref(Ref::new(1));
+        c.indirect(Ref::new(4)).start::<Action>().destination_ref(Ref::new(1));
+        test!(
+            c.as_bytes(),
+            b"1 0 obj",
+            b"[2 0 R /XYZ 0 842 0]",
+            b"endobj",
+            b"",
+            b"3 0 obj",
+            b"<<",
+            b"  /Dest 1 0 R",
+            b">>",
+            b"endobj",
+            b"",
+            b"4 0 obj",
+            b"<<",
+            b"  /Type /Action",
+            b"  /D 1 0 R",
+            b">>",
+            b"endobj\n\n",
+        );
+    }
+
+    #[test]
+    fn test_remote_destination() {
+        let mut c = Chunk::new();
+        let mut action = c.indirect(Ref::new(1)).start::<Action>();
+        action.action_type(ActionType::RemoteGoTo);
+        action.file_spec().path(Str(b"other.pdf"));
+        action.destination().page_index(4).fit();
+        action.finish();
+        test!(
+            c.as_bytes(),
+            b"1 0 obj",
+            b"<<",
+            b"  /Type /Action",
+            b"  /S /GoToR",
+            b"  /F <<",
+            b"    /Type /Filespec",
+            b"    /F (other.pdf)",
+            b"  >>",
+            b"  /D [4 /Fit]",
+            b">>",
+            b"endobj\n\n",
+        );
+    }
+
+    #[test]
+    fn test_annotations_array() {
+        let mut c = Chunk::new();
+        c.page(Ref::new(1)).annotations_ref(Ref::new(2));
+        c.annotations_array(Ref::new(2)).items([Ref::new(3), Ref::new(4)]);
+        test!(
+            c.as_bytes(),
+            b"1 0 obj",
+            b"<<\n  /Type /Page\n  /Annots 2 0 R\n>>",
+            b"endobj\n",
+            b"2 0 obj\n[3 0 R 4 0 R]\nendobj\n\n",
+        );
+    }
+
+    #[test]
+    fn test_page_rotation() {
+        let mut c = Chunk::new();
+        for (i, degrees) in [90, -90, 0, 450, -720].into_iter().enumerate() {
+            c.page(Ref::new(i as i32 + 1)).rotate(degrees);
+        }
+        let rotations: Vec<_> = c
+            .as_bytes()
+            .split(|&b| b == b'\n')
+            .filter_map(|line| line.strip_prefix(b"  /Rotate "))
+            .collect();
+        assert_eq!(rotations, [&b"90"[..], b"270", b"0", b"90", b"0"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "rotation must be a multiple of 90 degrees (is 45)")]
+    fn test_page_rotation_invalid() {
+        Chunk::new().page(Ref::new(1)).rotate(45);
+    }
+
+    #[test]
+    fn test_template_page() {
+        let mut c = Chunk::new();
+        c.template_page(Ref::new(1))
+            .media_box(Rect::new(0.0, 0.0, 10.0, 10.0));
+        test!(
+            c.as_bytes(),
+            b"1 0 obj",
+            b"<<\n  /Type /Template\n  /MediaBox [0 0 10 10]\n>>",
+            b"endobj\n\n",
+        );
+    }
+
+    #[test]
+    fn test_comment() {
+        let mut c = Chunk::new();
+        c.comment("Font descriptor\r\nfor MyFont");
+        c.indirect(Ref::new(1)).primitive(1);
+        c.comment("Array\rrefers to 1");
+        c.indirect(Ref::new(2)).array().item(Ref::new(1));
+        test!(
+            c.as_bytes(),
+            b"% Font descriptor",
+            b"% for MyFont",
+            b"1 0 obj\n1\nendobj\n",
+            b"% Array",
+            b"% refers to 1",
+            b"2 0 obj\n[1 0 R]\nendobj\n\n",
+        );
+
+        let renumbered = c.renumber(|id| Ref::new(id.get() + 1));
+        test!(
+            renumbered.as_bytes(),
+            b"2 0 obj\n1\nendobj\n",
+            b"3 0 obj\n[2 0 R]\nendobj\n\n",
+        );
+    }
+
+    #[test]
+    fn test_stream_from_reader() {
+        let mut w = Pdf::new();
+        w.stream_from_reader(Ref::new(1), &b"Hello"[..], 0)
+            .unwrap()
+            .filter(Filter::AsciiHexDecode);
+        w.stream(Ref::new(2), b"World");
+        test!(
+            w.finish(),
+            b"%PDF-1.7\n%\x80\x80\x80\x80\n",
+            b"1 0 obj",
+            b"<<\n  /Length 5\n  /Filter /ASCIIHexDecode\n>>",
+            b"stream",
+            b"Hello",
+            b"endstream",
+            b"endobj\n",
+            b"2 0 obj",
+            b"<<\n  /Length 5\n>>",
+            b"stream",
+            b"World",
+            b"endstream",
+            b"endobj\n",
+            b"xref",
+            b"0 3",
+            b"0000000000 65535 f\r",
+            b"0000000016 00000 n\r",
+            b"0000000099 00000 n\r",
+            b"trailer",
+            b"<<\n  /Size 3\n>>",
+            b"startxref\n156\n%%EOF",
+        );
+    }
+
+    #[test]
+    fn test_annotation_with_appearance() {
+        let mut c = Chunk::new();
+        let rect = Rect::new(10.0, 10.0, 30.0, 20.0);
+        c.annotation_with_appearance(Ref::new(1), Ref::new(2), rect, |content| {
+            content.rect(10.0, 10.0, 20.0, 10.0).fill_nonzero();
+        })
+        .subtype(AnnotationType::Square);
+        test!(
+            c.as_bytes(),
+            b"2 0 obj",
+            b"<<",
+            b"  /Length 16",
+            b"  /Type /XObject",
+            b"  /Subtype /Form",
+            b"  /BBox [10 10 30 20]",
+            b">>",
+            b"stream",
+            b"10 10 20 10 re\nf",
+            b"endstream",
+            b"endobj\n",
+            b"1 0 obj",
+            b"<<",
+            b"  /Type /Annot",
+            b"  /Rect [10 10 30 20]",
+            b"  /AP <<",
+            b"    /N 2 0 R",
+            b"  >>",
+            b"  /Subtype /Square",
+            b">>",
+            b"endobj\n\n",
+        );
+    }
+
+    #[test]
+    fn test_thumbnail() {
+        let mut c = Chunk::new();
+        c.thumbnail(Ref::new(1), &[0, 1])
+            .width(2)
+            .height(1)
+            .bits_per_component(8)
+            .indexed_gray(1, &[0, 255]);
+        test!(
+            c.as_bytes(),
+            b"1 0 obj",
+            b"<<",
+            b"  /Length 2",
+            b"  /Type /XObject",
+            b"  /Subtype /Image",
+            b"  /Width 2",
+            b"  /Height 1",
+            b"  /BitsPerComponent 8",
+            b"  /ColorSpace [/Indexed /DeviceGray 1 <00FF>]",
+            b">>",
+            b"stream",
+            b"\x00\x01",
+            b"endstream",
+            b"endobj\n\n",
+        );
+    }
+
+    #[test]
+    fn test_links() {
+        let mut c = Chunk::new();
+        let rect = Rect::new(0.0, 0.0, 10.0, 10.0);
+        c.uri_link(Ref::new(1), rect, Str(b"https://example.com"));
+        c.goto_link(Ref::new(2), rect, Ref::new(3), Some(500.0));
+        test!(
+            c.as_bytes(),
+            b"1 0 obj",
+            b"<<",
+            b"  /Type /Annot",
+            b"  /Subtype /Link",
+            b"  /Rect [0 0 10 10]",
+            b"  /Border [0 0 0]",
+            b"  /A <<",
+            b"    /Type /Action",
+            b"    /S /URI",
+            b"    /URI (https://example.com)",
+            b"  >>",
+            b">>",
+            b"endobj\n",
+            b"2 0 obj",
+            b"<<",
+            b"  /Type /Annot",
+            b"  /Subtype /Link",
+            b"  /Rect [0 0 10 10]",
+            b"  /Border [0 0 0]",
+            b"  /A <<",
+            b"    /Type /Action",
+            b"    /S /GoTo",
+            b"    /D [3 0 R /FitH 500]",
+            b"  >>",
+            b">>",
+            b"endobj\n\n",
+        );
+    }
+
+    #[test]
+    fn test_accessibility_basics() {
+        let mut c = Chunk::new();
+        c.indirect(Ref::new(1))
+            .start::<Catalog>()
+            .accessibility_basics(TextStr("en-US"), [Ref::new(2)]);
+        test!(
+            c.as_bytes(),
+            b"1 0 obj",
+            b"<<",
+            b"  /Type /Catalog",
+            b"  /Lang (en-US)",
+            b"  /MarkInfo <<",
+            b"    /Marked true",
+            b"  >>",
+            b"  /ViewerPreferences <<",
+            b"    /DisplayDocTitle true",
+            b"  >>",
+            b"  /AF [2 0 R]",
+            b">>",
+            b"endobj\n\n",
+        );
+    }
+
+    #[test]
+    fn test_rgba_image() {
+        let mut c = Chunk::new();
+        c.rgba_image(Ref::new(1), Ref::new(2), 2, 1, &[1, 2, 3, 4, 5, 6, 7, 8]);
+        test!(
+            c.as_bytes(),
+            b"2 0 obj",
+            b"<<",
+            b"  /Length 2",
+            b"  /Type /XObject",
+            b"  /Subtype /Image",
+            b"  /Width 2",
+            b"  /Height 1",
+            b"  /ColorSpace /DeviceGray",
+            b"  /BitsPerComponent 8",
+            b">>",
+            b"stream",
+            b"\x04\x08",
+            b"endstream",
+            b"endobj\n",
+            b"1 0 obj",
+            b"<<",
+            b"  /Length 6",
+            b"  /Type /XObject",
+            b"  /Subtype /Image",
+            b"  /Width 2",
+            b"  /Height 1",
+            b"  /ColorSpace /DeviceRGB",
+            b"  /BitsPerComponent 8",
+            b"  /SMask 2 0 R",
+            b">>",
+            b"stream",
+            b"\x01\x02\x03\x05\x06\x07",
+            b"endstream",
+            b"endobj\n\n",
+        );
+    }
 }
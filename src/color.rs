@@ -68,6 +68,179 @@ impl DeviceColorSpace {
     }
 }
 
+/// A color in one of the device color spaces or in a named color space.
+///
+/// Device components are in the range `0.0..=1.0`. Lab, indexed and named
+/// colors refer to a color space registered under `space` in the current
+/// [resource dictionary](crate::writers::Resources::color_spaces). Where only
+/// device colors are permitted, like in annotations and outline items, they
+/// are [converted](Self::to_rgb) to RGB.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Color<'a> {
+    /// A gray level, where `0.0` is black.
+    Gray(f32),
+    /// Red, green and blue components.
+    Rgb(f32, f32, f32),
+    /// Cyan, magenta, yellow and black components.
+    Cmyk(f32, f32, f32, f32),
+    /// A color in a [`Lab`](ColorSpace::lab) color space.
+    Lab {
+        /// The name of the color space.
+        space: Name<'a>,
+        /// The lightness in `0.0..=100.0`.
+        l: f32,
+        /// The `a*` component within the range of the color space.
+        a: f32,
+        /// The `b*` component within the range of the color space.
+        b: f32,
+    },
+    /// An entry of an [`Indexed`](ColorSpace::indexed) color space.
+    Indexed {
+        /// The name of the color space.
+        space: Name<'a>,
+        /// The index into the color table.
+        index: u8,
+        /// An approximation of the entry for conversions, since the color table
+        /// is not known here.
+        rgb: [f32; 3],
+    },
+    /// A tint of a named colorant in a [`Separation`](ColorSpace::separation)
+    /// color space.
+    Named {
+        /// The name of the color space.
+        space: Name<'a>,
+        /// The amount of colorant in `0.0..=1.0`.
+        tint: f32,
+        /// An approximation of the colorant at full tint for conversions.
+        rgb: [f32; 3],
+    },
+}
+
+impl Color<'_> {
+    /// The device color space this color is specified in or, for Lab, indexed
+    /// and named colors, converted to.
+    pub fn space(self) -> DeviceColorSpace {
+        match self {
+            Self::Gray(_) => DeviceColorSpace::Gray,
+            Self::Cmyk(..) => DeviceColorSpace::Cmyk,
+            _ => DeviceColorSpace::Rgb,
+        }
+    }
+
+    /// Convert the color to a gray level.
+    ///
+    /// This uses the conversion formulas for device color spaces from the PDF
+    /// specification (Section 10.3) and is not color-managed.
+    pub fn to_gray(self) -> f32 {
+        match self {
+            Self::Gray(gray) => gray,
+            Self::Cmyk(c, m, y, k) => 1.0 - (0.3 * c + 0.59 * m + 0.11 * y + k).min(1.0),
+            Self::Lab { l, .. } => (l / 100.0).clamp(0.0, 1.0),
+            _ => {
+                let [r, g, b] = self.to_rgb();
+                0.3 * r + 0.59 * g + 0.11 * b
+            }
+        }
+    }
+
+    /// Convert the color to red, green and blue components.
+    ///
+    /// This uses the conversion formulas for device color spaces from the PDF
+    /// specification (Section 10.3) and is not color-managed. Lab colors are
+    /// converted assuming a D65 white point and sRGB primaries, named colors
+    /// by mixing their approximation with white according to the tint.
+    pub fn to_rgb(self) -> [f32; 3] {
+        match self {
+            Self::Gray(gray) => [gray; 3],
+            Self::Rgb(r, g, b) => [r, g, b],
+            Self::Cmyk(c, m, y, k) => {
+                [1.0 - (c + k).min(1.0), 1.0 - (m + k).min(1.0), 1.0 - (y + k).min(1.0)]
+            }
+            Self::Lab { l, a, b, .. } => lab_to_rgb(l, a, b),
+            Self::Indexed { rgb, .. } => rgb,
+            Self::Named { tint, rgb, .. } => rgb.map(|c| 1.0 - tint * (1.0 - c)),
+        }
+    }
+
+    /// Convert the color to cyan, magenta, yellow and black components.
+    ///
+    /// This uses the conversion formulas for device color spaces from the PDF
+    /// specification (Section 10.3) with full black generation and undercolor
+    /// removal. It is not color-managed.
+    pub fn to_cmyk(self) -> [f32; 4] {
+        match self {
+            Self::Gray(gray) => [0.0, 0.0, 0.0, 1.0 - gray],
+            Self::Cmyk(c, m, y, k) => [c, m, y, k],
+            _ => {
+                let [r, g, b] = self.to_rgb();
+                let (c, m, y) = (1.0 - r, 1.0 - g, 1.0 - b);
+                let k = c.min(m).min(y);
+                [c - k, m - k, y - k, k]
+            }
+        }
+    }
+
+    /// Write the color's device components as an array.
+    pub(crate) fn write(self, obj: Obj) {
+        let mut array = obj.array();
+        match self {
+            Self::Gray(gray) => array.item(gray),
+            Self::Cmyk(c, m, y, k) => array.items([c, m, y, k]),
+            _ => array.items(self.to_rgb()),
+        };
+    }
+}
+
+/// Convert a CIE L*a*b* color to RGB with sRGB primaries and a D65 white
+/// point.
+///
+/// Since `powf` is not available without `std`, the linear components are
+/// companded with the L* curve, which is close to the sRGB curve.
+fn lab_to_rgb(l: f32, a: f32, b: f32) -> [f32; 3] {
+    const EPSILON: f32 = 216.0 / 24389.0;
+    const KAPPA: f32 = 24389.0 / 27.0;
+
+    let inverse = |t: f32| {
+        let cube = t * t * t;
+        if cube > EPSILON {
+            cube
+        } else {
+            (116.0 * t - 16.0) / KAPPA
+        }
+    };
+
+    let fy = (l + 16.0) / 116.0;
+    let x = 0.95047 * inverse(fy + a / 500.0);
+    let y = inverse(fy);
+    let z = 1.08883 * inverse(fy - b / 200.0);
+
+    let linear = [
+        3.2404542 * x - 1.5371385 * y - 0.4985314 * z,
+        -0.969266 * x + 1.8760108 * y + 0.041556 * z,
+        0.0556434 * x - 0.2040259 * y + 1.0572252 * z,
+    ];
+
+    linear.map(|c| {
+        let c = c.clamp(0.0, 1.0);
+        let companded =
+            if c > EPSILON { 1.16 * cbrt(c) - 0.16 } else { c * KAPPA / 100.0 };
+        companded.clamp(0.0, 1.0)
+    })
+}
+
+/// Compute the cube root of a number in `0.0..=1.0` with Newton's method.
+fn cbrt(x: f32) -> f32 {
+    if x == 0.0 {
+        return 0.0;
+    }
+
+    let mut y = 1.0;
+    for _ in 0..20 {
+        y -= (y * y * y - x) / (3.0 * y * y);
+    }
+    y
+}
+
 /// Writer for a _color space_.
 ///
 /// This struct is created by [`Chunk::color_space`],
@@ -1189,7 +1362,8 @@ impl StreamShadingType {
 
 /// Writer for the _separation information dictionary_. PDF 1.3+.
 ///
-/// This struct is created by [`Catalog::separation_info`].
+/// This struct is created by [`Page::separation_info`] and
+/// [`Catalog::separation_info`].
 pub struct SeparationInfo<'a> {
     dict: Dict<'a>,
 }
@@ -1,8 +1,12 @@
 use super::*;
+use crate::types::Color;
 
 /// A builder for a content stream.
 pub struct Content {
     buf: Vec<u8>,
+    state_depth: usize,
+    in_text: bool,
+    misused: bool,
 }
 
 /// Core methods.
@@ -16,7 +20,12 @@ impl Content {
 
     /// Create a new content stream with the specified initial buffer capacity.
     pub fn with_capacity(capacity: usize) -> Self {
-        Self { buf: Vec::with_capacity(capacity) }
+        Self {
+            buf: Vec::with_capacity(capacity),
+            state_depth: 0,
+            in_text: false,
+            misused: false,
+        }
     }
 
     /// Start writing an arbitrary operation.
@@ -25,13 +34,44 @@ impl Content {
         Operation::start(&mut self.buf, operator)
     }
 
+    /// The current nesting depth of the graphics state stack, i.e. the number
+    /// of [`save_state`](Self::save_state) calls without a matching
+    /// [`restore_state`](Self::restore_state).
+    pub fn state_depth(&self) -> usize {
+        self.state_depth
+    }
+
+    /// Whether a text object was started with
+    /// [`begin_text`](Self::begin_text), but not yet ended.
+    pub fn in_text(&self) -> bool {
+        self.in_text
+    }
+
+    /// Whether all graphics states were restored and all text objects were
+    /// ended, without restoring more states than were saved, ending a text
+    /// object that was not begun or nesting text objects. Once an operator was
+    /// misused, the stream stays unbalanced. Operators written through
+    /// [`op`](Self::op) are not tracked.
+    pub fn is_balanced(&self) -> bool {
+        !self.misused && self.state_depth == 0 && !self.in_text
+    }
+
     /// Return the raw constructed byte stream.
+    ///
+    /// Use [`finish_checked`](Self::finish_checked) to catch unbalanced
+    /// operators.
     pub fn finish(mut self) -> Vec<u8> {
         if self.buf.last() == Some(&b'\n') {
             self.buf.pop();
         }
         self.buf
     }
+
+    /// Return the raw constructed byte stream if it is
+    /// [balanced](Self::is_balanced) and `None` otherwise.
+    pub fn finish_checked(self) -> Option<Vec<u8>> {
+        self.is_balanced().then(|| self.finish())
+    }
 }
 
 /// Writer for an _operation_ in a content stream.
@@ -118,6 +158,9 @@ impl Content {
     }
 
     /// `M`: Set the miter limit.
+    ///
+    /// When the ratio of the miter length to the line width exceeds this
+    /// limit, a miter join is drawn as a bevel join instead.
     #[inline]
     pub fn set_miter_limit(&mut self, limit: f32) -> &mut Self {
         self.op("M").operand(limit);
@@ -126,14 +169,10 @@ impl Content {
 
     /// `d`: Set the line dash pattern.
     #[inline]
-    pub fn set_dash_pattern(
-        &mut self,
-        array: impl IntoIterator<Item = f32>,
-        phase: f32,
-    ) -> &mut Self {
+    pub fn set_dash_pattern(&mut self, pattern: DashPattern) -> &mut Self {
         let mut op = self.op("d");
-        op.obj().array().items(array);
-        op.operand(phase);
+        op.obj().array().items(pattern.array.iter().copied());
+        op.operand(pattern.phase);
         op.finish();
         self
     }
@@ -169,7 +208,7 @@ impl Content {
 /// How to terminate lines.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum LineCapStyle {
-    /// Square the line of at the endpoints of the path.
+    /// Square the line off at the endpoints of the path.
     ButtCap,
     /// Round the line off at its end with a semicircular arc as wide as the
     /// stroke.
@@ -212,6 +251,31 @@ impl LineJoinStyle {
         }
     }
 }
+
+/// A line dash pattern.
+///
+/// Used by [`Content::set_dash_pattern`], [`ExtGraphicsState::dash_pattern`]
+/// and the dash patterns of borders.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DashPattern<'a> {
+    /// The alternating lengths of dashes and gaps. An empty array results in
+    /// a solid line.
+    pub array: &'a [f32],
+    /// The distance into the pattern at which to start the dash.
+    pub phase: f32,
+}
+
+impl<'a> DashPattern<'a> {
+    /// Create a new dash pattern.
+    pub fn new(array: &'a [f32], phase: f32) -> Self {
+        Self { array, phase }
+    }
+
+    /// The pattern for solid lines.
+    pub fn solid() -> Self {
+        Self::new(&[], 0.0)
+    }
+}
 /// How the output device should aim to render colors.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum RenderingIntent {
@@ -243,6 +307,7 @@ impl Content {
     #[inline]
     pub fn save_state(&mut self) -> &mut Self {
         self.op("q");
+        self.state_depth += 1;
         self
     }
 
@@ -250,6 +315,10 @@ impl Content {
     #[inline]
     pub fn restore_state(&mut self) -> &mut Self {
         self.op("Q");
+        match self.state_depth.checked_sub(1) {
+            Some(depth) => self.state_depth = depth,
+            None => self.misused = true,
+        }
         self
     }
 
@@ -325,6 +394,101 @@ impl Content {
     }
 }
 
+/// Path shapes.
+///
+/// These approximate curved shapes with cubic Bézier segments and each start a
+/// new subpath.
+impl Content {
+    /// Append a circular arc around (cx, cy) to the current path.
+    ///
+    /// The arc starts at the angle `start` and sweeps by `sweep`, both in
+    /// radians. Positive sweeps go counterclockwise. The arc begins a new
+    /// subpath at its start point and is split into segments of at most a
+    /// quarter circle. Requires the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn arc(
+        &mut self,
+        cx: f32,
+        cy: f32,
+        radius: f32,
+        start: f32,
+        sweep: f32,
+    ) -> &mut Self {
+        let point = |angle: f32| (cx + radius * angle.cos(), cy + radius * angle.sin());
+        let (x, y) = point(start);
+        self.move_to(x, y);
+
+        let segments = (sweep.abs() / core::f32::consts::FRAC_PI_2).ceil().max(1.0);
+        let step = sweep / segments;
+        let k = 4.0 / 3.0 * (step / 4.0).tan() * radius;
+        for i in 0..segments as usize {
+            let a = start + step * i as f32;
+            let b = a + step;
+            let (x0, y0) = point(a);
+            let (x3, y3) = point(b);
+            self.cubic_to(
+                x0 - k * a.sin(),
+                y0 + k * a.cos(),
+                x3 + k * b.sin(),
+                y3 - k * b.cos(),
+                x3,
+                y3,
+            );
+        }
+        self
+    }
+
+    /// Append a closed ellipse centered at (cx, cy) with the radii `rx` and
+    /// `ry` to the current path.
+    pub fn ellipse(&mut self, cx: f32, cy: f32, rx: f32, ry: f32) -> &mut Self {
+        let (kx, ky) = (KAPPA * rx, KAPPA * ry);
+        self.move_to(cx + rx, cy);
+        self.cubic_to(cx + rx, cy + ky, cx + kx, cy + ry, cx, cy + ry);
+        self.cubic_to(cx - kx, cy + ry, cx - rx, cy + ky, cx - rx, cy);
+        self.cubic_to(cx - rx, cy - ky, cx - kx, cy - ry, cx, cy - ry);
+        self.cubic_to(cx + kx, cy - ry, cx + rx, cy - ky, cx + rx, cy);
+        self.close_path()
+    }
+
+    /// Append a closed circle centered at (cx, cy) to the current path.
+    pub fn circle(&mut self, cx: f32, cy: f32, radius: f32) -> &mut Self {
+        self.ellipse(cx, cy, radius, radius)
+    }
+
+    /// Append a closed rectangle with rounded corners to the current path.
+    ///
+    /// The corner radius is clamped to half of the smaller side.
+    pub fn rounded_rect(
+        &mut self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        radius: f32,
+    ) -> &mut Self {
+        let sign = |v: f32| if v < 0.0 { -1.0 } else { 1.0 };
+        let (sx, sy) = (sign(width), sign(height));
+        let r = radius.min(sx * width / 2.0).min(sy * height / 2.0).max(0.0);
+        let (rx, ry) = (r * sx, r * sy);
+        let (kx, ky) = (KAPPA * rx, KAPPA * ry);
+        let (x2, y2) = (x + width, y + height);
+        self.move_to(x + rx, y);
+        self.line_to(x2 - rx, y);
+        self.cubic_to(x2 - rx + kx, y, x2, y + ry - ky, x2, y + ry);
+        self.line_to(x2, y2 - ry);
+        self.cubic_to(x2, y2 - ry + ky, x2 - rx + kx, y2, x2 - rx, y2);
+        self.line_to(x + rx, y2);
+        self.cubic_to(x + rx - kx, y2, x, y2 - ry + ky, x, y2 - ry);
+        self.line_to(x, y + ry);
+        self.cubic_to(x, y + ry - ky, x + rx - kx, y, x + rx, y);
+        self.close_path()
+    }
+}
+
+/// The magic number for approximating a quarter circle with a cubic Bézier
+/// curve.
+const KAPPA: f32 = 0.552_284_8;
+
 /// Path painting.
 impl Content {
     /// `S`: Stroke the current path.
@@ -421,6 +585,8 @@ impl Content {
     #[inline]
     pub fn begin_text(&mut self) -> &mut Self {
         self.op("BT");
+        self.misused |= self.in_text;
+        self.in_text = true;
         self
     }
 
@@ -428,6 +594,8 @@ impl Content {
     #[inline]
     pub fn end_text(&mut self) -> &mut Self {
         self.op("ET");
+        self.misused |= !self.in_text;
+        self.in_text = false;
         self
     }
 }
@@ -593,6 +761,38 @@ impl Content {
     pub fn show_positioned(&mut self) -> ShowPositioned<'_> {
         ShowPositioned::start(self.op("TJ"))
     }
+
+    /// `TJ`: Show a run of glyphs with individual adjustments.
+    ///
+    /// Each item consists of a glyph ID and an adjustment that is applied after
+    /// the glyph, in the same unit as [`PositionedItems::adjust`]. Glyph IDs are
+    /// encoded with two bytes each, as is needed for Type 0 fonts with an
+    /// `Identity-H` or `Identity-V` encoding. Consecutive glyphs without
+    /// adjustment are merged into a single string.
+    ///
+    /// This maps well to the output of a text shaper: The adjustment is the
+    /// difference between the glyph's default width and the shaped advance.
+    pub fn show_glyphs(
+        &mut self,
+        glyphs: impl IntoIterator<Item = (u16, f32)>,
+    ) -> &mut Self {
+        let mut op = self.show_positioned();
+        let mut items = op.items();
+        let mut run = vec![];
+        for (glyph, adjustment) in glyphs {
+            run.extend(glyph.to_be_bytes());
+            if adjustment != 0.0 {
+                items.show(Str(&run)).adjust(adjustment);
+                run.clear();
+            }
+        }
+        if !run.is_empty() {
+            items.show(Str(&run));
+        }
+        items.finish();
+        op.finish();
+        self
+    }
 }
 
 /// Writer for an _individual glyph positioning operation_.
@@ -809,6 +1009,52 @@ impl Content {
         self.op("k").operands([c, m, y, k]);
         self
     }
+
+    /// `G`, `RG` or `K`: Set the stroke color and the matching device color
+    /// space.
+    ///
+    /// Lab, indexed and named colors are written with `CS` and `SCN` using
+    /// their color space name instead.
+    #[inline]
+    pub fn set_stroke_device_color(&mut self, color: Color) -> &mut Self {
+        match color {
+            Color::Gray(gray) => self.set_stroke_gray(gray),
+            Color::Rgb(r, g, b) => self.set_stroke_rgb(r, g, b),
+            Color::Cmyk(c, m, y, k) => self.set_stroke_cmyk(c, m, y, k),
+            Color::Lab { space, l, a, b } => {
+                self.set_stroke_color_space(space).set_stroke_color([l, a, b])
+            }
+            Color::Indexed { space, index, .. } => self
+                .set_stroke_color_space(space)
+                .set_stroke_color([f32::from(index)]),
+            Color::Named { space, tint, .. } => {
+                self.set_stroke_color_space(space).set_stroke_color([tint])
+            }
+        }
+    }
+
+    /// `g`, `rg` or `k`: Set the fill color and the matching device color
+    /// space.
+    ///
+    /// Lab, indexed and named colors are written with `cs` and `scn` using
+    /// their color space name instead.
+    #[inline]
+    pub fn set_fill_device_color(&mut self, color: Color) -> &mut Self {
+        match color {
+            Color::Gray(gray) => self.set_fill_gray(gray),
+            Color::Rgb(r, g, b) => self.set_fill_rgb(r, g, b),
+            Color::Cmyk(c, m, y, k) => self.set_fill_cmyk(c, m, y, k),
+            Color::Lab { space, l, a, b } => {
+                self.set_fill_color_space(space).set_fill_color([l, a, b])
+            }
+            Color::Indexed { space, index, .. } => {
+                self.set_fill_color_space(space).set_fill_color([f32::from(index)])
+            }
+            Color::Named { space, tint, .. } => {
+                self.set_fill_color_space(space).set_fill_color([tint])
+            }
+        }
+    }
 }
 
 /// A color space operand to the [`CS`](Content::set_stroke_color_space) or
@@ -886,6 +1132,71 @@ impl Content {
         self.op("Do").operand(name);
         self
     }
+
+    /// Draw an image XObject into the rectangle with the lower-left corner at
+    /// (x, y) and the given width and height.
+    ///
+    /// Images are always drawn into the unit square, so this writes a `cm`
+    /// operation that scales and translates it into place before the `Do`
+    /// operation. Both are wrapped in a `q`/`Q` pair so that the
+    /// transformation does not leak into subsequent operations.
+    #[inline]
+    pub fn draw_image(
+        &mut self,
+        name: Name,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    ) -> &mut Self {
+        self.save_state()
+            .transform([width, 0.0, 0.0, height, x, y])
+            .x_object(name)
+            .restore_state()
+    }
+
+    /// Draw the appearance stream of an annotation or form field widget into
+    /// its annotation rectangle, e.g. to flatten a filled form.
+    ///
+    /// The `bbox` and `matrix` are the `/BBox` and `/Matrix` of the named form
+    /// XObject and `rect` is the `/Rect` of the annotation. Like a viewer
+    /// does, this maps the transformed bounding box onto the rectangle with a
+    /// `cm` operation wrapped in a `q`/`Q` pair. A flattened widget must then
+    /// be left out of the page's [annotations](Page::annotations_flattened).
+    pub fn draw_appearance(
+        &mut self,
+        name: Name,
+        bbox: Rect,
+        matrix: Option<[f32; 6]>,
+        rect: Rect,
+    ) -> &mut Self {
+        let [a, b, c, d, e, f] = matrix.unwrap_or([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+        let corners = [
+            (bbox.x1, bbox.y1),
+            (bbox.x2, bbox.y1),
+            (bbox.x1, bbox.y2),
+            (bbox.x2, bbox.y2),
+        ]
+        .map(|(x, y)| (a * x + c * y + e, b * x + d * y + f));
+
+        let (mut x1, mut y1) = corners[0];
+        let (mut x2, mut y2) = corners[0];
+        for (x, y) in corners {
+            x1 = x1.min(x);
+            y1 = y1.min(y);
+            x2 = x2.max(x);
+            y2 = y2.max(y);
+        }
+
+        let rect = rect.union(rect);
+        let scale = |from: f32, to: f32| if from == 0.0 { 1.0 } else { to / from };
+        let sx = scale(x2 - x1, rect.x2 - rect.x1);
+        let sy = scale(y2 - y1, rect.y2 - rect.y1);
+        self.save_state()
+            .transform([sx, 0.0, 0.0, sy, rect.x1 - sx * x1, rect.y1 - sy * y1])
+            .x_object(name)
+            .restore_state()
+    }
 }
 
 /// Marked Content.
@@ -926,6 +1237,77 @@ impl Content {
         self.op("EMC");
         self
     }
+
+    /// `/Span <</ActualText ..>> BDC`: Begin a span whose content should be
+    /// extracted as `text`, for example the unhyphenated form of a hyphenated
+    /// word or the individual characters of a ligature. Must be ended with
+    /// [`end_marked_content`](Self::end_marked_content). PDF 1.5+.
+    ///
+    /// To share one property list between multiple spans, write it into the
+    /// [`Resources::properties`] dictionary instead and reference it with
+    /// [`MarkContent::properties_named`].
+    #[inline]
+    pub fn begin_actual_text(&mut self, text: TextStr) -> &mut Self {
+        self.begin_marked_content_with_properties(Name(b"Span"))
+            .properties()
+            .actual_text(text);
+        self
+    }
+
+    /// Show a text run that should be extracted as `text`, wrapping it in a
+    /// span with an `/ActualText` property. PDF 1.5+.
+    #[inline]
+    pub fn show_with_actual_text(&mut self, run: Str, text: TextStr) -> &mut Self {
+        self.begin_actual_text(text).show(run).end_marked_content()
+    }
+
+    /// `/Artifact <</Type ..>> BDC`: Begin an artifact, i.e. content that is
+    /// not part of the document's logical structure, like running headers,
+    /// decorative rules or backgrounds. Must be ended with
+    /// [`end_marked_content`](Self::end_marked_content). PDF 1.4+.
+    ///
+    /// Tagged PDFs, e.g. for PDF/UA, must mark all such content as artifacts.
+    /// The `subtype` is only permissible for pagination artifacts and the
+    /// `bbox` is required for background artifacts.
+    #[inline]
+    pub fn begin_artifact(
+        &mut self,
+        kind: ArtifactType,
+        subtype: Option<ArtifactSubtype>,
+        bbox: Option<Rect>,
+    ) -> &mut Self {
+        let mut mark = self.begin_marked_content_with_properties(Name(b"Artifact"));
+        let mut artifact = mark.properties().artifact();
+        artifact.kind(kind);
+        if let Some(subtype) = subtype {
+            artifact.subtype(subtype);
+        }
+        if let Some(bbox) = bbox {
+            artifact.bounding_box(bbox);
+        }
+        artifact.finish();
+        mark.finish();
+        self
+    }
+
+    /// Write the content drawn by `draw` as an artifact, wrapping it with
+    /// [`begin_artifact`](Self::begin_artifact) and
+    /// [`end_marked_content`](Self::end_marked_content). PDF 1.4+.
+    #[inline]
+    pub fn artifact<F>(
+        &mut self,
+        kind: ArtifactType,
+        subtype: Option<ArtifactSubtype>,
+        bbox: Option<Rect>,
+        draw: F,
+    ) -> &mut Self
+    where
+        F: FnOnce(&mut Self),
+    {
+        self.begin_artifact(kind, subtype, bbox);
+        draw(self);
+        self.end_marked_content()
+    }
 }
 
 /// Writer for a _begin marked content operation_. PDF 1.3+.
@@ -981,6 +1363,30 @@ impl<'a> PropertyList<'a> {
         self
     }
 
+    /// Write the `/Lang` attribute to set the natural language of this marked
+    /// content sequence. The tag should be `/Span`. PDF 1.4+.
+    #[inline]
+    pub fn lang(&mut self, lang: TextStr) -> &mut Self {
+        self.pair(Name(b"Lang"), lang);
+        self
+    }
+
+    /// Write the `/Alt` attribute to provide a description of this marked
+    /// content sequence, for example of an image. PDF 1.5+.
+    #[inline]
+    pub fn alt(&mut self, alt: TextStr) -> &mut Self {
+        self.pair(Name(b"Alt"), alt);
+        self
+    }
+
+    /// Write the `/E` attribute to set the expanded form of an abbreviation
+    /// in this marked content sequence. PDF 1.5+.
+    #[inline]
+    pub fn expanded(&mut self, expanded: TextStr) -> &mut Self {
+        self.pair(Name(b"E"), expanded);
+        self
+    }
+
     /// Start writing artifact property list. The tag of the marked content
     /// operation must have been `/Artifact`. PDF 1.4+.
     #[inline]
@@ -1307,14 +1713,10 @@ impl<'a> ExtGraphicsState<'a> {
     }
 
     /// Write the `D` attribute to set the dash pattern. PDF 1.3+.
-    pub fn dash_pattern(
-        &mut self,
-        pattern: impl IntoIterator<Item = f32>,
-        phase: f32,
-    ) -> &mut Self {
+    pub fn dash_pattern(&mut self, pattern: DashPattern) -> &mut Self {
         let mut array = self.insert(Name(b"D")).array();
-        array.push().array().items(pattern);
-        array.item(phase);
+        array.push().array().items(pattern.array.iter().copied());
+        array.item(pattern.phase);
         array.finish();
         self
     }
@@ -1397,7 +1799,53 @@ impl<'a> ExtGraphicsState<'a> {
         self
     }
 
+    /// Write the `BG2` attribute to set the black-generation function. Takes
+    /// precedence over the `BG` attribute. PDF 1.3+.
+    pub fn black_generation_2(&mut self, func: Ref) -> &mut Self {
+        self.pair(Name(b"BG2"), func);
+        self
+    }
+
+    /// Write the `UCR2` attribute to set the undercolor removal function. Takes
+    /// precedence over the `UCR` attribute. PDF 1.3+.
+    pub fn undercolor_removal_2(&mut self, func: Ref) -> &mut Self {
+        self.pair(Name(b"UCR2"), func);
+        self
+    }
+
+    /// Write the `TR` attribute to set separate transfer functions for the
+    /// cyan, magenta, yellow, and black components (or red, green, blue, and
+    /// gray).
+    pub fn transfer_components(&mut self, funcs: [Ref; 4]) -> &mut Self {
+        self.insert(Name(b"TR")).array().items(funcs);
+        self
+    }
+
+    /// Write the `TR` attribute to set the identity transfer function.
+    pub fn transfer_identity(&mut self) -> &mut Self {
+        self.pair(Name(b"TR"), Name(b"Identity"));
+        self
+    }
+
+    /// Write the `TR2` attribute to set the transfer function. Takes
+    /// precedence over the `TR` attribute. PDF 1.3+.
+    pub fn transfer_2(&mut self, func: Ref) -> &mut Self {
+        self.pair(Name(b"TR2"), func);
+        self
+    }
+
+    /// Write the `TR2` attribute to set separate transfer functions for the
+    /// four components. Takes precedence over the `TR` attribute. PDF 1.3+.
+    pub fn transfer_2_components(&mut self, funcs: [Ref; 4]) -> &mut Self {
+        self.insert(Name(b"TR2")).array().items(funcs);
+        self
+    }
+
     /// Write the `HT` attribute to set the halftone.
+    ///
+    /// The reference may point to a [screen](ScreenHalftone),
+    /// [composite](CompositeHalftone) or [threshold](ThresholdHalftone)
+    /// halftone.
     pub fn halftone(&mut self, ht: Ref) -> &mut Self {
         self.pair(Name(b"HT"), ht);
         self
@@ -1410,6 +1858,18 @@ impl<'a> ExtGraphicsState<'a> {
         self
     }
 
+    /// Start writing the `HT` attribute as a direct type 1 halftone
+    /// dictionary.
+    pub fn screen_halftone(&mut self) -> ScreenHalftone<'_> {
+        self.insert(Name(b"HT")).start()
+    }
+
+    /// Start writing the `HT` attribute as a direct type 5 halftone
+    /// dictionary.
+    pub fn composite_halftone(&mut self) -> CompositeHalftone<'_> {
+        self.insert(Name(b"HT")).start()
+    }
+
     /// Write the `FL` attribute to set the flatness tolerance. PDF 1.3+.
     pub fn flatness(&mut self, tolerance: f32) -> &mut Self {
         self.pair(Name(b"FL"), tolerance);
@@ -1606,6 +2066,258 @@ impl MaskType {
     }
 }
 
+/// Writer for a _type 1 halftone dictionary_.
+///
+/// This struct is created by [`Chunk::screen_halftone`],
+/// [`ExtGraphicsState::screen_halftone`] and [`CompositeHalftone::colorant`].
+/// It defines a halftone screen through its frequency, angle and spot
+/// function.
+pub struct ScreenHalftone<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(ScreenHalftone: |obj| {
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Type"), Name(b"Halftone"));
+    dict.pair(Name(b"HalftoneType"), 1);
+    Self { dict }
+});
+
+impl<'a> ScreenHalftone<'a> {
+    /// Write the `/HalftoneName` attribute to name the halftone.
+    pub fn halftone_name(&mut self, name: Str) -> &mut Self {
+        self.pair(Name(b"HalftoneName"), name);
+        self
+    }
+
+    /// Write the `/Frequency` attribute to set the screen frequency in halftone
+    /// cells per inch. Required.
+    pub fn frequency(&mut self, frequency: f32) -> &mut Self {
+        self.pair(Name(b"Frequency"), frequency);
+        self
+    }
+
+    /// Write the `/Angle` attribute to set the screen angle in degrees
+    /// counterclockwise relative to the device coordinate system. Required.
+    pub fn angle(&mut self, angle: f32) -> &mut Self {
+        self.pair(Name(b"Angle"), angle);
+        self
+    }
+
+    /// Write the `/SpotFunction` attribute to use a predefined spot function.
+    /// Required if no [custom spot function](Self::spot_function_custom) is
+    /// written.
+    pub fn spot_function(&mut self, func: SpotFunction) -> &mut Self {
+        self.pair(Name(b"SpotFunction"), func.to_name());
+        self
+    }
+
+    /// Write the `/SpotFunction` attribute to reference a function that maps
+    /// positions in a halftone cell to their priority.
+    pub fn spot_function_custom(&mut self, func: Ref) -> &mut Self {
+        self.pair(Name(b"SpotFunction"), func);
+        self
+    }
+
+    /// Write the `/AccurateScreens` attribute to request a more precise but
+    /// potentially slower rendering of the screen.
+    ///
+    /// _Default value_: `false`.
+    pub fn accurate_screens(&mut self, accurate: bool) -> &mut Self {
+        self.pair(Name(b"AccurateScreens"), accurate);
+        self
+    }
+
+    /// Write the `/TransferFunction` attribute to override the transfer
+    /// function of the graphics state for this halftone. Only permissible
+    /// for the components of a [composite halftone](CompositeHalftone).
+    pub fn transfer_function(&mut self, func: Ref) -> &mut Self {
+        self.pair(Name(b"TransferFunction"), func);
+        self
+    }
+}
+
+deref!('a, ScreenHalftone<'a> => Dict<'a>, dict);
+
+/// Writer for a _type 5 halftone dictionary_.
+///
+/// This struct is created by [`Chunk::composite_halftone`] and
+/// [`ExtGraphicsState::composite_halftone`]. It defines separate halftones
+/// for individual colorants.
+pub struct CompositeHalftone<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(CompositeHalftone: |obj| {
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Type"), Name(b"Halftone"));
+    dict.pair(Name(b"HalftoneType"), 5);
+    Self { dict }
+});
+
+impl<'a> CompositeHalftone<'a> {
+    /// Write the `/HalftoneName` attribute to name the halftone.
+    pub fn halftone_name(&mut self, name: Str) -> &mut Self {
+        self.pair(Name(b"HalftoneName"), name);
+        self
+    }
+
+    /// Start writing the screen halftone for a colorant. The name may be a
+    /// device colorant like `Cyan` or a [separation](Separation) colorant.
+    pub fn colorant(&mut self, name: Name) -> ScreenHalftone<'_> {
+        self.insert(name).start()
+    }
+
+    /// Write a reference to a halftone for a colorant. This is required for
+    /// [threshold halftones](ThresholdHalftone) as they are streams.
+    pub fn colorant_ref(&mut self, name: Name, ht: Ref) -> &mut Self {
+        self.pair(name, ht);
+        self
+    }
+
+    /// Start writing the `/Default` screen halftone for all colorants without
+    /// a dedicated halftone. Required.
+    pub fn default_halftone(&mut self) -> ScreenHalftone<'_> {
+        self.colorant(Name(b"Default"))
+    }
+}
+
+deref!('a, CompositeHalftone<'a> => Dict<'a>, dict);
+
+/// Writer for a _type 6, 10 or 16 halftone stream_.
+///
+/// This struct is created by [`Chunk::threshold_halftone`]. The stream contains
+/// a threshold array with one byte or, for type 16, two bytes per cell.
+pub struct ThresholdHalftone<'a> {
+    stream: Stream<'a>,
+}
+
+impl<'a> ThresholdHalftone<'a> {
+    /// Create a new threshold halftone writer.
+    pub(crate) fn start(mut stream: Stream<'a>, kind: ThresholdHalftoneType) -> Self {
+        stream.pair(Name(b"Type"), Name(b"Halftone"));
+        stream.pair(Name(b"HalftoneType"), kind.to_int());
+        Self { stream }
+    }
+
+    /// Write the `/HalftoneName` attribute to name the halftone.
+    pub fn halftone_name(&mut self, name: Str) -> &mut Self {
+        self.pair(Name(b"HalftoneName"), name);
+        self
+    }
+
+    /// Write the `/Width` and `/Height` attributes to set the size of the
+    /// threshold array. Required for types 6 and 16.
+    pub fn size(&mut self, width: i32, height: i32) -> &mut Self {
+        self.pair(Name(b"Width"), width);
+        self.pair(Name(b"Height"), height);
+        self
+    }
+
+    /// Write the `/Width2` and `/Height2` attributes to set the size of a
+    /// second rectangle in the threshold array. Only permissible for type 16.
+    pub fn size_2(&mut self, width: i32, height: i32) -> &mut Self {
+        self.pair(Name(b"Width2"), width);
+        self.pair(Name(b"Height2"), height);
+        self
+    }
+
+    /// Write the `/Xsquare` and `/Ysquare` attributes to set the side lengths
+    /// of the two squares making up an angled threshold array. Required for
+    /// type 10.
+    pub fn squares(&mut self, x: i32, y: i32) -> &mut Self {
+        self.pair(Name(b"Xsquare"), x);
+        self.pair(Name(b"Ysquare"), y);
+        self
+    }
+
+    /// Write the `/TransferFunction` attribute to override the transfer
+    /// function of the graphics state for this halftone. Only permissible
+    /// for the components of a [composite halftone](CompositeHalftone).
+    pub fn transfer_function(&mut self, func: Ref) -> &mut Self {
+        self.pair(Name(b"TransferFunction"), func);
+        self
+    }
+}
+
+deref!('a, ThresholdHalftone<'a> => Stream<'a>, stream);
+
+/// The layout of a [threshold halftone](ThresholdHalftone).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ThresholdHalftoneType {
+    /// A rectangular array of 8-bit thresholds (type 6).
+    Rectangle,
+    /// Two squares of 8-bit thresholds for angled screens (type 10).
+    Angled,
+    /// One or two rectangles of 16-bit thresholds (type 16).
+    Rectangle16,
+}
+
+impl ThresholdHalftoneType {
+    pub(crate) fn to_int(self) -> i32 {
+        match self {
+            Self::Rectangle => 6,
+            Self::Angled => 10,
+            Self::Rectangle16 => 16,
+        }
+    }
+}
+
+/// A predefined spot function for a [screen halftone](ScreenHalftone).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[allow(missing_docs)]
+pub enum SpotFunction {
+    SimpleDot,
+    InvertedSimpleDot,
+    DoubleDot,
+    InvertedDoubleDot,
+    CosineDot,
+    Double,
+    InvertedDouble,
+    Line,
+    LineX,
+    LineY,
+    Round,
+    Ellipse,
+    EllipseA,
+    InvertedEllipseA,
+    EllipseB,
+    EllipseC,
+    InvertedEllipseC,
+    Square,
+    Cross,
+    Rhomboid,
+    Diamond,
+}
+
+impl SpotFunction {
+    pub(crate) fn to_name(self) -> Name<'static> {
+        match self {
+            Self::SimpleDot => Name(b"SimpleDot"),
+            Self::InvertedSimpleDot => Name(b"InvertedSimpleDot"),
+            Self::DoubleDot => Name(b"DoubleDot"),
+            Self::InvertedDoubleDot => Name(b"InvertedDoubleDot"),
+            Self::CosineDot => Name(b"CosineDot"),
+            Self::Double => Name(b"Double"),
+            Self::InvertedDouble => Name(b"InvertedDouble"),
+            Self::Line => Name(b"Line"),
+            Self::LineX => Name(b"LineX"),
+            Self::LineY => Name(b"LineY"),
+            Self::Round => Name(b"Round"),
+            Self::Ellipse => Name(b"Ellipse"),
+            Self::EllipseA => Name(b"EllipseA"),
+            Self::InvertedEllipseA => Name(b"InvertedEllipseA"),
+            Self::EllipseB => Name(b"EllipseB"),
+            Self::EllipseC => Name(b"EllipseC"),
+            Self::InvertedEllipseC => Name(b"InvertedEllipseC"),
+            Self::Square => Name(b"Square"),
+            Self::Cross => Name(b"Cross"),
+            Self::Rhomboid => Name(b"Rhomboid"),
+            Self::Diamond => Name(b"Diamond"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1617,7 +2329,7 @@ mod tests {
             .save_state()
             .rect(1.0, 2.0, 3.0, 4.0)
             .fill_nonzero()
-            .set_dash_pattern([7.0, 2.0], 4.0)
+            .set_dash_pattern(DashPattern::new(&[7.0, 2.0], 4.0))
             .x_object(Name(b"MyImage"))
             .set_fill_pattern([2.0, 3.5], Name(b"MyPattern"))
             .restore_state();
@@ -1628,6 +2340,63 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_content_shapes() {
+        let mut content = Content::new();
+        content.circle(0.0, 0.0, 10.0);
+        content.rounded_rect(0.0, 0.0, 20.0, 10.0, 10.0);
+        assert_eq!(
+            std::str::from_utf8(&content.finish()).unwrap(),
+            "10 0 m\n\
+             10 5.5228477 5.5228477 10 0 10 c\n\
+             -5.5228477 10 -10 5.5228477 -10 0 c\n\
+             -10 -5.5228477 -5.5228477 -10 0 -10 c\n\
+             5.5228477 -10 10 -5.5228477 10 0 c\n\
+             h\n\
+             5 0 m\n\
+             15 0 l\n\
+             17.761423 0 20 2.2385762 20 5 c\n\
+             20 5 l\n\
+             20 7.761424 17.761423 10 15 10 c\n\
+             5 10 l\n\
+             2.2385762 10 0 7.761424 0 5 c\n\
+             0 5 l\n\
+             0 2.2385762 2.2385762 0 5 0 c\n\
+             h"
+        );
+
+        let mut content = Content::new();
+        content.arc(0.0, 0.0, 1.0, 0.0, std::f32::consts::PI * 1.5);
+        let buf = content.finish();
+        assert!(buf.starts_with(b"1 0 m\n1 0.5522848 "));
+        assert_eq!(buf.iter().filter(|&&b| b == b'c').count(), 3);
+    }
+
+    #[test]
+    fn test_content_stroke_params() {
+        let mut content = Content::new();
+        content
+            .set_line_cap(LineCapStyle::RoundCap)
+            .set_line_join(LineJoinStyle::BevelJoin)
+            .set_miter_limit(4.0);
+        assert_eq!(content.finish(), b"1 J\n2 j\n4 M");
+
+        test_obj!(
+            |obj| {
+                obj.start::<ExtGraphicsState>()
+                    .line_cap(LineCapStyle::ProjectingSquareCap)
+                    .line_join(LineJoinStyle::RoundJoin)
+                    .miter_limit(2.5);
+            },
+            b"<<",
+            b"  /Type /ExtGState",
+            b"  /LC 2",
+            b"  /LJ 1",
+            b"  /ML 2.5",
+            b">>",
+        );
+    }
+
     #[test]
     fn test_content_text() {
         let mut content = Content::new();
@@ -1645,4 +2414,244 @@ mod tests {
 
         assert_eq!(content.finish(), b"/F1 12 Tf\nBT\n[] TJ\n[(AB) 2 (CD)] TJ\nET");
     }
+
+    #[test]
+    fn test_content_path_painting() {
+        let mut content = Content::new();
+        content
+            .stroke()
+            .close_and_stroke()
+            .fill_nonzero()
+            .fill_even_odd()
+            .fill_nonzero_and_stroke()
+            .fill_even_odd_and_stroke()
+            .close_fill_nonzero_and_stroke()
+            .close_fill_even_odd_and_stroke()
+            .clip_nonzero()
+            .clip_even_odd()
+            .end_path();
+
+        assert_eq!(content.finish(), b"S\ns\nf\nf*\nB\nB*\nb\nb*\nW\nW*\nn");
+    }
+
+    #[test]
+    fn test_content_draw_image() {
+        let mut content = Content::new();
+        content.draw_image(Name(b"Im1"), 10.0, 20.0, 200.0, 100.0);
+        assert_eq!(content.finish(), b"q\n200 0 0 100 10 20 cm\n/Im1 Do\nQ");
+    }
+
+    #[test]
+    fn test_content_show_glyphs() {
+        let mut content = Content::new();
+        content.show_glyphs([(1, 0.0), (2, -50.0), (3, 0.0), (0x2929, 0.0)]);
+        content.show_glyphs([]);
+        assert_eq!(
+            content.finish(),
+            b"[(\\000\\001\\000\\002) -50 (\\000\\003\\)\\))] TJ\n[] TJ"
+        );
+    }
+
+    #[test]
+    fn test_content_actual_text() {
+        let mut content = Content::new();
+        content.show_with_actual_text(Str(b"\x01"), TextStr("fi"));
+        assert_eq!(
+            content.finish(),
+            b"/Span <<\n  /ActualText (fi)\n>> BDC\n(\\001) Tj\nEMC"
+        );
+    }
+
+    #[test]
+    fn test_content_accessibility_properties() {
+        let mut content = Content::new();
+        content
+            .begin_marked_content_with_properties(Name(b"Span"))
+            .properties()
+            .lang(TextStr("en-US"))
+            .alt(TextStr("Logo"))
+            .expanded(TextStr("Portable Document Format"));
+        content.show(Str(b"PDF")).end_marked_content();
+        assert_eq!(
+            content.finish(),
+            b"/Span <<\n  /Lang (en-US)\n  /Alt (Logo)\n  \
+              /E (Portable Document Format)\n>> BDC\n(PDF) Tj\nEMC"
+        );
+    }
+
+    #[test]
+    fn test_content_artifact() {
+        let mut content = Content::new();
+        content.artifact(
+            ArtifactType::Pagination,
+            Some(ArtifactSubtype::Footer),
+            Some(Rect::new(0.0, 0.0, 100.0, 20.0)),
+            |content| {
+                content.move_to(0.0, 20.0).line_to(100.0, 20.0).stroke();
+            },
+        );
+        assert_eq!(
+            content.finish(),
+            b"/Artifact <<\n  /Type /Pagination\n  /Subtype /Footer\n  \
+              /BBox [0 0 100 20]\n>> BDC\n0 20 m\n100 20 l\nS\nEMC"
+        );
+    }
+
+    #[test]
+    fn test_content_draw_appearance() {
+        let mut content = Content::new();
+        content.draw_appearance(
+            Name(b"Fm1"),
+            Rect::new(0.0, 0.0, 50.0, 10.0),
+            Some([0.0, 1.0, -1.0, 0.0, 10.0, 0.0]),
+            Rect::new(100.0, 100.0, 120.0, 200.0),
+        );
+        assert_eq!(content.finish(), b"q\n2 0 0 2 100 100 cm\n/Fm1 Do\nQ");
+    }
+
+    #[test]
+    fn test_content_device_color() {
+        let mut content = Content::new();
+        content
+            .set_fill_device_color(Color::Gray(0.5))
+            .set_stroke_device_color(Color::Rgb(1.0, 0.0, 0.0))
+            .set_fill_device_color(Color::Cmyk(0.0, 0.0, 0.0, 1.0));
+        assert_eq!(content.finish(), b"0.5 g\n1 0 0 RG\n0 0 0 1 k");
+    }
+
+    #[test]
+    fn test_content_named_color() {
+        let mut content = Content::new();
+        content
+            .set_fill_device_color(Color::Lab {
+                space: Name(b"L0"),
+                l: 50.0,
+                a: 10.0,
+                b: -5.0,
+            })
+            .set_stroke_device_color(Color::Indexed {
+                space: Name(b"I0"),
+                index: 3,
+                rgb: [0.0; 3],
+            })
+            .set_fill_device_color(Color::Named {
+                space: Name(b"S0"),
+                tint: 0.5,
+                rgb: [1.0, 0.0, 0.0],
+            });
+        assert_eq!(
+            content.finish(),
+            b"/L0 cs\n50 10 -5 scn\n/I0 CS\n3 SCN\n/S0 cs\n0.5 scn"
+        );
+    }
+
+    #[test]
+    fn test_content_patterns() {
+        let mut content = Content::new();
+        content
+            .set_fill_color_space(ColorSpaceOperand::Pattern)
+            .set_fill_pattern(None, Name(b"P1"))
+            .set_stroke_color_space(Name(b"Cs1"))
+            .set_stroke_pattern([0.5], Name(b"P2"))
+            .set_stroke_color([1.0])
+            .shading(Name(b"Sh1"));
+
+        assert_eq!(
+            content.finish(),
+            b"/Pattern cs\n/P1 scn\n/Cs1 CS\n0.5 /P2 SCN\n1 SCN\n/Sh1 sh"
+        );
+    }
+
+    #[test]
+    fn test_content_nesting() {
+        let mut content = Content::new();
+        content.save_state().save_state();
+        assert_eq!(content.state_depth(), 2);
+        content.restore_state().begin_text();
+        assert_eq!(content.state_depth(), 1);
+        assert!(content.in_text());
+        content.end_text().restore_state();
+        assert_eq!(content.state_depth(), 0);
+        assert!(!content.in_text());
+        assert!(content.is_balanced());
+        assert_eq!(content.finish(), b"q\nq\nQ\nBT\nET\nQ");
+    }
+
+    #[test]
+    fn test_content_unbalanced() {
+        let mut content = Content::new();
+        content.save_state().begin_text();
+        assert!(!content.is_balanced());
+        content.end_text().end_text().restore_state().restore_state();
+        assert_eq!(content.state_depth(), 0);
+        assert!(!content.in_text());
+        assert!(!content.is_balanced());
+        assert_eq!(content.finish_checked(), None);
+
+        let mut content = Content::new();
+        content.begin_text().begin_text().end_text();
+        assert!(!content.is_balanced());
+
+        let mut content = Content::new();
+        content.save_state().begin_text().end_text().restore_state();
+        assert_eq!(content.finish_checked().as_deref(), Some(&b"q\nBT\nET\nQ"[..]));
+    }
+
+    #[test]
+    fn test_halftones() {
+        let mut c = Chunk::new();
+        let mut gs = c.ext_graphics(Ref::new(1));
+        gs.transfer_components([Ref::new(2), Ref::new(3), Ref::new(4), Ref::new(5)])
+            .black_generation_2(Ref::new(6))
+            .flatness(0.5);
+        let mut ht = gs.composite_halftone();
+        ht.default_halftone()
+            .frequency(60.0)
+            .angle(45.0)
+            .spot_function(SpotFunction::Round);
+        ht.colorant_ref(Name(b"Black"), Ref::new(7));
+        ht.finish();
+        gs.finish();
+        c.threshold_halftone(
+            Ref::new(7),
+            &[0, 128, 64, 255],
+            ThresholdHalftoneType::Rectangle,
+        )
+        .size(2, 2);
+        test!(
+            c.as_bytes(),
+            b"1 0 obj",
+            b"<<",
+            b"  /Type /ExtGState",
+            b"  /TR [2 0 R 3 0 R 4 0 R 5 0 R]",
+            b"  /BG2 6 0 R",
+            b"  /FL 0.5",
+            b"  /HT <<",
+            b"    /Type /Halftone",
+            b"    /HalftoneType 5",
+            b"    /Default <<",
+            b"      /Type /Halftone",
+            b"      /HalftoneType 1",
+            b"      /Frequency 60",
+            b"      /Angle 45",
+            b"      /SpotFunction /Round",
+            b"    >>",
+            b"    /Black 7 0 R",
+            b"  >>",
+            b">>",
+            b"endobj\n",
+            b"7 0 obj",
+            b"<<",
+            b"  /Length 4",
+            b"  /Type /Halftone",
+            b"  /HalftoneType 6",
+            b"  /Width 2",
+            b"  /Height 2",
+            b">>",
+            b"stream",
+            b"\x00\x80\x40\xff",
+            b"endstream",
+            b"endobj\n\n",
+        );
+    }
 }
@@ -0,0 +1,269 @@
+use super::*;
+
+/// A high-level builder for a simple PDF document.
+///
+/// This wraps a [`Pdf`] and takes care of allocating indirect reference ids as
+/// well as of writing the document catalog and the page tree. Pages are added
+/// through [`PageBuilder`]s, which collect the page's content and resources.
+///
+/// For everything that isn't covered, the underlying [`Pdf`] is available
+/// through [`pdf`](Self::pdf) and [`alloc`](Self::alloc) hands out fresh ids
+/// that don't collide with the ones used internally.
+///
+/// ```
+/// use pdf_writer::{Document, Finish, Name, Rect, Str};
+///
+/// let mut doc = Document::new();
+/// let font_id = doc.alloc();
+/// doc.pdf().type1_font(font_id).base_font(Name(b"Helvetica"));
+///
+/// let mut page = doc.page(Rect::new(0.0, 0.0, 595.0, 842.0));
+/// page.font(Name(b"F1"), font_id);
+/// page.content()
+///     .begin_text()
+///     .set_font(Name(b"F1"), 14.0)
+///     .next_line(108.0, 734.0)
+///     .show(Str(b"Hello World!"))
+///     .end_text();
+/// page.finish();
+///
+/// let bytes = doc.finish();
+/// ```
+pub struct Document {
+    pdf: Pdf,
+    alloc: Ref,
+    catalog_id: Ref,
+    page_tree_id: Ref,
+    pages: Vec<Ref>,
+}
+
+impl Document {
+    /// Create a new, empty document.
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        let mut alloc = Ref::new(1);
+        let catalog_id = alloc.bump();
+        let page_tree_id = alloc.bump();
+        Self {
+            pdf: Pdf::new(),
+            alloc,
+            catalog_id,
+            page_tree_id,
+            pages: vec![],
+        }
+    }
+
+    /// Allocate a new indirect reference id.
+    pub fn alloc(&mut self) -> Ref {
+        self.alloc.bump()
+    }
+
+    /// The underlying PDF writer, to write arbitrary objects.
+    pub fn pdf(&mut self) -> &mut Pdf {
+        &mut self.pdf
+    }
+
+    /// The id of the document catalog.
+    pub fn catalog_id(&self) -> Ref {
+        self.catalog_id
+    }
+
+    /// The id of the page tree, which all pages are children of.
+    pub fn page_tree_id(&self) -> Ref {
+        self.page_tree_id
+    }
+
+    /// The ids of the pages written so far.
+    pub fn pages(&self) -> &[Ref] {
+        &self.pages
+    }
+
+    /// Start building a new page with the given media box.
+    ///
+    /// The page is written when the builder is dropped.
+    pub fn page(&mut self, media_box: Rect) -> PageBuilder<'_> {
+        let id = self.alloc();
+        self.pages.push(id);
+        PageBuilder {
+            doc: self,
+            id,
+            media_box,
+            content: Content::new(),
+            fonts: vec![],
+            x_objects: vec![],
+            ext_g_states: vec![],
+        }
+    }
+
+    /// Write the document catalog, the page tree, the cross-reference table
+    /// and the file trailer and return the underlying buffer.
+    pub fn finish(self) -> Vec<u8> {
+        self.finish_with(|_| {})
+    }
+
+    /// Like [`finish`](Self::finish), but allows to write additional entries
+    /// into the document catalog.
+    pub fn finish_with<F>(mut self, f: F) -> Vec<u8>
+    where
+        F: FnOnce(&mut Catalog),
+    {
+        let mut catalog = self.pdf.catalog(self.catalog_id);
+        catalog.pages(self.page_tree_id);
+        f(&mut catalog);
+        catalog.finish();
+
+        self.pdf
+            .pages(self.page_tree_id)
+            .kids(self.pages.iter().copied())
+            .count(self.pages.len() as i32);
+
+        self.pdf.finish()
+    }
+}
+
+impl Debug for Document {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad("Document(..)")
+    }
+}
+
+/// Builder for a page in a [`Document`].
+///
+/// This struct is created by [`Document::page`]. It collects the page's
+/// content stream and resources and writes the page and its content when
+/// dropped.
+pub struct PageBuilder<'a> {
+    doc: &'a mut Document,
+    id: Ref,
+    media_box: Rect,
+    content: Content,
+    fonts: Vec<(Vec<u8>, Ref)>,
+    x_objects: Vec<(Vec<u8>, Ref)>,
+    ext_g_states: Vec<(Vec<u8>, Ref)>,
+}
+
+impl PageBuilder<'_> {
+    /// The id of the page.
+    pub fn id(&self) -> Ref {
+        self.id
+    }
+
+    /// Allocate a new indirect reference id.
+    pub fn alloc(&mut self) -> Ref {
+        self.doc.alloc()
+    }
+
+    /// The underlying PDF writer, for example to write an image while building
+    /// the page.
+    pub fn pdf(&mut self) -> &mut Pdf {
+        self.doc.pdf()
+    }
+
+    /// The page's content stream.
+    pub fn content(&mut self) -> &mut Content {
+        &mut self.content
+    }
+
+    /// Make a font available to the content stream under the given name.
+    pub fn font(&mut self, name: Name, id: Ref) -> &mut Self {
+        self.fonts.push((name.0.to_vec(), id));
+        self
+    }
+
+    /// Make an XObject available to the content stream under the given name.
+    pub fn x_object(&mut self, name: Name, id: Ref) -> &mut Self {
+        self.x_objects.push((name.0.to_vec(), id));
+        self
+    }
+
+    /// Make a graphics state parameter dictionary available to the content
+    /// stream under the given name.
+    pub fn ext_g_state(&mut self, name: Name, id: Ref) -> &mut Self {
+        self.ext_g_states.push((name.0.to_vec(), id));
+        self
+    }
+}
+
+impl Drop for PageBuilder<'_> {
+    fn drop(&mut self) {
+        let content_id = self.doc.alloc();
+        let content = core::mem::replace(&mut self.content, Content::new()).finish();
+
+        let mut page = self.doc.pdf.page(self.id);
+        page.parent(self.doc.page_tree_id);
+        page.media_box(self.media_box);
+        page.contents(content_id);
+
+        let mut resources = page.resources();
+        for (key, entries) in [
+            (Name(b"Font"), &self.fonts),
+            (Name(b"XObject"), &self.x_objects),
+            (Name(b"ExtGState"), &self.ext_g_states),
+        ] {
+            if !entries.is_empty() {
+                resources
+                    .insert(key)
+                    .dict()
+                    .pairs(entries.iter().map(|(name, id)| (Name(name), *id)));
+            }
+        }
+
+        resources.finish();
+        page.finish();
+
+        self.doc.pdf.stream(content_id, &content);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document() {
+        let mut doc = Document::new();
+        let image_id = doc.alloc();
+        let mut page = doc.page(Rect::new(0.0, 0.0, 10.0, 10.0));
+        page.x_object(Name(b"Im1"), image_id);
+        page.content().x_object(Name(b"Im1"));
+        page.finish();
+        test!(
+            doc.finish(),
+            b"%PDF-1.7\n%\x80\x80\x80\x80\n",
+            b"4 0 obj",
+            b"<<",
+            b"  /Type /Page",
+            b"  /Parent 2 0 R",
+            b"  /MediaBox [0 0 10 10]",
+            b"  /Contents 5 0 R",
+            b"  /Resources <<",
+            b"    /XObject <<",
+            b"      /Im1 3 0 R",
+            b"    >>",
+            b"  >>",
+            b">>",
+            b"endobj\n",
+            b"5 0 obj",
+            b"<<\n  /Length 7\n>>",
+            b"stream\n/Im1 Do\nendstream",
+            b"endobj\n",
+            b"1 0 obj",
+            b"<<\n  /Type /Catalog\n  /Pages 2 0 R\n>>",
+            b"endobj\n",
+            b"2 0 obj",
+            b"<<\n  /Type /Pages\n  /Kids [4 0 R]\n  /Count 1\n>>",
+            b"endobj\n",
+            b"xref",
+            b"0 6",
+            b"0000000003 65535 f\r",
+            b"0000000230 00000 n\r",
+            b"0000000284 00000 n\r",
+            b"0000000000 00000 f\r",
+            b"0000000016 00000 n\r",
+            b"0000000171 00000 n\r",
+            b"trailer",
+            b"<<\n  /Size 6\n  /Root 1 0 R\n>>",
+            b"startxref\n348\n%%EOF",
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use super::*;
+use crate::types::Permissions;
+
+/// The padding string of the standard security handler.
+const PADDING: [u8; 32] = [
+    0x28, 0xBF, 0x4E, 0x5E, 0x4E, 0x75, 0x8A, 0x41, 0x64, 0x00, 0x4E, 0x56, 0xFF, 0xFA,
+    0x01, 0x08, 0x2E, 0x2E, 0x00, 0xB6, 0xD0, 0x68, 0x3E, 0x80, 0x2F, 0x0C, 0xA9, 0xFE,
+    0x64, 0x53, 0x69, 0x7A,
+];
+
+/// Write a standard security handler encryption dictionary that restricts the
+/// `permissions` of a document without a user password.
+///
+/// The crypt filters are `Identity`, so strings and streams stay unencrypted
+/// and the document opens without a password. Only the `/O` and `/U` entries
+/// are computed, according to revision 4 of the handler with a 128-bit key.
+pub(crate) fn write_restrictions(
+    obj: Obj,
+    owner_password: &[u8],
+    permissions: Permissions,
+    file_id: &[u8],
+) {
+    let p = permissions.to_p_value();
+    let owner = owner_value(owner_password);
+    let user = user_value(&owner, p, file_id);
+
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Filter"), Name(b"Standard"));
+    dict.pair(Name(b"V"), 4);
+    dict.pair(Name(b"R"), 4);
+    dict.pair(Name(b"Length"), 128);
+    dict.pair(Name(b"StmF"), Name(b"Identity"));
+    dict.pair(Name(b"StrF"), Name(b"Identity"));
+    dict.pair(Name(b"O"), Str(&owner));
+    dict.pair(Name(b"U"), Str(&user));
+    dict.pair(Name(b"P"), p);
+    dict.pair(Name(b"EncryptMetadata"), false);
+}
+
+/// Compute the `/O` entry from the owner password and the empty user password
+/// (Algorithm 3 of ISO 32000-1).
+fn owner_value(owner_password: &[u8]) -> [u8; 32] {
+    let mut key = md5(&pad(owner_password));
+    for _ in 0..50 {
+        key = md5(&key);
+    }
+
+    let mut value = PADDING;
+    for i in 0..20 {
+        let round_key = key.map(|byte| byte ^ i);
+        rc4(&round_key, &mut value);
+    }
+    value
+}
+
+/// Compute the `/U` entry for the empty user password (Algorithms 2 and 5 of
+/// ISO 32000-1).
+fn user_value(owner: &[u8; 32], p: i32, file_id: &[u8]) -> [u8; 32] {
+    let mut input = PADDING.to_vec();
+    input.extend(owner);
+    input.extend(p.to_le_bytes());
+    input.extend(file_id);
+    input.extend([0xFF; 4]);
+
+    let mut key = md5(&input);
+    for _ in 0..50 {
+        key = md5(&key);
+    }
+
+    let mut input = PADDING.to_vec();
+    input.extend(file_id);
+    let mut hash = md5(&input);
+    for i in 0..20 {
+        let round_key = key.map(|byte| byte ^ i);
+        rc4(&round_key, &mut hash);
+    }
+
+    let mut value = [0; 32];
+    value[..16].copy_from_slice(&hash);
+    value
+}
+
+/// Pad or truncate a password to 32 bytes.
+fn pad(password: &[u8]) -> [u8; 32] {
+    let mut padded = PADDING;
+    let len = password.len().min(32);
+    padded[..len].copy_from_slice(&password[..len]);
+    padded[len..].copy_from_slice(&PADDING[..32 - len]);
+    padded
+}
+
+/// Encrypt or decrypt data in place with the RC4 stream cipher.
+fn rc4(key: &[u8], data: &mut [u8]) {
+    let mut state: [u8; 256] = core::array::from_fn(|i| i as u8);
+    let mut j = 0u8;
+    for i in 0..256 {
+        j = j.wrapping_add(state[i]).wrapping_add(key[i % key.len()]);
+        state.swap(i, usize::from(j));
+    }
+
+    let (mut i, mut j) = (0u8, 0u8);
+    for byte in data {
+        i = i.wrapping_add(1);
+        j = j.wrapping_add(state[usize::from(i)]);
+        state.swap(usize::from(i), usize::from(j));
+        let k =
+            state[usize::from(state[usize::from(i)].wrapping_add(state[usize::from(j)]))];
+        *byte ^= k;
+    }
+}
+
+/// Compute the MD5 digest of some data.
+pub(crate) fn md5(data: &[u8]) -> [u8; 16] {
+    const SHIFTS: [u32; 64] = [
+        7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 5, 9, 14, 20, 5, 9,
+        14, 20, 5, 9, 14, 20, 5, 9, 14, 20, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23,
+        4, 11, 16, 23, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+    ];
+    const CONSTANTS: [u32; 64] = [
+        0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a,
+        0xa8304613, 0xfd469501, 0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be,
+        0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821, 0xf61e2562, 0xc040b340,
+        0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+        0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8,
+        0x676f02d9, 0x8d2a4c8a, 0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c,
+        0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70, 0x289b7ec6, 0xeaa127fa,
+        0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+        0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92,
+        0xffeff47d, 0x85845dd1, 0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1,
+        0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+    ];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend((data.len() as u64).wrapping_mul(8).to_le_bytes());
+
+    let mut state: [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+    for block in message.chunks_exact(64) {
+        let words: [u32; 16] = core::array::from_fn(|i| {
+            u32::from_le_bytes(block[4 * i..4 * i + 4].try_into().unwrap())
+        });
+
+        let [mut a, mut b, mut c, mut d] = state;
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+            let sum = a.wrapping_add(f).wrapping_add(CONSTANTS[i]).wrapping_add(words[g]);
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(sum.rotate_left(SHIFTS[i]));
+        }
+
+        for (value, add) in state.iter_mut().zip([a, b, c, d]) {
+            *value = value.wrapping_add(add);
+        }
+    }
+
+    let mut digest = [0; 16];
+    for (i, value) in state.iter().enumerate() {
+        digest[4 * i..4 * i + 4].copy_from_slice(&value.to_le_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_md5() {
+        let hex = |digest: [u8; 16]| {
+            digest.iter().map(|b| format!("{b:02x}")).collect::<String>()
+        };
+        assert_eq!(hex(md5(b"")), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(hex(md5(b"abc")), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(hex(md5(&[b'a'; 100])), "36a92cc94a9e0fa21f625f8bfb007adf");
+    }
+
+    #[test]
+    fn test_rc4() {
+        let mut data = *b"Plaintext";
+        rc4(b"Key", &mut data);
+        assert_eq!(data, [0xBB, 0xF3, 0x16, 0xE8, 0xD9, 0x40, 0xAF, 0x0A, 0xD3]);
+    }
+}
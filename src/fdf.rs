@@ -0,0 +1,278 @@
+use super::*;
+use crate::types::FieldFlags;
+
+/// A builder for an FDF file.
+///
+/// Forms Data Format (FDF) files carry the values of interactive form fields
+/// separately from the PDF they belong to. They can, for example, be generated
+/// on a server to pre-fill the [fields](Field) of an existing form or be
+/// submitted by a viewer.
+///
+/// Like a [`Pdf`], this derefs to a [`Chunk`] for writing indirect objects.
+/// The FDF file consists of a single [catalog](Self::catalog) and,
+/// optionally, further objects referenced by it.
+pub struct Fdf {
+    chunk: Chunk,
+    catalog_id: Option<Ref>,
+}
+
+impl Fdf {
+    /// Create a new FDF file with the default buffer capacity (currently 1
+    /// KB).
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self::with_capacity(1024)
+    }
+
+    /// Create a new FDF file with the specified initial buffer capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut chunk = Chunk::with_capacity(capacity);
+        chunk.buf.extend(b"%FDF-1.2\n%\x80\x80\x80\x80\n\n");
+        Self { chunk, catalog_id: None }
+    }
+
+    /// Start writing the FDF catalog. Required.
+    ///
+    /// This will also register the catalog with the file trailer.
+    pub fn catalog(&mut self, id: Ref) -> FdfCatalog<'_> {
+        self.catalog_id = Some(id);
+        self.indirect(id).start()
+    }
+
+    /// Write the file trailer and return the underlying buffer.
+    ///
+    /// FDF files do not need a cross-reference table, so none is written.
+    pub fn finish(self) -> Vec<u8> {
+        let mut buf = self.chunk.buf;
+        buf.extend(b"trailer\n");
+        let mut trailer = Obj::direct(&mut buf, 0).dict();
+        if let Some(catalog_id) = self.catalog_id {
+            trailer.pair(Name(b"Root"), catalog_id);
+        }
+        trailer.finish();
+        buf.extend(b"\n%%EOF");
+        buf
+    }
+}
+
+impl Debug for Fdf {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad("Fdf(..)")
+    }
+}
+
+impl Deref for Fdf {
+    type Target = Chunk;
+
+    fn deref(&self) -> &Self::Target {
+        &self.chunk
+    }
+}
+
+impl DerefMut for Fdf {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.chunk
+    }
+}
+
+/// Writer for an _FDF catalog dictionary_.
+///
+/// This struct is created by [`Fdf::catalog`].
+pub struct FdfCatalog<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(FdfCatalog: |obj| Self { dict: obj.dict() });
+
+impl<'a> FdfCatalog<'a> {
+    /// Write the `/Version` attribute to set the FDF version if it is later
+    /// than the one in the file header. PDF 1.4+.
+    pub fn version(&mut self, major: u8, minor: u8) -> &mut Self {
+        if major < 10 && minor < 10 {
+            let chars = [b'0' + major, b'.', b'0' + minor];
+            self.pair(Name(b"Version"), Name(&chars));
+        }
+        self
+    }
+
+    /// Start writing the `/FDF` dictionary. Required.
+    pub fn fdf(&mut self) -> FdfDictionary<'_> {
+        self.insert(Name(b"FDF")).start()
+    }
+}
+
+deref!('a, FdfCatalog<'a> => Dict<'a>, dict);
+
+/// Writer for an _FDF dictionary_.
+///
+/// This struct is created by [`FdfCatalog::fdf`].
+pub struct FdfDictionary<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(FdfDictionary: |obj| Self { dict: obj.dict() });
+
+impl<'a> FdfDictionary<'a> {
+    /// Write the `/F` attribute to set the PDF file this FDF file was
+    /// exported from or is meant to be imported into.
+    pub fn file(&mut self, path: Str) -> &mut Self {
+        self.pair(Name(b"F"), path);
+        self
+    }
+
+    /// Write the `/ID` attribute to set the file identifier of the PDF file.
+    pub fn file_id(&mut self, permanent: Str, changing: Str) -> &mut Self {
+        self.insert(Name(b"ID")).array().item(permanent).item(changing);
+        self
+    }
+
+    /// Start writing the `/Fields` array with the values of the root fields.
+    pub fn fields(&mut self) -> TypedArray<'_, FdfField<'_>> {
+        self.insert(Name(b"Fields")).array().typed()
+    }
+
+    /// Write the `/Status` attribute to set a message the viewer displays
+    /// when importing the FDF file.
+    pub fn status(&mut self, status: TextStr) -> &mut Self {
+        self.pair(Name(b"Status"), status);
+        self
+    }
+
+    /// Write the `/Target` attribute to set the browser frame in which to
+    /// display the [file](Self::file). PDF 1.4+.
+    pub fn target(&mut self, target: Str) -> &mut Self {
+        self.pair(Name(b"Target"), target);
+        self
+    }
+}
+
+deref!('a, FdfDictionary<'a> => Dict<'a>, dict);
+
+/// Writer for an _FDF field dictionary_.
+///
+/// An array of this struct is created by [`FdfDictionary::fields`] and
+/// [`FdfField::kids`]. The fields are matched to the [fields](Field) of the
+/// PDF file by their partial names.
+pub struct FdfField<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(FdfField: |obj| Self { dict: obj.dict() });
+
+impl<'a> FdfField<'a> {
+    /// Write the `/T` attribute to set the partial name of the field. Required.
+    pub fn partial_name(&mut self, name: TextStr) -> &mut Self {
+        self.pair(Name(b"T"), name);
+        self
+    }
+
+    /// Start writing the `/Kids` array with the values of the field's
+    /// children.
+    pub fn kids(&mut self) -> TypedArray<'_, FdfField<'_>> {
+        self.insert(Name(b"Kids")).array().typed()
+    }
+
+    /// Write the `/V` attribute to set the value of a text or single-choice
+    /// field.
+    pub fn text_value(&mut self, value: TextStr) -> &mut Self {
+        self.pair(Name(b"V"), value);
+        self
+    }
+
+    /// Write the `/V` attribute to set the state of a check box or radio
+    /// button, e.g. `Off`.
+    pub fn state_value(&mut self, state: Name) -> &mut Self {
+        self.pair(Name(b"V"), state);
+        self
+    }
+
+    /// Write the `/V` attribute to set the selected options of a
+    /// multiple-choice field.
+    pub fn choice_values<'b>(
+        &mut self,
+        options: impl IntoIterator<Item = TextStr<'b>>,
+    ) -> &mut Self {
+        self.insert(Name(b"V")).array().items(options);
+        self
+    }
+
+    /// Write the `/Ff` attribute to replace the field's flags when importing.
+    pub fn field_flags(&mut self, flags: FieldFlags) -> &mut Self {
+        self.pair(Name(b"Ff"), flags.bits() as i32);
+        self
+    }
+
+    /// Write the `/SetFf` attribute to set individual field flags when
+    /// importing. Ignored if [`field_flags`](Self::field_flags) is present.
+    pub fn set_field_flags(&mut self, flags: FieldFlags) -> &mut Self {
+        self.pair(Name(b"SetFf"), flags.bits() as i32);
+        self
+    }
+
+    /// Write the `/ClrFf` attribute to clear individual field flags when
+    /// importing. Ignored if [`field_flags`](Self::field_flags) is present.
+    pub fn clear_field_flags(&mut self, flags: FieldFlags) -> &mut Self {
+        self.pair(Name(b"ClrFf"), flags.bits() as i32);
+        self
+    }
+}
+
+deref!('a, FdfField<'a> => Dict<'a>, dict);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fdf() {
+        let mut fdf = Fdf::new();
+        let mut catalog = fdf.catalog(Ref::new(1));
+        let mut dict = catalog.fdf();
+        dict.file(Str(b"form.pdf"));
+        let mut fields = dict.fields();
+        fields
+            .push()
+            .partial_name(TextStr("name"))
+            .text_value(TextStr("Jane"));
+        let mut address = fields.push();
+        address.partial_name(TextStr("address"));
+        address
+            .kids()
+            .push()
+            .partial_name(TextStr("city"))
+            .text_value(TextStr("Berlin"));
+        address.finish();
+        fields.push().partial_name(TextStr("agree")).state_value(Name(b"Yes"));
+        fields.finish();
+        dict.finish();
+        catalog.finish();
+
+        test!(
+            fdf.finish(),
+            b"%FDF-1.2\n%\x80\x80\x80\x80\n",
+            b"1 0 obj",
+            b"<<",
+            b"  /FDF <<",
+            b"    /F (form.pdf)",
+            b"    /Fields [<<",
+            b"      /T (name)",
+            b"      /V (Jane)",
+            b"    >> <<",
+            b"      /T (address)",
+            b"      /Kids [<<",
+            b"        /T (city)",
+            b"        /V (Berlin)",
+            b"      >>]",
+            b"    >> <<",
+            b"      /T (agree)",
+            b"      /V /Yes",
+            b"    >>]",
+            b"  >>",
+            b">>",
+            b"endobj\n",
+            b"trailer",
+            b"<<\n  /Root 1 0 R\n>>",
+            b"%%EOF",
+        );
+    }
+}
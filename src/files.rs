@@ -37,6 +37,43 @@ impl<'a> FileSpec<'a> {
         self
     }
 
+    /// Write both the `/F` and the `/UF` attributes from the same path, so that
+    /// they are consistent. Directories are indicated by `/`, independent of
+    /// the platform.
+    ///
+    /// The `/F` attribute receives the UTF-8 bytes of the path, while the
+    /// `/UF` attribute receives it as a text string.
+    pub fn file_name(&mut self, path: &str) -> &mut Self {
+        self.path(Str(path.as_bytes()));
+        self.unic_file(TextStr(path))
+    }
+
+    /// Write the `/DOS` attribute to set a DOS file name. Deprecated.
+    pub fn dos(&mut self, path: Str) -> &mut Self {
+        self.pair(Name(b"DOS"), path);
+        self
+    }
+
+    /// Write the `/Mac` attribute to set a Mac OS file name. Deprecated.
+    pub fn mac(&mut self, path: Str) -> &mut Self {
+        self.pair(Name(b"Mac"), path);
+        self
+    }
+
+    /// Write the `/Unix` attribute to set a UNIX file name. Deprecated.
+    pub fn unix(&mut self, path: Str) -> &mut Self {
+        self.pair(Name(b"Unix"), path);
+        self
+    }
+
+    /// Write the `/ID` attribute to set the file identifier of the referenced
+    /// file. This improves the chance of finding the correct version of the
+    /// file. PDF 1.1+.
+    pub fn id(&mut self, permanent: Str, changing: Str) -> &mut Self {
+        self.insert(Name(b"ID")).array().item(permanent).item(changing);
+        self
+    }
+
     /// Write the `/V` attribute to indicate whether _not_ to cache the file.
     pub fn volatile(&mut self, dont_cache: bool) -> &mut Self {
         self.pair(Name(b"V"), dont_cache);
@@ -59,10 +96,71 @@ impl<'a> FileSpec<'a> {
         self.insert(Name(b"EF")).dict().pair(Name(b"F"), id);
         self
     }
+
+    /// Write the `/EF` attribute to reference an [embedded file](EmbeddedFile)
+    /// for both the `F` and the `UF` attribute. PDF 1.7+.
+    pub fn embedded_file_unicode(&mut self, id: Ref) -> &mut Self {
+        self.insert(Name(b"EF"))
+            .dict()
+            .pair(Name(b"F"), id)
+            .pair(Name(b"UF"), id);
+        self
+    }
+
+    /// Write the `/RF` attribute to reference a related files array for the
+    /// `F` attribute. Requires `/EF`. PDF 1.3+.
+    ///
+    /// The related files array consists of pairs of file names and references
+    /// to [embedded files](EmbeddedFile) and can be written with
+    /// [`Chunk::indirect`].
+    pub fn related_files(&mut self, id: Ref) -> &mut Self {
+        self.insert(Name(b"RF")).dict().pair(Name(b"F"), id);
+        self
+    }
+
+    /// Start writing the `/EP` dictionary to mark the embedded file as the
+    /// encrypted payload of an unencrypted wrapper document. PDF 2.0+.
+    ///
+    /// The file specification should also have an `/AFRelationship` of
+    /// `/EncryptedPayload` and be referenced from the catalog's `/AF` array.
+    pub fn encrypted_payload(&mut self) -> EncryptedPayload<'_> {
+        self.insert(Name(b"EP")).start()
+    }
 }
 
 deref!('a, FileSpec<'a> => Dict<'a>, dict);
 
+/// Writer for an _encrypted payload dictionary_. PDF 2.0+.
+///
+/// This struct is created by [`FileSpec::encrypted_payload`].
+pub struct EncryptedPayload<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(EncryptedPayload: |obj| {
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Type"), Name(b"EncryptedPayload"));
+    Self { dict }
+});
+
+impl<'a> EncryptedPayload<'a> {
+    /// Write the `/Subtype` attribute to set the name of the cryptographic
+    /// filter used to encrypt the payload document. Required.
+    pub fn subtype(&mut self, subtype: Name) -> &mut Self {
+        self.pair(Name(b"Subtype"), subtype);
+        self
+    }
+
+    /// Write the `/Version` attribute to set the version of the cryptographic
+    /// filter.
+    pub fn version(&mut self, version: TextStr) -> &mut Self {
+        self.pair(Name(b"Version"), version);
+        self
+    }
+}
+
+deref!('a, EncryptedPayload<'a> => Dict<'a>, dict);
+
 /// Writer for an _embedded file stream_.
 ///
 /// This struct is created by [`Chunk::embedded_file`].
@@ -80,9 +178,8 @@ impl<'a> EmbeddedFile<'a> {
     /// Write the `/Subtype` attribute to set the file type.
     ///
     /// This can either be a MIME type or a name prefixed by a first class PDF
-    /// prefix. Note that special characters must be encoded as described in
-    /// section 7.3.5 of the PDF 1.7 specification, e.g. `image/svg+xml` would
-    /// become `Name(b"image#2Fsvg+xml")`.
+    /// prefix. Special characters are escaped automatically, e.g.
+    /// `Name(b"image/svg+xml")` is written as `/image#2Fsvg+xml`.
     pub fn subtype(&mut self, subtype: Name) -> &mut Self {
         self.pair(Name(b"Subtype"), subtype);
         self
@@ -1,6 +1,7 @@
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
 use super::*;
+use crate::types::WritingMode;
 
 /// Writer for a _Type-1 font dictionary_.
 ///
@@ -86,6 +87,57 @@ impl<'a> Type1Font<'a> {
 
 deref!('a, Type1Font<'a> => Dict<'a>, dict);
 
+/// One of the 14 standard Type-1 fonts that every PDF viewer provides.
+///
+/// These fonts don't need to be embedded. A font dictionary for them can be
+/// written with [`Chunk::standard_font`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[allow(missing_docs)]
+pub enum StandardFont {
+    Helvetica,
+    HelveticaBold,
+    HelveticaOblique,
+    HelveticaBoldOblique,
+    TimesRoman,
+    TimesBold,
+    TimesItalic,
+    TimesBoldItalic,
+    Courier,
+    CourierBold,
+    CourierOblique,
+    CourierBoldOblique,
+    Symbol,
+    ZapfDingbats,
+}
+
+impl StandardFont {
+    pub(crate) fn to_name(self) -> Name<'static> {
+        match self {
+            Self::Helvetica => Name(b"Helvetica"),
+            Self::HelveticaBold => Name(b"Helvetica-Bold"),
+            Self::HelveticaOblique => Name(b"Helvetica-Oblique"),
+            Self::HelveticaBoldOblique => Name(b"Helvetica-BoldOblique"),
+            Self::TimesRoman => Name(b"Times-Roman"),
+            Self::TimesBold => Name(b"Times-Bold"),
+            Self::TimesItalic => Name(b"Times-Italic"),
+            Self::TimesBoldItalic => Name(b"Times-BoldItalic"),
+            Self::Courier => Name(b"Courier"),
+            Self::CourierBold => Name(b"Courier-Bold"),
+            Self::CourierOblique => Name(b"Courier-Oblique"),
+            Self::CourierBoldOblique => Name(b"Courier-BoldOblique"),
+            Self::Symbol => Name(b"Symbol"),
+            Self::ZapfDingbats => Name(b"ZapfDingbats"),
+        }
+    }
+
+    /// Whether the font uses its own built-in encoding for a non-Latin
+    /// character set. This is the case for `Symbol` and `ZapfDingbats`, which
+    /// should not be given a Latin `/Encoding`.
+    pub fn is_symbolic(self) -> bool {
+        matches!(self, Self::Symbol | Self::ZapfDingbats)
+    }
+}
+
 /// Writer for a _Type-3 font dictionary_.
 ///
 /// This struct is created by [`Chunk::type3_font`].
@@ -274,6 +326,15 @@ impl<'a> Type0Font<'a> {
         self
     }
 
+    /// Write the `/Encoding` attribute as the predefined `Identity-H` or
+    /// `Identity-V` encoding, which map two-byte codes directly to CIDs for
+    /// horizontal or vertical writing respectively.
+    pub fn encoding_identity(&mut self, mode: WritingMode) -> &mut Self {
+        let encoding = if mode.is_vertical() { "Identity-V" } else { "Identity-H" };
+        self.pair(Name(b"Encoding"), Name(encoding.as_bytes()));
+        self
+    }
+
     /// Write the `/Encoding` attribute as a reference to a character map.
     /// Either this or [`encoding_predefined`](Self::encoding_predefined) is
     /// required.
@@ -349,6 +410,23 @@ impl<'a> CidFont<'a> {
         self.insert(Name(b"W")).start()
     }
 
+    /// Write the `/DW2` attribute, specifying the default vertical metrics for
+    /// vertical writing: the vertical component of the position vector and
+    /// the vertical displacement. Defaults to `[880 -1000]`.
+    pub fn default_vertical_metrics(
+        &mut self,
+        position_y: f32,
+        advance: f32,
+    ) -> &mut Self {
+        self.insert(Name(b"DW2")).array().items([position_y, advance]);
+        self
+    }
+
+    /// Start writing the `/W2` (vertical metrics) array for vertical writing.
+    pub fn vertical_metrics(&mut self) -> VerticalMetrics<'_> {
+        self.insert(Name(b"W2")).start()
+    }
+
     /// Write the `/CIDToGIDMap` attribute as a predefined name.
     pub fn cid_to_gid_map_predefined(&mut self, name: Name) -> &mut Self {
         self.pair(Name(b"CIDToGIDMap"), name);
@@ -412,10 +490,107 @@ impl<'a> Widths<'a> {
         self.item(width);
         self
     }
+
+    /// Specifies the widths of arbitrary CIDs given as `(cid, width)` pairs in
+    /// the most compact form. Runs of at least three equal widths are written
+    /// as [ranges](Self::same) and everything else is grouped into
+    /// [consecutive](Self::consecutive) lists.
+    ///
+    /// The pairs can be given in any order. If a CID occurs multiple times,
+    /// its first width is used. Glyphs whose width equals the
+    /// [default width](CidFont::default_width) can be omitted entirely.
+    pub fn compact(&mut self, widths: impl IntoIterator<Item = (u16, f32)>) -> &mut Self {
+        let mut widths: Vec<(u16, f32)> = widths.into_iter().collect();
+        widths.sort_by_key(|&(cid, _)| cid);
+        widths.dedup_by_key(|&mut (cid, _)| cid);
+
+        let mut start = 0;
+        let mut pending: Vec<f32> = vec![];
+        let mut i = 0;
+        while i < widths.len() {
+            let (cid, width) = widths[i];
+            let mut end = i + 1;
+            while end < widths.len()
+                && widths[end].0 == widths[end - 1].0 + 1
+                && widths[end].1 == width
+            {
+                end += 1;
+            }
+
+            let continues = u32::from(start) + pending.len() as u32 == u32::from(cid);
+            if end - i >= 3 || !continues {
+                if !pending.is_empty() {
+                    self.consecutive(start, pending.drain(..));
+                }
+                start = cid;
+            }
+
+            if end - i >= 3 {
+                self.same(cid, widths[end - 1].0, width);
+            } else {
+                pending.extend(widths[i..end].iter().map(|&(_, width)| width));
+            }
+
+            i = end;
+        }
+
+        if !pending.is_empty() {
+            self.consecutive(start, pending);
+        }
+
+        self
+    }
 }
 
 deref!('a, Widths<'a> => Array<'a>, array);
 
+/// Writer for a _CID font vertical metrics array_.
+///
+/// Each entry consists of the vertical displacement of a glyph, followed by
+/// the x and y components of the position vector that maps the glyph's
+/// horizontal origin to its vertical one. This struct is created by
+/// [`CidFont::vertical_metrics`].
+pub struct VerticalMetrics<'a> {
+    array: Array<'a>,
+}
+
+writer!(VerticalMetrics: |obj| Self { array: obj.array() });
+
+impl<'a> VerticalMetrics<'a> {
+    /// Specifies individual metrics as `(advance, position_x, position_y)`
+    /// triples for a range of consecutive CIDs starting at `start`.
+    pub fn consecutive(
+        &mut self,
+        start: u16,
+        metrics: impl IntoIterator<Item = (f32, f32, f32)>,
+    ) -> &mut Self {
+        self.item(i32::from(start));
+        let mut array = self.push().array();
+        for (advance, x, y) in metrics {
+            array.items([advance, x, y]);
+        }
+        array.finish();
+        self
+    }
+
+    /// Specifies the same metrics for all CIDs between `first` and `last`.
+    pub fn same(
+        &mut self,
+        first: u16,
+        last: u16,
+        advance: f32,
+        position_x: f32,
+        position_y: f32,
+    ) -> &mut Self {
+        self.item(i32::from(first));
+        self.item(i32::from(last));
+        self.items([advance, position_x, position_y]);
+        self
+    }
+}
+
+deref!('a, VerticalMetrics<'a> => Array<'a>, array);
+
 /// Writer for a _font descriptor dictionary_.
 ///
 /// This struct is created by [`Chunk::font_descriptor`].
@@ -459,7 +634,20 @@ impl<'a> FontDescriptor<'a> {
     }
 
     /// Write the `/Flags` attribute. Required.
+    ///
+    /// Symbolic fonts map their character codes directly to glyphs through
+    /// their built-in encoding, so their font dictionaries should not specify
+    /// a `/BaseEncoding`.
+    ///
+    /// Exactly one of [`SYMBOLIC`](FontFlags::SYMBOLIC) and
+    /// [`NON_SYMBOLIC`](FontFlags::NON_SYMBOLIC) should be set. This is only
+    /// checked with a debug assertion, so release builds write any flags.
     pub fn flags(&mut self, flags: FontFlags) -> &mut Self {
+        debug_assert!(
+            flags.contains(FontFlags::SYMBOLIC)
+                != flags.contains(FontFlags::NON_SYMBOLIC),
+            "exactly one of the symbolic and non-symbolic flags must be set",
+        );
         self.pair(Name(b"Flags"), flags.bits() as i32);
         self
     }
@@ -551,7 +739,8 @@ impl<'a> FontDescriptor<'a> {
     }
 
     /// Write the `/FontFile3` attribute, referencing CFF font data. PDF 1.2+ or
-    /// PDF 1.3+ for CID-keyed fonts.
+    /// PDF 1.3+ for CID-keyed fonts. Also used for OpenType font data in PDF
+    /// 1.6+.
     pub fn font_file3(&mut self, id: Ref) -> &mut Self {
         self.pair(Name(b"FontFile3"), id);
         self
@@ -563,6 +752,45 @@ impl<'a> FontDescriptor<'a> {
         self.pair(Name(b"CharSet"), names);
         self
     }
+
+    /// Write the `/Style` dictionary with its `/Panose` attribute to classify
+    /// the font's visual style. The string consists of the font's two-byte
+    /// `sFamilyClass` from the OpenType `OS/2` table followed by its ten-byte
+    /// PANOSE classification. This is only relevant for CID fonts.
+    pub fn panose(&mut self, family_class: i16, panose: [u8; 10]) -> &mut Self {
+        let mut bytes = family_class.to_be_bytes().to_vec();
+        bytes.extend(panose);
+        self.insert(Name(b"Style")).dict().pair(Name(b"Panose"), Str(&bytes));
+        self
+    }
+
+    /// Write the `/Lang` attribute to specify the language of the font's
+    /// glyphs as a language code. This is only relevant for CID fonts. PDF
+    /// 1.5+.
+    pub fn lang(&mut self, lang: Name) -> &mut Self {
+        self.pair(Name(b"Lang"), lang);
+        self
+    }
+
+    /// Start writing the `/FD` dictionary. It maps the class names of glyph
+    /// groups to dictionaries that override the metrics in this descriptor,
+    /// e.g. for the proportional Latin glyphs in a CJK font. This is only
+    /// relevant for CID fonts.
+    ///
+    /// The overrides are marked with the `/FontDescriptor` type, but should
+    /// only contain the entries that differ from this descriptor.
+    pub fn fd(&mut self) -> TypedDict<'_, FontDescriptor<'_>> {
+        self.insert(Name(b"FD")).dict().typed()
+    }
+
+    /// Write the `/CIDSet` attribute to reference a stream identifying the
+    /// CIDs present in an embedded font subset. Each bit of the stream, from
+    /// the most significant bit of the first byte, stands for one CID. This
+    /// is only relevant for CID fonts.
+    pub fn cid_set(&mut self, id: Ref) -> &mut Self {
+        self.pair(Name(b"CIDSet"), id);
+        self
+    }
 }
 
 deref!('a, FontDescriptor<'a> => Dict<'a>, dict);
@@ -625,6 +853,128 @@ bitflags::bitflags! {
     }
 }
 
+/// Writer for an _embedded font file stream_.
+///
+/// This struct is created by [`Chunk::font_file`]. It is referenced from a
+/// [font descriptor](FontDescriptor) through `/FontFile` for Type 1 fonts,
+/// `/FontFile2` for TrueType fonts and `/FontFile3` for everything with a
+/// [`/Subtype`](Self::subtype).
+///
+/// WOFF and WOFF2 fonts cannot be embedded as-is. Decompress them to their
+/// underlying TrueType or OpenType data first.
+pub struct FontFile<'a> {
+    stream: Stream<'a>,
+}
+
+impl<'a> FontFile<'a> {
+    /// Create a new font file stream writer.
+    pub(crate) fn start(stream: Stream<'a>) -> Self {
+        Self { stream }
+    }
+
+    /// Write the `/Subtype` attribute to specify the format of the font data.
+    /// Required for `/FontFile3` streams only. PDF 1.2+.
+    pub fn subtype(&mut self, subtype: FontFileType) -> &mut Self {
+        self.pair(Name(b"Subtype"), subtype.to_name());
+        self
+    }
+
+    /// Write the `/Length1`, `/Length2` and `/Length3` attributes of a
+    /// `/FontFile` stream by splitting the unencoded Type 1 font `program`
+    /// into its clear-text, encrypted and fixed-content portions. Required
+    /// for Type 1 fonts.
+    ///
+    /// The program must be in the PFA layout, i.e. without PFB segment
+    /// headers. If it lacks the trailing zeros and `cleartomark`, the fixed
+    /// portion is empty.
+    ///
+    /// Panics if the program has no `eexec` section.
+    pub fn type1_lengths(&mut self, program: &[u8]) -> &mut Self {
+        let [clear, encrypted, fixed] = split_type1(program);
+        self.pair(Name(b"Length1"), to_length(clear));
+        self.pair(Name(b"Length2"), to_length(encrypted));
+        self.pair(Name(b"Length3"), to_length(fixed));
+        self
+    }
+
+    /// Write the `/Length1` attribute of a `/FontFile2` stream to specify
+    /// the length of the unencoded TrueType font `program`. Required for
+    /// TrueType fonts.
+    ///
+    /// Panics if the program is not TrueType data. OpenType fonts with CFF
+    /// outlines must instead be embedded through `/FontFile3` with the
+    /// [`OpenType`](FontFileType::OpenType) subtype.
+    pub fn true_type_length(&mut self, program: &[u8]) -> &mut Self {
+        match program.get(..4) {
+            Some(b"\0\x01\0\0" | b"true") => {}
+            Some(b"OTTO") => {
+                panic!("OpenType font with CFF outlines must use /FontFile3")
+            }
+            _ => panic!("font program is not TrueType data"),
+        }
+        self.pair(Name(b"Length1"), to_length(program.len()));
+        self
+    }
+}
+
+deref!('a, FontFile<'a> => Stream<'a>, stream);
+
+/// Split a Type 1 font program into its clear-text, encrypted and
+/// fixed-content portions.
+fn split_type1(program: &[u8]) -> [usize; 3] {
+    let find = |needle: &[u8]| program.windows(needle.len()).position(|w| w == needle);
+    let mut clear = find(b"eexec").expect("Type 1 font program has no eexec section") + 5;
+    while program.get(clear).is_some_and(|b| b.is_ascii_whitespace()) {
+        clear += 1;
+    }
+
+    // The fixed portion consists of 512 zeros, usually split over several
+    // lines, followed by `cleartomark`.
+    let mut fixed_start = program.len();
+    if let Some(mark) = program[clear..].windows(11).rposition(|w| w == b"cleartomark") {
+        fixed_start = clear + mark;
+        let mut zeros = 0;
+        while fixed_start > clear && zeros < 512 {
+            match program[fixed_start - 1] {
+                b'0' => zeros += 1,
+                b' ' | b'\t' | b'\r' | b'\n' => {}
+                _ => break,
+            }
+            fixed_start -= 1;
+        }
+    }
+
+    [clear, fixed_start - clear, program.len() - fixed_start]
+}
+
+/// Convert the length of a font program portion to a PDF integer.
+fn to_length(len: usize) -> i32 {
+    i32::try_from(len).unwrap_or_else(|_| {
+        panic!("font program length (is `{}`) must be <= i32::MAX", len);
+    })
+}
+
+/// The format of the font data in a `/FontFile3` stream.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FontFileType {
+    /// A Type 1 font in the Compact Font Format. PDF 1.2+.
+    Type1C,
+    /// A CID-keyed font in the Compact Font Format. PDF 1.3+.
+    CidFontType0C,
+    /// An OpenType font with TrueType or CFF outlines. PDF 1.6+.
+    OpenType,
+}
+
+impl FontFileType {
+    pub(crate) fn to_name(self) -> Name<'static> {
+        match self {
+            Self::Type1C => Name(b"Type1C"),
+            Self::CidFontType0C => Name(b"CIDFontType0C"),
+            Self::OpenType => Name(b"OpenType"),
+        }
+    }
+}
+
 /// Writer for a _character map stream_.
 ///
 /// This struct is created by [`Chunk::cmap`].
@@ -650,6 +1000,27 @@ impl<'a> Cmap<'a> {
         info.write(self.insert(Name(b"CIDSystemInfo")));
         self
     }
+
+    /// Write the `/WMode` attribute to set whether the character map is for
+    /// horizontal or vertical writing. Must match the mode in the CMap data.
+    pub fn writing_mode(&mut self, mode: WritingMode) -> &mut Self {
+        self.pair(Name(b"WMode"), i32::from(mode.is_vertical()));
+        self
+    }
+
+    /// Write the `/UseCMap` attribute to base this character map on a
+    /// predefined CMap.
+    pub fn use_cmap(&mut self, name: Name) -> &mut Self {
+        self.pair(Name(b"UseCMap"), name);
+        self
+    }
+
+    /// Write the `/UseCMap` attribute to base this character map on another
+    /// embedded CMap stream.
+    pub fn use_cmap_stream(&mut self, id: Ref) -> &mut Self {
+        self.pair(Name(b"UseCMap"), id);
+        self
+    }
 }
 
 deref!('a, Cmap<'a> => Stream<'a>, stream);
@@ -668,51 +1039,8 @@ where
 {
     /// Create a new, empty unicode character map.
     pub fn new(name: Name, info: SystemInfo) -> Self {
-        // https://www.adobe.com/content/dam/acom/en/devnet/font/pdfs/5014.CIDFont_Spec.pdf
-
         let mut buf = Vec::new();
-
-        // Static header.
-        buf.extend(b"%!PS-Adobe-3.0 Resource-CMap\n");
-        buf.extend(b"%%DocumentNeededResources: procset CIDInit\n");
-        buf.extend(b"%%IncludeResource: procset CIDInit\n");
-
-        // Dynamic header.
-        buf.extend(b"%%BeginResource: CMap ");
-        buf.extend(name.0);
-        buf.push(b'\n');
-        buf.extend(b"%%Title: (");
-        buf.extend(name.0);
-        buf.push(b' ');
-        buf.extend(info.registry.0);
-        buf.push(b' ');
-        buf.extend(info.ordering.0);
-        buf.push(b' ');
-        buf.push_int(info.supplement);
-        buf.extend(b")\n");
-        buf.extend(b"%%Version: 1\n");
-        buf.extend(b"%%EndComments\n");
-
-        // General body.
-        buf.extend(b"/CIDInit /ProcSet findresource begin\n");
-        buf.extend(b"12 dict begin\n");
-        buf.extend(b"begincmap\n");
-        buf.extend(b"/CIDSystemInfo 3 dict dup begin\n");
-        buf.extend(b"    /Registry ");
-        buf.push_val(info.registry);
-        buf.extend(b" def\n");
-        buf.extend(b"    /Ordering ");
-        buf.push_val(info.ordering);
-        buf.extend(b" def\n");
-        buf.extend(b"    /Supplement ");
-        buf.push_val(info.supplement);
-        buf.extend(b" def\n");
-        buf.extend(b"end def\n");
-        buf.extend(b"/CMapName ");
-        buf.push_val(name);
-        buf.extend(b" def\n");
-        buf.extend(b"/CMapVersion 1 def\n");
-        buf.extend(b"/CMapType 0 def\n");
+        write_cmap_header(&mut buf, name, info, 0);
 
         // We just cover the whole unicode codespace.
         buf.extend(b"1 begincodespacerange\n");
@@ -765,15 +1093,7 @@ where
     pub fn finish(mut self) -> Vec<u8> {
         // Flush the in-progress range.
         self.flush_range();
-
-        // End of body.
-        self.buf.extend(b"endcmap\n");
-        self.buf.extend(b"CMapName currentdict /CMap defineresource pop\n");
-        self.buf.extend(b"end\n");
-        self.buf.extend(b"end\n");
-        self.buf.extend(b"%%EndResource\n");
-        self.buf.extend(b"%%EOF");
-
+        write_cmap_footer(&mut self.buf);
         self.buf
     }
 
@@ -790,6 +1110,167 @@ where
     }
 }
 
+/// A builder for a CID-keyed character map stream, which maps two-byte
+/// character codes to CIDs.
+///
+/// This can be used as the `/Encoding` of a [`Type0Font`] to define a custom
+/// encoding, for example for vertical writing. Write the resulting bytes with
+/// [`Chunk::cmap`].
+pub struct CidCmap {
+    buf: Vec<u8>,
+    chars: Vec<u8>,
+    char_count: i32,
+    ranges: Vec<u8>,
+    range_count: i32,
+}
+
+impl CidCmap {
+    /// Create a new, empty CID character map covering all two-byte codes.
+    pub fn new(name: Name, info: SystemInfo, mode: WritingMode) -> Self {
+        let mut buf = Vec::new();
+        write_cmap_header(&mut buf, name, info, 1);
+        if mode.is_vertical() {
+            buf.extend(b"/WMode 1 def\n");
+        }
+
+        buf.extend(b"1 begincodespacerange\n");
+        buf.extend(b"<0000> <FFFF>\n");
+        buf.extend(b"endcodespacerange\n");
+
+        Self {
+            buf,
+            chars: vec![],
+            char_count: 0,
+            ranges: vec![],
+            range_count: 0,
+        }
+    }
+
+    /// Add a mapping from a character code to a CID.
+    pub fn pair(&mut self, code: u16, cid: u16) {
+        self.chars.push(b'<');
+        self.chars.push_hex_u16(code);
+        self.chars.extend(b"> ");
+        self.chars.push_int(cid.into());
+        self.chars.push(b'\n');
+        self.char_count += 1;
+
+        // At most 100 lines per block.
+        if self.char_count >= 100 {
+            self.flush_chars();
+        }
+    }
+
+    /// Add a mapping from the inclusive range of character codes `first` to
+    /// `last` to consecutive CIDs starting at `cid`.
+    pub fn range(&mut self, first: u16, last: u16, cid: u16) {
+        self.ranges.push(b'<');
+        self.ranges.push_hex_u16(first);
+        self.ranges.extend(b"> <");
+        self.ranges.push_hex_u16(last);
+        self.ranges.extend(b"> ");
+        self.ranges.push_int(cid.into());
+        self.ranges.push(b'\n');
+        self.range_count += 1;
+
+        // At most 100 lines per block.
+        if self.range_count >= 100 {
+            self.flush_ranges();
+        }
+    }
+
+    /// Finish building the character map.
+    pub fn finish(mut self) -> Vec<u8> {
+        self.flush_chars();
+        self.flush_ranges();
+        write_cmap_footer(&mut self.buf);
+        self.buf
+    }
+
+    fn flush_chars(&mut self) {
+        if self.char_count > 0 {
+            self.buf.push_int(self.char_count);
+            self.buf.extend(b" begincidchar\n");
+            self.buf.extend(&self.chars);
+            self.buf.extend(b"endcidchar\n");
+        }
+
+        self.char_count = 0;
+        self.chars.clear();
+    }
+
+    fn flush_ranges(&mut self) {
+        if self.range_count > 0 {
+            self.buf.push_int(self.range_count);
+            self.buf.extend(b" begincidrange\n");
+            self.buf.extend(&self.ranges);
+            self.buf.extend(b"endcidrange\n");
+        }
+
+        self.range_count = 0;
+        self.ranges.clear();
+    }
+}
+
+/// Write the common beginning of a character map up to the codespace ranges.
+fn write_cmap_header(buf: &mut Vec<u8>, name: Name, info: SystemInfo, cmap_type: i32) {
+    // https://www.adobe.com/content/dam/acom/en/devnet/font/pdfs/5014.CIDFont_Spec.pdf
+
+    // Static header.
+    buf.extend(b"%!PS-Adobe-3.0 Resource-CMap\n");
+    buf.extend(b"%%DocumentNeededResources: procset CIDInit\n");
+    buf.extend(b"%%IncludeResource: procset CIDInit\n");
+
+    // Dynamic header.
+    buf.extend(b"%%BeginResource: CMap ");
+    buf.extend(name.0);
+    buf.push(b'\n');
+    buf.extend(b"%%Title: (");
+    buf.extend(name.0);
+    buf.push(b' ');
+    buf.extend(info.registry.0);
+    buf.push(b' ');
+    buf.extend(info.ordering.0);
+    buf.push(b' ');
+    buf.push_int(info.supplement);
+    buf.extend(b")\n");
+    buf.extend(b"%%Version: 1\n");
+    buf.extend(b"%%EndComments\n");
+
+    // General body.
+    buf.extend(b"/CIDInit /ProcSet findresource begin\n");
+    buf.extend(b"12 dict begin\n");
+    buf.extend(b"begincmap\n");
+    buf.extend(b"/CIDSystemInfo 3 dict dup begin\n");
+    buf.extend(b"    /Registry ");
+    buf.push_val(info.registry);
+    buf.extend(b" def\n");
+    buf.extend(b"    /Ordering ");
+    buf.push_val(info.ordering);
+    buf.extend(b" def\n");
+    buf.extend(b"    /Supplement ");
+    buf.push_val(info.supplement);
+    buf.extend(b" def\n");
+    buf.extend(b"end def\n");
+    buf.extend(b"/CMapName ");
+    buf.push_val(name);
+    buf.extend(b" def\n");
+    buf.extend(b"/CMapVersion 1 def\n");
+    buf.extend(b"/CMapType ");
+    buf.push_int(cmap_type);
+    buf.extend(b" def\n");
+}
+
+/// Write the common end of a character map.
+fn write_cmap_footer(buf: &mut Vec<u8>) {
+    buf.extend(b"endcmap\n");
+    buf.extend(b"CMapName currentdict /CMap defineresource pop\n");
+    buf.extend(b"end\n");
+    buf.extend(b"end\n");
+    buf.extend(b"%%EndResource\n");
+    buf.extend(b"%%EOF");
+}
+
 /// Type3 fonts require (in Acrobat at least) IDs in CMaps to be encoded with
 /// one byte only, whereas other font types use two bytes.
 ///
@@ -802,6 +1283,8 @@ impl GlyphId for u16 {}
 
 /// Module to seal the `GlyphId` trait.
 mod private {
+    use alloc::vec::Vec;
+
     use crate::buf::BufExt;
 
     pub trait Sealed {
@@ -848,3 +1331,156 @@ impl SystemInfo<'_> {
             .pair(Name(b"Supplement"), self.supplement);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_font() {
+        test!(
+            crate::tests::slice(|w| {
+                w.standard_font(Ref::new(1), StandardFont::TimesBoldItalic)
+                    .encoding_predefined(Name(b"WinAnsiEncoding"));
+            }),
+            b"1 0 obj",
+            b"<<",
+            b"  /Type /Font",
+            b"  /Subtype /Type1",
+            b"  /BaseFont /Times-BoldItalic",
+            b"  /Encoding /WinAnsiEncoding",
+            b">>",
+            b"endobj\n\n",
+        );
+    }
+
+    #[test]
+    fn test_compact_widths() {
+        test_obj!(
+            |obj| {
+                obj.start::<CidFont>().widths().compact([
+                    (7, 300.0),
+                    (1, 500.0),
+                    (2, 600.0),
+                    (3, 250.0),
+                    (4, 250.0),
+                    (5, 250.0),
+                    (6, 700.0),
+                    (8, 300.0),
+                    (1, 900.0),
+                    (20, 400.0),
+                ]);
+            },
+            b"<<",
+            b"  /Type /Font",
+            b"  /W [1 [500 600] 3 5 250 6 [700 300 300] 20 [400]]",
+            b">>",
+        );
+    }
+
+    #[test]
+    fn test_vertical_metrics() {
+        test_obj!(
+            |obj| {
+                let mut font = obj.start::<CidFont>();
+                font.default_vertical_metrics(880.0, -1000.0);
+                font.vertical_metrics()
+                    .consecutive(1, [(-1000.0, 500.0, 880.0), (-500.0, 250.0, 880.0)])
+                    .same(10, 20, -1000.0, 500.0, 900.0);
+            },
+            b"<<",
+            b"  /Type /Font",
+            b"  /DW2 [880 -1000]",
+            b"  /W2 [1 [-1000 500 880 -500 250 880] 10 20 -1000 500 900]",
+            b">>",
+        );
+    }
+
+    #[test]
+    fn test_font_flags() {
+        test_obj!(
+            |obj| {
+                obj.start::<FontDescriptor>()
+                    .flags(FontFlags::SERIF | FontFlags::NON_SYMBOLIC);
+            },
+            b"<<",
+            b"  /Type /FontDescriptor",
+            b"  /Flags 34",
+            b">>",
+        );
+    }
+
+    #[test]
+    fn test_cid_font_descriptor() {
+        test_obj!(
+            |obj| {
+                let mut descriptor = obj.start::<FontDescriptor>();
+                descriptor.panose(0x0801, [2, 2, 6, 0, 0, 0, 0, 0, 0, 0]);
+                descriptor.lang(Name(b"ja"));
+                descriptor.fd().insert(Name(b"Proportional")).stem_v(80.0);
+            },
+            b"<<",
+            b"  /Type /FontDescriptor",
+            b"  /Style <<",
+            br"    /Panose (\b\001\002\002\006\000\000\000\000\000\000\000)",
+            b"  >>",
+            b"  /Lang /ja",
+            b"  /FD <<",
+            b"    /Proportional <<",
+            b"      /Type /FontDescriptor",
+            b"      /StemV 80",
+            b"    >>",
+            b"  >>",
+            b">>",
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(
+        expected = "exactly one of the symbolic and non-symbolic flags must be set"
+    )]
+    fn test_font_flags_ambiguous() {
+        Chunk::new().font_descriptor(Ref::new(1)).flags(FontFlags::ITALIC);
+    }
+
+    #[test]
+    fn test_font_file() {
+        let zeros = "0".repeat(64) + "\n";
+        let program = format!(
+            "%!FontType1\ncurrentfile eexec\nA1B2C3D0\n{}cleartomark\n",
+            zeros.repeat(8)
+        );
+        let mut c = Chunk::new();
+        c.font_file(Ref::new(1), program.as_bytes())
+            .type1_lengths(program.as_bytes());
+        c.font_file(Ref::new(2), b"OTTO").subtype(FontFileType::OpenType);
+        let text = std::str::from_utf8(c.as_bytes()).unwrap();
+        assert!(text.contains("/Length1 30\n  /Length2 9\n  /Length3 532\n"));
+        assert!(text.contains("/Subtype /OpenType\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = "OpenType font with CFF outlines must use /FontFile3")]
+    fn test_true_type_length_cff() {
+        Chunk::new().font_file(Ref::new(1), b"OTTO").true_type_length(b"OTTO");
+    }
+
+    #[test]
+    fn test_cid_cmap() {
+        let info = SystemInfo {
+            registry: Str(b"Adobe"),
+            ordering: Str(b"Identity"),
+            supplement: 0,
+        };
+        let mut cmap = CidCmap::new(Name(b"Custom-V"), info, WritingMode::TtbRtl);
+        cmap.pair(0x20, 1);
+        cmap.range(0x41, 0x5A, 34);
+        let data = cmap.finish();
+        let text = std::str::from_utf8(&data).unwrap();
+        assert!(text.contains("/CMapType 1 def\n/WMode 1 def\n"));
+        assert!(text.contains("1 begincidchar\n<0020> 1\nendcidchar\n"));
+        assert!(text.contains("1 begincidrange\n<0041> <005A> 34\nendcidrange\n"));
+        assert!(text.ends_with("endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n%%EndResource\n%%EOF"));
+    }
+}
@@ -0,0 +1,329 @@
+use super::*;
+
+/// Rewrite the header and each indirect object in `buf` with `f` and update
+/// the `offsets` accordingly.
+pub(crate) fn rewrite_objects(
+    buf: &[u8],
+    offsets: &mut [(Ref, usize)],
+    mut f: impl FnMut(&[u8], &mut Vec<u8>),
+) -> Vec<u8> {
+    let mut order: Vec<usize> = (0..offsets.len()).collect();
+    order.sort_by_key(|&i| offsets[i].1);
+
+    let first = order.first().map_or(buf.len(), |&i| offsets[i].1);
+    let mut out = Vec::with_capacity(buf.len() + buf.len() / 64);
+    f(&buf[..first], &mut out);
+
+    for (k, &i) in order.iter().enumerate() {
+        let start = offsets[i].1;
+        let end = order.get(k + 1).map_or(buf.len(), |&j| offsets[j].1);
+        offsets[i].1 = out.len();
+        f(&buf[start..end], &mut out);
+    }
+
+    out
+}
+
+/// The end-of-line marker between the lines of a file.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum LineEnding {
+    /// A line feed (`\n`).
+    Lf,
+    /// A carriage return followed by a line feed (`\r\n`).
+    CrLf,
+}
+
+impl LineEnding {
+    pub(crate) fn to_bytes(self) -> &'static [u8] {
+        match self {
+            Self::Lf => b"\n",
+            Self::CrLf => b"\r\n",
+        }
+    }
+}
+
+/// The two bytes that end each 20-byte entry of the cross-reference table.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum XrefLineEnding {
+    /// A carriage return followed by a line feed.
+    CrLf,
+    /// A space followed by a line feed.
+    SpaceLf,
+    /// A space followed by a carriage return.
+    SpaceCr,
+}
+
+impl XrefLineEnding {
+    pub(crate) fn to_bytes(self) -> &'static [u8] {
+        match self {
+            Self::CrLf => b"\r\n",
+            Self::SpaceLf => b" \n",
+            Self::SpaceCr => b" \r",
+        }
+    }
+}
+
+/// Write an object with `\r\n` instead of `\n` line endings. Stream data is
+/// copied verbatim.
+pub(crate) fn crlf(obj: &[u8], out: &mut Vec<u8>) {
+    let push = |text: &[u8], out: &mut Vec<u8>| {
+        for (i, &byte) in text.iter().enumerate() {
+            if byte == b'\n' && (i == 0 || text[i - 1] != b'\r') {
+                out.push(b'\r');
+            }
+            out.push(byte);
+        }
+    };
+
+    let data = (0..obj.len()).find(|&i| is_stream_start(obj, i)).and_then(|i| {
+        let start = i + if obj[i + 6..].starts_with(b"\r\n") { 8 } else { 7 };
+        let end = memchr::memmem::rfind(&obj[start..], b"\nendstream")?;
+        Some(start..start + end)
+    });
+
+    match data {
+        Some(data) => {
+            push(&obj[..data.start], out);
+            out.extend(&obj[data.clone()]);
+            push(&obj[data.end..], out);
+        }
+        None => push(obj, out),
+    }
+}
+
+/// Where we are in the object's syntax.
+#[derive(Copy, Clone)]
+enum State {
+    Normal,
+    Literal(usize),
+    Hex,
+    Comment,
+}
+
+/// Write an object such that no line is longer than `limit` bytes if possible.
+///
+/// Lines are broken at spaces between tokens, within literal strings through a
+/// backslash at the end of the line (which is not part of the string) and
+/// within hexadecimal strings anywhere, since whitespace is ignored there.
+/// Tokens that are longer than the limit, like long names, are not split and
+/// neither are the object header, indirect references and the `/Length` entry
+/// of streams. Stream data is copied verbatim because its length is fixed.
+pub(crate) fn wrap(obj: &[u8], limit: usize, out: &mut Vec<u8>) {
+    let mut state = State::Normal;
+    let mut col = 0;
+    let mut space = None;
+    let mut indent = true;
+    let mut i = 0;
+
+    if obj.first().is_some_and(u8::is_ascii_digit) {
+        let header = obj.iter().position(|&b| b == b'\n').map_or(obj.len(), |i| i + 1);
+        out.extend(&obj[..header]);
+        i = header;
+    }
+
+    while i < obj.len() {
+        let byte = obj[i];
+        let len = match state {
+            State::Normal if obj[i..].starts_with(b"<<") => 2,
+            State::Literal(_) if byte == b'\\' => escape_len(&obj[i..]),
+            _ => 1,
+        };
+
+        match state {
+            State::Normal => {
+                if is_stream_start(obj, i) {
+                    out.extend(&obj[i..]);
+                    return;
+                }
+
+                if col + len > limit {
+                    if let Some(pos) = space.take() {
+                        out[pos] = b'\n';
+                        col = out.len() - pos - 1;
+                    }
+                }
+
+                match byte {
+                    b'(' => state = State::Literal(1),
+                    b'<' if len == 1 => state = State::Hex,
+                    b'%' => state = State::Comment,
+                    _ => {}
+                }
+            }
+            State::Literal(depth) => {
+                // Leave room for the backslash.
+                if col + len + 1 > limit {
+                    out.extend(b"\\\n");
+                    col = 0;
+                    space = None;
+                }
+
+                match byte {
+                    b'(' => state = State::Literal(depth + 1),
+                    b')' if depth == 1 => state = State::Normal,
+                    b')' => state = State::Literal(depth - 1),
+                    _ => {}
+                }
+            }
+            State::Hex => {
+                if col + 1 > limit {
+                    out.push(b'\n');
+                    col = 0;
+                    space = None;
+                }
+
+                if byte == b'>' {
+                    state = State::Normal;
+                }
+            }
+            State::Comment => {
+                if byte == b'\n' || byte == b'\r' {
+                    state = State::Normal;
+                }
+            }
+        }
+
+        for &byte in &obj[i..i + len] {
+            out.push(byte);
+            if byte == b'\n' || byte == b'\r' {
+                col = 0;
+                space = None;
+                indent = true;
+            } else {
+                col += 1;
+                indent &= byte == b' ';
+            }
+        }
+
+        if byte == b' ' && !indent && matches!(state, State::Normal) && breakable(obj, i)
+        {
+            space = Some(out.len() - 1);
+        }
+
+        i += len;
+    }
+}
+
+/// Whether a line may be broken at the space at `i`, which is not the case
+/// within an indirect reference and between `/Length` and its value. Readers
+/// look for the stream length without parsing the whole dictionary.
+fn breakable(obj: &[u8], i: usize) -> bool {
+    let (before, after) = (&obj[..i], &obj[i + 1..]);
+    if before.ends_with(b"/Length") {
+        return false;
+    }
+
+    if !before.last().is_some_and(u8::is_ascii_digit) {
+        return true;
+    }
+
+    let is_r = |s: &[u8]| {
+        s.first() == Some(&b'R') && !matches!(s.get(1), Some(&b) if is_regular(b))
+    };
+    let digits = after.iter().take_while(|b| b.is_ascii_digit()).count();
+    let first =
+        digits > 0 && after.get(digits) == Some(&b' ') && is_r(&after[digits + 1..]);
+    !first && !is_r(after)
+}
+
+/// Whether a byte is neither whitespace nor a delimiter.
+fn is_regular(byte: u8) -> bool {
+    !matches!(
+        byte,
+        b'\0'
+            | b'\t'
+            | b'\n'
+            | b'\x0C'
+            | b'\r'
+            | b' '
+            | b'('
+            | b')'
+            | b'<'
+            | b'>'
+            | b'['
+            | b']'
+            | b'{'
+            | b'}'
+            | b'/'
+            | b'%'
+    )
+}
+
+/// The length of the escape sequence at the start of `s`.
+fn escape_len(s: &[u8]) -> usize {
+    let digits = s[1..].iter().take(3).take_while(|b| matches!(b, b'0'..=b'7')).count();
+    match (digits, s.len()) {
+        (0, 1) => 1,
+        (0, _) => 2,
+        (n, _) => 1 + n,
+    }
+}
+
+/// Whether the `stream` keyword that introduces stream data starts at `i`.
+fn is_stream_start(obj: &[u8], i: usize) -> bool {
+    obj[i..].starts_with(b"stream")
+        && i > 0
+        && matches!(obj[i - 1], b'\n' | b'\r' | b' ' | b'>')
+        && matches!(obj.get(i + 6), Some(b'\n' | b'\r'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wrapped(limit: usize, f: impl FnOnce(&mut Chunk)) -> Vec<u8> {
+        let mut c = Chunk::new();
+        f(&mut c);
+        let mut offsets = c.offsets.clone();
+        rewrite_objects(&c.buf, &mut offsets, |obj, out| wrap(obj, limit, out))
+    }
+
+    #[test]
+    fn test_wrap_strings_and_arrays() {
+        let out = wrapped(16, |c| {
+            c.indirect(Ref::new(1))
+                .primitive(Str(b"a (long) string with \\ escapes"));
+            c.indirect(Ref::new(2)).array().items([1, 22, 333, 4444, 55555]);
+            c.indirect(Ref::new(3))
+                .primitive(Str(b"\xff\xfe\xfd\xfc\xfb\xfa\xf9\xf8"));
+        });
+        assert!(out.split(|&b| b == b'\n').all(|line| line.len() <= 16));
+        test!(
+            out,
+            b"1 0 obj",
+            b"(a (long) strin\\",
+            b"g with \\\\ escap\\",
+            b"es)",
+            b"endobj",
+            b"",
+            b"2 0 obj",
+            b"[1 22 333 4444",
+            b"55555]",
+            b"endobj",
+            b"",
+            b"3 0 obj",
+            b"<FFFEFDFCFBFAF9F",
+            b"8>",
+            b"endobj\n\n",
+        );
+    }
+
+    #[test]
+    fn test_wrap_keeps_references_together() {
+        let out = wrapped(4, |c| {
+            c.indirect(Ref::new(12)).array().items([Ref::new(3), Ref::new(45)]);
+        });
+        test!(out, b"12 0 obj", b"[3 0 R", b"45 0 R]", b"endobj\n\n");
+    }
+
+    #[test]
+    fn test_wrap_keeps_stream_data() {
+        let data = [b'x'; 40];
+        let out = wrapped(16, |c| {
+            c.stream(Ref::new(1), &data);
+        });
+        let mut c = Chunk::new();
+        c.stream(Ref::new(1), &data);
+        assert_eq!(out, c.buf);
+    }
+}
@@ -19,6 +19,14 @@ impl<'a> Form<'a> {
         self
     }
 
+    /// Write the `/NeedAppearances` attribute to set whether the viewer
+    /// should construct appearance streams and appearance dictionaries for
+    /// all widget annotations. Deprecated in PDF 2.0.
+    pub fn need_appearances(&mut self, need: bool) -> &mut Self {
+        self.pair(Name(b"NeedAppearances"), need);
+        self
+    }
+
     /// Write the `/SigFlags` attribute to set various document-level
     /// characteristics related to signature fields.
     pub fn sig_flags(&mut self, flags: SigFlags) -> &mut Self {
@@ -59,6 +67,29 @@ impl<'a> Form<'a> {
         self.pair(Name(b"Q"), default as i32);
         self
     }
+
+    /// Write the `/XFA` attribute to reference a stream with the complete XML
+    /// Forms Architecture document of the form. PDF 1.5+. Deprecated in PDF
+    /// 2.0.
+    pub fn xfa(&mut self, id: Ref) -> &mut Self {
+        self.pair(Name(b"XFA"), id);
+        self
+    }
+
+    /// Write the `/XFA` attribute as an array of packets, each consisting of
+    /// the packet's name, like `template` or `datasets`, and a reference to
+    /// the stream with its XML. PDF 1.5+. Deprecated in PDF 2.0.
+    pub fn xfa_packets<'b>(
+        &mut self,
+        packets: impl IntoIterator<Item = (Str<'b>, Ref)>,
+    ) -> &mut Self {
+        let mut array = self.insert(Name(b"XFA")).array();
+        for (name, id) in packets {
+            array.item(name).item(id);
+        }
+        array.finish();
+        self
+    }
 }
 
 deref!('a, Form<'a> => Dict<'a>, dict);
@@ -253,6 +284,18 @@ impl CheckBoxState {
     }
 }
 
+/// The symbol drawn in the on state of a generated check box or radio button
+/// appearance.
+///
+/// See [`Chunk::button_appearances`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ButtonStyle {
+    /// A check mark from the ZapfDingbats font.
+    Check,
+    /// A filled circle, as usual for radio buttons.
+    Circle,
+}
+
 /// Only permissible on radio button fields.
 impl<'a> Field<'a> {
     /// Write the `/V` attribute to set the state of this radio button field.
@@ -277,6 +320,22 @@ impl<'a> Field<'a> {
 }
 
 /// Only permissible on text fields.
+///
+/// A comb field for a nine digit number with right-aligned text could for
+/// example be written like this:
+///
+/// ```
+/// # use pdf_writer::{Pdf, Ref, TextStr};
+/// # use pdf_writer::types::{FieldFlags, FieldType, Quadding};
+/// let mut pdf = Pdf::new();
+/// pdf.form_field(Ref::new(1))
+///     .field_type(FieldType::Text)
+///     .partial_name(TextStr("ssn"))
+///     .field_flags(FieldFlags::COMB)
+///     .text_max_len(9)
+///     .vartext_quadding(Quadding::Right)
+///     .text_value(TextStr("123456789"));
+/// ```
 impl<'a> Field<'a> {
     /// Write the `/MaxLen` attribute to set the maximum length of the fields
     /// text in characters. Only permissible on text fields.
@@ -303,6 +362,113 @@ impl<'a> Field<'a> {
     }
 }
 
+/// Only permissible on barcode fields.
+impl<'a> Field<'a> {
+    /// Start writing the `/PMD` dictionary to turn this text field into a
+    /// barcode field whose value is rendered as a barcode. Adobe extension to
+    /// PDF 1.7.
+    pub fn barcode(&mut self) -> PaperMetadata<'_> {
+        self.insert(Name(b"PMD")).start()
+    }
+}
+
+/// Writer for a _paper metadata dictionary_ describing a barcode field.
+///
+/// This struct is created by [`Field::barcode`].
+pub struct PaperMetadata<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(PaperMetadata: |obj| Self { dict: obj.dict() });
+
+impl<'a> PaperMetadata<'a> {
+    /// Write the `/Symbology` attribute to set the kind of barcode. Required.
+    pub fn symbology(&mut self, symbology: BarcodeSymbology) -> &mut Self {
+        self.pair(Name(b"Symbology"), symbology.to_name());
+        self
+    }
+
+    /// Write the `/Caption` attribute to set a caption for the barcode.
+    pub fn caption(&mut self, caption: TextStr) -> &mut Self {
+        self.pair(Name(b"Caption"), caption);
+        self
+    }
+
+    /// Write the `/Width` attribute to set the width of the barcode.
+    pub fn width(&mut self, width: f32) -> &mut Self {
+        self.pair(Name(b"Width"), width);
+        self
+    }
+
+    /// Write the `/Height` attribute to set the height of the barcode.
+    pub fn height(&mut self, height: f32) -> &mut Self {
+        self.pair(Name(b"Height"), height);
+        self
+    }
+
+    /// Write the `/XSymWidth` attribute to set the width of a single module
+    /// of the barcode in pixels.
+    pub fn module_width(&mut self, width: i32) -> &mut Self {
+        self.pair(Name(b"XSymWidth"), width);
+        self
+    }
+
+    /// Write the `/XSymHeight` attribute to set the height of a single module
+    /// of the barcode in pixels.
+    pub fn module_height(&mut self, height: i32) -> &mut Self {
+        self.pair(Name(b"XSymHeight"), height);
+        self
+    }
+
+    /// Write the `/ECC` attribute to set the error correction level.
+    pub fn error_correction(&mut self, level: i32) -> &mut Self {
+        self.pair(Name(b"ECC"), level);
+        self
+    }
+
+    /// Write the `/nCodeWordRow` attribute to set the number of rows.
+    pub fn code_word_rows(&mut self, rows: i32) -> &mut Self {
+        self.pair(Name(b"nCodeWordRow"), rows);
+        self
+    }
+
+    /// Write the `/nCodeWordCol` attribute to set the number of columns.
+    pub fn code_word_columns(&mut self, columns: i32) -> &mut Self {
+        self.pair(Name(b"nCodeWordCol"), columns);
+        self
+    }
+
+    /// Write the `/Resolution` attribute to set the resolution of the
+    /// barcode in dots per inch.
+    pub fn resolution(&mut self, dpi: i32) -> &mut Self {
+        self.pair(Name(b"Resolution"), dpi);
+        self
+    }
+}
+
+deref!('a, PaperMetadata<'a> => Dict<'a>, dict);
+
+/// The kind of barcode in a barcode [`Field`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum BarcodeSymbology {
+    /// A PDF417 stacked linear barcode.
+    Pdf417,
+    /// A QR code.
+    QrCode,
+    /// A Data Matrix code.
+    DataMatrix,
+}
+
+impl BarcodeSymbology {
+    pub(crate) fn to_name(self) -> Name<'static> {
+        match self {
+            Self::Pdf417 => Name(b"PDF417"),
+            Self::QrCode => Name(b"QRCode"),
+            Self::DataMatrix => Name(b"DataMatrix"),
+        }
+    }
+}
+
 /// Only permissible on fields containing variable text.
 impl<'a> Field<'a> {
     /// Write the `/DA` attribute containing a sequence of valid page-content
@@ -432,7 +598,23 @@ impl<'a> Field<'a> {
 
 /// Writer for a _choice options array_.
 ///
-/// This struct is created by [`Field::choice_options`].
+/// Each option is either a plain text string that is both displayed and
+/// exported or a pair of display and export value. Both kinds may be mixed in
+/// one array. This struct is created by [`Field::choice_options`].
+///
+/// ```
+/// # use pdf_writer::{Pdf, Ref, TextStr};
+/// # use pdf_writer::types::FieldType;
+/// let mut pdf = Pdf::new();
+/// let mut field = pdf.form_field(Ref::new(1));
+/// field.field_type(FieldType::Choice);
+/// field
+///     .choice_options()
+///     .option(TextStr("Other"))
+///     .export(TextStr("Germany"), TextStr("DE"))
+///     .export(TextStr("France"), TextStr("FR"));
+/// field.choice_top_index(1).choice_indices([2]);
+/// ```
 pub struct ChoiceOptions<'a> {
     array: Array<'a>,
 }
@@ -533,13 +715,14 @@ bitflags::bitflags! {
         /// area. PDF 1.4+.
         const DO_NOT_SCROLL = 1 << 23;
         /// The field shall be automatically divided into as many equally
-        /// spaced positions or _combs_ as the value of [`Field::max_len`]
-        /// and the text is layed out into these combs. May only be set if
-        /// the [`Field::max_len`] property is set and if the [`MULTILINE`],
-        /// [`PASSWORD`] and [`FILE_SELECT`] flags are clear. PDF 1.5+.
+        /// spaced positions or _combs_ as the value of
+        /// [`Field::text_max_len`] and the text is layed out into these
+        /// combs. May only be set if the [`Field::text_max_len`] property is
+        /// set and if the [`MULTILINE`], [`PASSWORD`] and [`FILE_SELECT`]
+        /// flags are clear. PDF 1.5+.
         const COMB = 1 << 24;
         /// The value of this field shall be a rich text string. If the field
-        /// has a value, the [`TextField::rich_text_value`] shall specify the
+        /// has a value, the [`Field::vartext_rich_value`] shall specify the
         /// rich text string. PDF 1.5+.
         const RICH_TEXT = 1 << 25;
 
@@ -67,6 +67,30 @@ std::fs::write("target/empty.pdf", pdf.finish())?;
 
 For more examples, check out the [examples folder] in the repository.
 
+# Layout
+The output is always laid out for human inspection: Every indirect object
+starts on its own line, each dictionary entry is written on a separate line
+indented according to its nesting depth, and arrays are written on a single
+line. This stays fully spec-compliant while making generated files easy to
+diff and review.
+
+# Reproducibility
+The output only depends on the sequence of calls made to the writers:
+Dictionary keys are written in the order in which they are inserted, floats
+are always formatted the same way and no timestamps or random file identifiers
+are written unless you provide them explicitly. This makes the crate suitable
+for reproducible builds and snapshot testing.
+
+The only exception is the producer: By default, the crate writes its own name
+and version into the document information and XMP metadata, so upgrading it
+changes the output. Call `Pdf::set_auto_producer(false)` for output that only
+depends on what you write.
+
+# `no_std`
+Disabling the default `std` feature makes the crate `no_std` compatible. It
+then only requires the `alloc` crate. Without `std`, `Chunk::stream_from_reader`
+and `Content::arc` are not available.
+
 # Note
 This crate is rather low-level. It does not allocate or validate indirect
 reference IDs for you and it does not check whether you write all required
@@ -80,120 +104,189 @@ valid PDFs.
 [PDF specification]: https://opensource.adobe.com/dc-acrobat-sdk-docs/pdfstandards/PDF32000_2008.pdf
 */
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![forbid(unsafe_code)]
 #![deny(missing_docs)]
 #![allow(clippy::wrong_self_convention)]
 
+extern crate alloc;
+
 #[macro_use]
 mod macros;
 mod actions;
 mod annotations;
+mod appearance;
 mod attributes;
 mod buf;
+mod capture;
 mod chunk;
 mod color;
 mod content;
+mod document;
+mod encryption;
+mod fdf;
 mod files;
 mod font;
+mod formatting;
 mod forms;
 mod functions;
 mod object;
+mod observer;
+mod optional;
 mod renditions;
 mod renumber;
+mod richmedia;
+mod signature;
+mod stats;
 mod structure;
+mod table;
+mod toc;
 mod transitions;
+#[cfg(any(test, feature = "validate"))]
+mod validate;
+#[cfg(feature = "xfdf")]
+mod xfdf;
 mod xobject;
 
 /// Strongly typed writers for specific PDF structures.
 pub mod writers {
     use super::*;
-    pub use actions::{Action, AdditionalActions, Fields};
+    pub use actions::{Action, AdditionalActions, Fields, OcgState, WinLaunchParams};
     pub use annotations::{
         Annotation, Appearance, AppearanceCharacteristics, AppearanceEntry, BorderStyle,
-        IconFit,
+        ExData, FixedPrint, IconFit, Measure3D,
     };
     pub use attributes::{
         Attributes, FieldAttributes, LayoutAttributes, ListAttributes, TableAttributes,
         UserProperty,
     };
+    pub use capture::{ContentSet, SourceInfo, WebCaptureCommand, WebCaptureInfo};
     pub use color::{
         ColorSpace, DeviceN, DeviceNAttrs, DeviceNMixingHints, DeviceNProcess,
         FunctionShading, IccProfile, OutputIntent, Separation, SeparationInfo,
         ShadingPattern, StreamShading, StreamShadingType, TilingPattern,
     };
     pub use content::{
-        Artifact, ExtGraphicsState, MarkContent, Operation, PositionedItems,
-        PropertyList, Resources, ShowPositioned, SoftMask,
+        Artifact, CompositeHalftone, ExtGraphicsState, MarkContent, Operation,
+        PositionedItems, PropertyList, Resources, ScreenHalftone, ShowPositioned,
+        SoftMask, ThresholdHalftone,
     };
-    pub use files::{EmbeddedFile, EmbeddingParams, FileSpec};
+    pub use document::PageBuilder;
+    pub use fdf::{FdfCatalog, FdfDictionary, FdfField};
+    pub use files::{EmbeddedFile, EmbeddingParams, EncryptedPayload, FileSpec};
     pub use font::{
-        CidFont, Cmap, Differences, Encoding, FontDescriptor, Type0Font, Type1Font,
-        Type3Font, Widths,
+        CidFont, Cmap, Differences, Encoding, FontDescriptor, FontFile, Type0Font,
+        Type1Font, Type3Font, VerticalMetrics, Widths,
     };
-    pub use forms::{Field, Form};
+    pub use forms::{Field, Form, PaperMetadata};
     pub use functions::{
         ExponentialFunction, PostScriptFunction, SampledFunction, StitchingFunction,
     };
     pub use object::DecodeParms;
     pub use object::{NameTree, NameTreeEntries, NumberTree, NumberTreeEntries};
-    pub use renditions::{MediaClip, MediaPermissions, MediaPlayParams, Rendition};
+    pub use optional::{
+        OcConfig, OcMembership, OcProperties, OcUsage, OcUsageApplication,
+        OptionalContentGroup, VisibilityExpression,
+    };
+    pub use renditions::{
+        MediaClip, MediaPermissions, MediaPlayParams, Rendition, Sound,
+    };
+    pub use richmedia::{
+        RichMediaActivation, RichMediaConfiguration, RichMediaContent,
+        RichMediaDeactivation, RichMediaInstance, RichMediaParams, RichMediaSettings,
+    };
+    pub use signature::{
+        DocMdpParams, DocumentPermissions, FieldMdpParams, Signature, SignatureReference,
+        UrParams,
+    };
     pub use structure::{
-        Catalog, ClassMap, Destination, DeveloperExtension, DocumentInfo, MarkInfo,
-        MarkedRef, Metadata, Names, ObjectRef, Outline, OutlineItem, Page, PageLabel,
-        Pages, RoleMap, StructChildren, StructElement, StructTreeRoot, ViewerPreferences,
+        BoxColorInfo, BoxStyle, Catalog, ClassMap, Destination, DeveloperExtension,
+        DocumentInfo, MarkInfo, MarkedRef, Metadata, Names, ObjectRef, Outline,
+        OutlineItem, Page, PageLabel, Pages, PieceInfo, RoleMap, StructChildren,
+        StructElement, StructTreeRoot, ViewerPreferences,
     };
     pub use transitions::Transition;
-    pub use xobject::{FormXObject, Group, ImageXObject, Reference};
+    pub use xobject::{FormXObject, Group, ImageXObject, Reference, Thumbnail};
 }
 
 /// Types used by specific PDF structures.
 pub mod types {
     use super::*;
-    pub use actions::{ActionType, FormActionFlags, RenditionOperation};
+    pub use actions::{
+        ActionType, FormActionFlags, MovieOperation, RenditionOperation,
+        WinLaunchOperation,
+    };
     pub use annotations::{
-        AnnotationFlags, AnnotationIcon, AnnotationType, BorderType, HighlightEffect,
-        IconScale, IconScaleType, TextPosition,
+        AnnotationFlags, AnnotationIcon, AnnotationType, BorderType, CaretSymbol,
+        ExDataType, HighlightEffect, IconScale, IconScaleType, Measure3DType,
+        TextPosition,
     };
     pub use attributes::{
         AttributeOwner, BlockAlign, FieldRole, FieldState, InlineAlign,
         LayoutBorderStyle, ListNumbering, Placement, RubyAlign, RubyPosition,
         TableHeaderScope, TextAlign, TextDecorationType, WritingMode,
     };
+    pub use capture::{ContentSetType, SubmissionType, WebCaptureFlags};
     pub use color::{
-        DeviceNSubtype, FunctionShadingType, OutputIntentSubtype, PaintType, TilingType,
+        Color, DeviceNSubtype, FunctionShadingType, OutputIntentSubtype, PaintType,
+        TilingType,
     };
     pub use content::{
         ArtifactAttachment, ArtifactSubtype, ArtifactType, BlendMode, ColorSpaceOperand,
-        LineCapStyle, LineJoinStyle, MaskType, OverprintMode, ProcSet, RenderingIntent,
-        TextRenderingMode,
+        DashPattern, LineCapStyle, LineJoinStyle, MaskType, OverprintMode, ProcSet,
+        RenderingIntent, SpotFunction, TextRenderingMode, ThresholdHalftoneType,
+    };
+    pub use font::{
+        CidCmap, CidFontType, FontFileType, FontFlags, FontStretch, StandardFont,
+        SystemInfo, UnicodeCmap,
     };
-    pub use font::{CidFontType, FontFlags, FontStretch, SystemInfo, UnicodeCmap};
+    pub use formatting::{LineEnding, XrefLineEnding};
     pub use forms::{
-        CheckBoxState, ChoiceOptions, FieldFlags, FieldType, Quadding, SigFlags,
+        BarcodeSymbology, ButtonStyle, CheckBoxState, ChoiceOptions, FieldFlags,
+        FieldType, Quadding, SigFlags,
     };
     pub use functions::{InterpolationOrder, PostScriptOp};
     pub use object::Predictor;
-    pub use renditions::{MediaClipType, RenditionType, TempFileType};
+    pub use optional::{OcEvent, OcUsageCategory, VisibilityOperator, VisibilityPolicy};
+    pub use renditions::{MediaClipType, RenditionType, SoundEncoding, TempFileType};
+    pub use richmedia::{
+        RichMediaActivationCondition, RichMediaDeactivationCondition, RichMediaType,
+    };
+    pub use signature::{FieldMdpAction, MdpPermissions};
     pub use structure::{
-        Direction, NumberingStyle, OutlineItemFlags, PageLayout, PageMode, StructRole,
-        TabOrder, TrappingStatus,
+        Direction, GuideStyle, NumberingStyle, OutlineItemFlags, PageLayout, PageMode,
+        PdfVersion, Permissions, StructRole, TabOrder, TrappingStatus,
     };
     pub use transitions::{TransitionAngle, TransitionStyle};
     pub use xobject::SMaskInData;
 }
 
+pub use self::annotations::AnnotationSpec;
 pub use self::chunk::Chunk;
 pub use self::content::Content;
+pub use self::document::Document;
+pub use self::fdf::Fdf;
 pub use self::object::{
-    Array, Date, Dict, Filter, Finish, Name, Null, Obj, Primitive, Rect, Ref, Rewrite,
-    Str, Stream, TextStr, TypedArray, TypedDict, Writer,
+    Array, Date, Dict, Filter, Finish, LiteralStr, Name, Null, Obj, Point, Preset,
+    Primitive, Quad, Rect, Ref, Rewrite, Str, Stream, TextStr, TextStrUtf8, TypedArray,
+    TypedDict, Writer,
 };
+pub use self::observer::Observer;
+pub use self::stats::{ObjectStats, Stats};
+pub use self::table::{TableCell, TableTagger};
+pub use self::toc::TocEntry;
 
-use std::fmt::{self, Debug, Formatter};
-use std::io::Write;
-use std::ops::{Deref, DerefMut};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::fmt::{self, Debug, Formatter};
+use core::ops::{Deref, DerefMut, Range};
 
 use self::buf::BufExt;
+use self::observer::Observing;
+use self::types::{LineEnding, PdfVersion, Permissions, XrefLineEnding};
 use self::writers::*;
 
 /// A builder for a PDF file.
@@ -204,11 +297,26 @@ use self::writers::*;
 /// into a [`Chunk`], which you can add to the [`Pdf`] (or another chunk) later.
 /// Therefore, most writing methods are exposed on the chunk type, which this
 /// type dereferences to.
+///
+/// Indirect objects can be written in any order: The cross-reference table is
+/// only built in [`finish`](Self::finish), sorted by object id. Since only one
+/// writer can borrow a buffer at a time, objects that need to be written in an
+/// interleaved fashion (start object A, write object B, resume A) should be
+/// written into separate chunks, which are then merged into the PDF with
+/// [`Chunk::extend`] before finishing.
 pub struct Pdf {
     chunk: Chunk,
     catalog_id: Option<Ref>,
     info_id: Option<Ref>,
+    xref_stream_id: Option<Ref>,
     file_id: Option<(Vec<u8>, Vec<u8>)>,
+    auto_producer: bool,
+    producer: Option<String>,
+    line_limit: Option<usize>,
+    line_ending: LineEnding,
+    xref_line_ending: XrefLineEnding,
+    freed: BTreeMap<i32, u16>,
+    restrictions: Option<(Ref, Vec<u8>, Permissions)>,
 }
 
 impl Pdf {
@@ -226,7 +334,15 @@ impl Pdf {
             chunk,
             catalog_id: None,
             info_id: None,
+            xref_stream_id: None,
             file_id: None,
+            auto_producer: true,
+            producer: None,
+            line_limit: None,
+            line_ending: LineEnding::Lf,
+            xref_line_ending: XrefLineEnding::CrLf,
+            freed: BTreeMap::new(),
+            restrictions: None,
         }
     }
 
@@ -245,6 +361,13 @@ impl Pdf {
         }
     }
 
+    /// Set the PDF version from a [`PdfVersion`].
+    ///
+    /// Set this to [`PdfVersion::V2_0`] when using constructs from ISO 32000-2.
+    pub fn set_pdf_version(&mut self, version: PdfVersion) {
+        self.set_version(version.major(), version.minor());
+    }
+
     /// Set the file identifier for the document.
     ///
     /// The file identifier is a pair of two byte strings that shall be used to
@@ -255,6 +378,114 @@ impl Pdf {
         self.file_id = Some(id);
     }
 
+    /// Set whether the `/Producer` of the [document
+    /// information](Self::document_info) and the `pdf:Producer` of the
+    /// [metadata](Self::metadata) should automatically be set to this crate's
+    /// name and version, unless they are written explicitly.
+    ///
+    /// Disable this for output that only depends on what you write and does
+    /// not change with the crate version.
+    ///
+    /// _Default value_: `true`.
+    pub fn set_auto_producer(&mut self, enabled: bool) {
+        self.auto_producer = enabled;
+    }
+
+    /// Set a maximum line length for the indirect objects in the finished
+    /// file.
+    ///
+    /// Some legacy readers cannot handle very long lines and the specification
+    /// recommends that lines have at most 255 bytes. With a limit, long literal
+    /// and hexadecimal strings are split across lines and arrays are broken at
+    /// the spaces between their items in [`finish`](Self::finish). This
+    /// doesn't change the meaning of the objects. Stream data and tokens that
+    /// are too long by themselves, like long names, are left as is.
+    ///
+    /// Panics if the limit is smaller than 2.
+    ///
+    /// _Default value_: `None`.
+    pub fn set_line_limit(&mut self, limit: Option<usize>) {
+        assert!(!matches!(limit, Some(limit) if limit < 2), "line limit is too small");
+        self.line_limit = limit;
+    }
+
+    /// Set the end-of-line marker used between the lines of the header,
+    /// indirect objects and trailer.
+    ///
+    /// This is useful to produce byte-identical output to other tools. The
+    /// data of streams is never changed.
+    ///
+    /// _Default value_: [`LineEnding::Lf`].
+    pub fn set_line_ending(&mut self, ending: LineEnding) {
+        self.line_ending = ending;
+    }
+
+    /// Set the two bytes that end each entry of the cross-reference table.
+    ///
+    /// Entries always take 20 bytes, so the line feed or carriage return is
+    /// padded with a space if it is used alone.
+    ///
+    /// _Default value_: [`XrefLineEnding::CrLf`].
+    pub fn set_xref_line_ending(&mut self, ending: XrefLineEnding) {
+        self.xref_line_ending = ending;
+    }
+
+    /// Register a cross-reference stream to produce a hybrid-reference file.
+    /// PDF 1.5+.
+    ///
+    /// The trailer then points to the stream's byte offset with `/XRefStm`.
+    /// Readers that understand cross-reference streams additionally consult
+    /// it, usually to find objects stored in object streams, while PDF 1.4
+    /// readers only use the classic table and ignore those objects.
+    ///
+    /// This crate doesn't compress objects into object streams. You need to
+    /// write both these and the cross-reference stream itself, for instance
+    /// with [`Chunk::stream`].
+    ///
+    /// Panics in [`finish`](Self::finish) if no object with this id was
+    /// written.
+    pub fn set_xref_stream(&mut self, id: Option<Ref>) {
+        self.xref_stream_id = id;
+    }
+
+    /// Mark an object id as free in the cross-reference table.
+    ///
+    /// This is needed for incremental updates that delete objects: The id is
+    /// linked into the free list with the given `generation`, which should be
+    /// one more than the generation of the deleted object. A generation of
+    /// 65535 means that the id shall not be reused. Ids that are not written
+    /// and not marked as free are emitted as free with generation zero.
+    ///
+    /// Freed ids beyond the last written object extend the table. Only the
+    /// object number of `id` matters, so freeing the same number again
+    /// replaces the generation.
+    ///
+    /// Panics in [`finish`](Self::finish) if the id is also written.
+    pub fn free(&mut self, id: Ref, generation: u16) {
+        self.freed.insert(id.get(), generation);
+    }
+
+    /// Restrict what users may do with the document without requiring a
+    /// password to open it.
+    ///
+    /// In [`finish`](Self::finish), this writes an encryption dictionary with
+    /// the id `id` for the standard security handler and registers it with the
+    /// trailer. Since its crypt filters are `Identity`, strings and streams
+    /// are not encrypted, but readers honor the `permissions` unless the
+    /// `owner_password` is entered. If no [file identifier](Self::set_file_id)
+    /// was set, one is derived from the document's contents. PDF 1.5+.
+    ///
+    /// Note that such restrictions are only advisory, as the document can
+    /// still be read without any password.
+    pub fn restrict_permissions(
+        &mut self,
+        id: Ref,
+        owner_password: &[u8],
+        permissions: Permissions,
+    ) {
+        self.restrictions = Some((id, owner_password.to_vec(), permissions));
+    }
+
     /// Start writing the document catalog. Required.
     ///
     /// This will also register the document catalog with the file trailer,
@@ -271,62 +502,243 @@ impl Pdf {
     /// anywhere else.
     pub fn document_info(&mut self, id: Ref) -> DocumentInfo<'_> {
         self.info_id = Some(id);
-        self.indirect(id).start()
+        let mut info: DocumentInfo = self.chunk.indirect(id).start();
+        if self.auto_producer {
+            info.auto_producer();
+        }
+        info.producer = Some(&mut self.producer);
+        info
+    }
+
+    /// Start writing a metadata stream with an XMP packet.
+    ///
+    /// Unless [disabled](Self::set_auto_producer), a `pdf:Producer` is added
+    /// to packets that don't have one, so that it matches the `/Producer` of
+    /// the document information: This is the producer explicitly written with
+    /// [`DocumentInfo::producer`] or else this crate's name and version. To
+    /// pick up an explicit producer, the document information must be written
+    /// before the metadata.
+    pub fn metadata<'a>(&'a mut self, id: Ref, bytes: &'a [u8]) -> Metadata<'a> {
+        let find = |needle: &[u8]| bytes.windows(needle.len()).position(|w| w == needle);
+        let end = match find(b"</rdf:RDF>") {
+            Some(end) if self.auto_producer && find(b"pdf:Producer").is_none() => end,
+            _ => return self.chunk.metadata(id, bytes),
+        };
+
+        let producer = self.producer.as_deref().unwrap_or(structure::AUTO_PRODUCER);
+
+        let start = self.chunk.buf.len();
+        self.chunk.buf.extend(&bytes[..end]);
+        self.chunk.buf.extend(b"<rdf:Description rdf:about=\"\" ");
+        self.chunk.buf.extend(b"xmlns:pdf=\"http://ns.adobe.com/pdf/1.3/\">");
+        self.chunk.buf.extend(b"<pdf:Producer>");
+        for c in producer.chars() {
+            match c {
+                '&' => self.chunk.buf.extend(b"&amp;"),
+                '<' => self.chunk.buf.extend(b"&lt;"),
+                '>' => self.chunk.buf.extend(b"&gt;"),
+                _ => self.chunk.buf.extend(c.encode_utf8(&mut [0; 4]).as_bytes()),
+            }
+        }
+        self.chunk.buf.extend(b"</pdf:Producer></rdf:Description>");
+        self.chunk.buf.extend(&bytes[end..]);
+        let len = self.chunk.buf.len() - start;
+
+        self.chunk.record(id, start);
+        let obj = Obj::indirect(&mut self.chunk.buf, id);
+        Metadata::start(Stream::start_preloaded(obj, start, len))
     }
 
     /// Write the cross-reference table and file trailer and return the
     /// underlying buffer.
     ///
+    /// With the `validate` feature, the finished file is re-parsed to check
+    /// the cross-reference table, object headers, stream lengths and balanced
+    /// delimiters. This is meant to catch bugs in tests and panics if the file
+    /// is malformed.
+    ///
+    /// A registered [observer](Chunk::set_observer) is notified about the last
+    /// object and then dropped.
+    ///
     /// Panics if any indirect reference id was used twice.
     pub fn finish(self) -> Vec<u8> {
-        let Chunk { mut buf, mut offsets } = self.chunk;
+        self.finish_with_layout().bytes
+    }
 
+    /// Write the cross-reference table and file trailer like
+    /// [`finish`](Self::finish), but also return where the cross-reference
+    /// table and each indirect object ended up in the file.
+    ///
+    /// This is useful for post-processors that patch the finished file, e.g.
+    /// to fill in a signature or to linearize it.
+    ///
+    /// Panics if any indirect reference id was used twice.
+    pub fn finish_with_layout(mut self) -> FinishedPdf {
+        let (mut buf, mut offsets, encrypt_id) = self.finish_body();
         offsets.sort();
 
-        let xref_len = 1 + offsets.last().map_or(0, |p| p.0.get());
         let xref_offset = buf.len();
+        let tail = self.finish_tail(&offsets, encrypt_id, xref_offset);
 
-        buf.extend(b"xref\n0 ");
-        buf.push_int(xref_len);
-        buf.push(b'\n');
+        // Determine the extent of each object, which ends at the latest where
+        // the next object or the cross-reference table starts.
+        let mut by_offset = offsets.clone();
+        by_offset.sort_by_key(|&(_, offset)| offset);
+        let mut ends = BTreeMap::new();
+        for (i, &(id, start)) in by_offset.iter().enumerate() {
+            let next = by_offset.get(i + 1).map_or(xref_offset, |&(_, offset)| offset);
+            ends.insert(id, start + chunk::object_len(&buf[start..next]));
+        }
+        let objects = offsets
+            .into_iter()
+            .map(|(id, start)| (id, start..ends[&id]))
+            .collect();
+
+        buf.extend(tail);
+
+        #[cfg(feature = "validate")]
+        if let Err(err) = validate::validate(&buf) {
+            panic!("invalid PDF: {err}");
+        }
+
+        FinishedPdf { bytes: buf, xref_offset, objects }
+    }
+
+    /// Write the cross-reference table and file trailer and asynchronously
+    /// write the whole file into the given writer.
+    ///
+    /// This is useful to avoid blocking an executor thread when serving large
+    /// PDFs: The already buffered objects are written as they are and only the
+    /// cross-reference table and trailer are built on the calling thread.
+    /// With a [line limit](Self::set_line_limit) or
+    /// [`CrLf`](LineEnding::CrLf) line endings, all objects are rewritten
+    /// before anything is written, which blocks like [`finish`](Self::finish).
+    /// For huge files, consider calling `finish` in
+    /// `tokio::task::spawn_blocking` in that case. Requires the `tokio`
+    /// feature.
+    ///
+    /// Panics if any indirect reference id was used twice.
+    #[cfg(feature = "tokio")]
+    pub async fn finish_async<W>(mut self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: tokio::io::AsyncWrite + Unpin,
+    {
+        use tokio::io::AsyncWriteExt;
+        let (body, mut offsets, encrypt_id) = self.finish_body();
+        offsets.sort();
+
+        let tail = self.finish_tail(&offsets, encrypt_id, body.len());
+
+        #[cfg(feature = "validate")]
+        if let Err(err) = validate::validate(&[&body[..], &tail].concat()) {
+            panic!("invalid PDF: {err}");
+        }
+
+        writer.write_all(&body).await?;
+        writer.write_all(&tail).await?;
+        writer.flush().await
+    }
+
+    /// Write the encryption dictionary, report the last object to the
+    /// observer and apply the line formatting. Returns the body of the file,
+    /// the offsets of its objects and the id of the encryption dictionary.
+    fn finish_body(&mut self) -> (Vec<u8>, Vec<(Ref, usize)>, Option<Ref>) {
+        let mut encrypt_id = None;
+        if let Some((id, owner_password, permissions)) = self.restrictions.take() {
+            let chunk = &mut self.chunk;
+            let file_id = self.file_id.get_or_insert_with(|| {
+                let hash = encryption::md5(&chunk.buf).to_vec();
+                (hash.clone(), hash)
+            });
+            let obj = chunk.indirect(id);
+            encryption::write_restrictions(obj, &owner_password, permissions, &file_id.0);
+            encrypt_id = Some(id);
+        }
+
+        let mut chunk = core::mem::replace(&mut self.chunk, Chunk::with_capacity(0));
+        chunk.take_observer();
+        let Chunk { mut buf, mut offsets, .. } = chunk;
 
-        if offsets.is_empty() {
-            write!(buf, "0000000000 65535 f\r\n").unwrap();
+        if let Some(limit) = self.line_limit {
+            buf = formatting::rewrite_objects(&buf, &mut offsets, |obj, out| {
+                formatting::wrap(obj, limit, out)
+            });
         }
 
-        let mut written = 0;
-        for (i, (object_id, offset)) in offsets.iter().enumerate() {
-            if written > object_id.get() {
+        if self.line_ending == LineEnding::CrLf {
+            buf = formatting::rewrite_objects(&buf, &mut offsets, formatting::crlf);
+        }
+
+        (buf, offsets, encrypt_id)
+    }
+
+    /// Write the cross-reference table, the trailer and the end of file marker
+    /// for a body of `xref_offset` bytes with the given sorted object offsets.
+    fn finish_tail(
+        &self,
+        offsets: &[(Ref, usize)],
+        encrypt_id: Option<Ref>,
+        xref_offset: usize,
+    ) -> Vec<u8> {
+        let nl = self.line_ending.to_bytes();
+        let eol = self.xref_line_ending.to_bytes();
+
+        let freed = &self.freed;
+        let last_used = offsets.last().map_or(0, |p| p.0.get());
+        let last_freed = freed.keys().last().copied().unwrap_or(0);
+        let xref_len = 1 + last_used.max(last_freed);
+
+        // Collect the offset of each used object. Everything else is free.
+        let mut entries = vec![None; xref_len as usize];
+        for &(object_id, offset) in offsets {
+            let entry = &mut entries[object_id.get() as usize];
+            if entry.replace((offset, object_id.generation())).is_some() {
                 panic!("duplicate indirect reference id: {}", object_id.get());
             }
+        }
 
-            // Fill in free list.
-            let start = written;
-            for free_id in start..object_id.get() {
-                let mut next = free_id + 1;
-                if next == object_id.get() {
-                    // Find next free id.
-                    for (used_id, _) in &offsets[i..] {
-                        if next < used_id.get() {
-                            break;
-                        } else {
-                            next = used_id.get() + 1;
-                        }
-                    }
-                }
-
-                let gen = if free_id == 0 { "65535" } else { "00000" };
-                write!(buf, "{:010} {} f\r\n", next % xref_len, gen).unwrap();
-                written += 1;
+        for &object_id in freed.keys() {
+            if entries[object_id as usize].is_some() {
+                panic!("freed indirect reference id is in use: {object_id}");
             }
+        }
 
-            write!(buf, "{:010} 00000 n\r\n", offset).unwrap();
-            written += 1;
+        // Each cross-reference entry takes exactly 20 bytes.
+        let mut buf = Vec::with_capacity(20 * xref_len as usize + 256);
+        buf.extend(b"xref");
+        buf.extend(nl);
+        buf.extend(b"0 ");
+        buf.push_int(xref_len);
+        buf.extend(nl);
+
+        // Each free entry links to the next free one and the last one links
+        // back to the head of the list at object zero.
+        let free: Vec<usize> =
+            (0..entries.len()).filter(|&i| entries[i].is_none()).collect();
+        let mut next_free = free.iter().skip(1);
+        for (i, entry) in entries.iter().enumerate() {
+            match entry {
+                Some((offset, gen)) => {
+                    buf.push_fmt(format_args!("{:010} {:05} n", offset, gen));
+                    buf.extend(eol);
+                }
+                None => {
+                    let next = next_free.next().copied().unwrap_or(0);
+                    let gen = match i {
+                        0 => u16::MAX,
+                        _ => freed.get(&(i as i32)).copied().unwrap_or(0),
+                    };
+                    buf.push_fmt(format_args!("{:010} {:05} f", next, gen));
+                    buf.extend(eol);
+                }
+            }
         }
 
         // Write the trailer dictionary.
-        buf.extend(b"trailer\n");
+        buf.extend(b"trailer");
+        buf.extend(nl);
 
+        let trailer_start = buf.len();
         let mut trailer = Obj::direct(&mut buf, 0).dict();
         trailer.pair(Name(b"Size"), xref_len);
 
@@ -338,7 +750,21 @@ impl Pdf {
             trailer.pair(Name(b"Info"), info_id);
         }
 
-        if let Some(file_id) = self.file_id {
+        if let Some(encrypt_id) = encrypt_id {
+            trailer.pair(Name(b"Encrypt"), encrypt_id);
+        }
+
+        if let Some(xref_stream_id) = self.xref_stream_id {
+            let offset = entries
+                .get(xref_stream_id.get() as usize)
+                .copied()
+                .flatten()
+                .map(|(offset, _)| offset)
+                .expect("cross-reference stream was not written");
+            trailer.pair(Name(b"XRefStm"), offset as i32);
+        }
+
+        if let Some(file_id) = &self.file_id {
             let mut ids = trailer.insert(Name(b"ID")).array();
             ids.item(Str(&file_id.0));
             ids.item(Str(&file_id.1));
@@ -346,16 +772,56 @@ impl Pdf {
 
         trailer.finish();
 
+        if self.line_ending == LineEnding::CrLf {
+            let trailer = buf.split_off(trailer_start);
+            formatting::crlf(&trailer, &mut buf);
+        }
+
         // Write where the cross-reference table starts.
-        buf.extend(b"\nstartxref\n");
-        write!(buf, "{}", xref_offset).unwrap();
+        buf.extend(nl);
+        buf.extend(b"startxref");
+        buf.extend(nl);
+        buf.push_fmt(format_args!("{}", xref_offset));
 
         // Write the end of file marker.
-        buf.extend(b"\n%%EOF");
+        buf.extend(nl);
+        buf.extend(b"%%EOF");
         buf
     }
 }
 
+/// A finished PDF file along with the location of its parts.
+///
+/// This struct is created by [`Pdf::finish_with_layout`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FinishedPdf {
+    /// The bytes of the file.
+    pub bytes: Vec<u8>,
+    /// The byte offset of the cross-reference table.
+    pub xref_offset: usize,
+    /// The byte ranges of the indirect objects, from the object number up to
+    /// and including the `endobj` keyword, sorted by object id.
+    pub objects: Vec<(Ref, Range<usize>)>,
+}
+
+impl FinishedPdf {
+    /// The number of indirect objects in the file.
+    pub fn object_count(&self) -> usize {
+        self.objects.len()
+    }
+
+    /// The byte range of the indirect object with the object number of the
+    /// given id. Its generation is not compared, since each number occurs at
+    /// most once in a file.
+    pub fn object(&self, id: Ref) -> Option<Range<usize>> {
+        let i = self
+            .objects
+            .binary_search_by_key(&id.get(), |(id, _)| id.get())
+            .ok()?;
+        Some(self.objects[i].1.clone())
+    }
+}
+
 impl Debug for Pdf {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.pad("Pdf(..)")
@@ -474,6 +940,260 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_xref_freed_ids() {
+        let mut w = Pdf::new();
+        w.indirect(Ref::new(1)).primitive(1);
+        w.indirect(Ref::new(3)).primitive(3);
+        w.free(Ref::new(2).with_generation(3), 2);
+        w.free(Ref::new(2), 1);
+        w.free(Ref::new(5).with_generation(1), 65535);
+        test!(
+            w.finish(),
+            b"%PDF-1.7\n%\x80\x80\x80\x80\n",
+            b"1 0 obj\n1\nendobj\n",
+            b"3 0 obj\n3\nendobj\n",
+            b"xref",
+            b"0 6",
+            b"0000000002 65535 f\r",
+            b"0000000016 00000 n\r",
+            b"0000000004 00001 f\r",
+            b"0000000034 00000 n\r",
+            b"0000000005 00000 f\r",
+            b"0000000000 65535 f\r",
+            b"trailer",
+            b"<<\n  /Size 6\n>>",
+            b"startxref\n52\n%%EOF",
+        )
+    }
+
+    #[test]
+    fn test_restrict_permissions() {
+        let mut w = Pdf::new();
+        w.set_file_id((b"abc".to_vec(), b"abc".to_vec()));
+        w.restrict_permissions(Ref::new(1), b"owner", Permissions::PRINT);
+        let buf = w.finish();
+        let text = String::from_utf8_lossy(&buf);
+        assert!(text.contains(
+            "1 0 obj\n<<\n  /Filter /Standard\n  /V 4\n  /R 4\n  /Length 128\n  \
+             /StmF /Identity\n  /StrF /Identity\n  \
+             /O <566FA873EE33C797CD3B904FDADF814AFA34DF9A38F6ED41B984E2C6DA2AA6F5>\n  \
+             /U <A7103E8A4B0E61A3238C1952C80F87D800000000000000000000000000000000>\n  \
+             /P -3900\n  /EncryptMetadata false\n>>\nendobj"
+        ));
+        assert!(text.contains("/Encrypt 1 0 R\n  /ID [(abc) (abc)]"));
+    }
+
+    #[test]
+    #[should_panic(expected = "freed indirect reference id is in use: 1")]
+    fn test_xref_freed_id_in_use() {
+        let mut w = Pdf::new();
+        w.indirect(Ref::new(1)).primitive(1);
+        w.free(Ref::new(1), 1);
+        w.finish();
+    }
+
+    #[test]
+    fn test_pdf_version() {
+        let mut w = Pdf::new();
+        w.set_pdf_version(PdfVersion::V2_0);
+        w.catalog(Ref::new(1)).pdf_version(PdfVersion::V2_0);
+        let buf = w.finish();
+        assert!(buf.starts_with(b"%PDF-2.0\n"));
+        assert!(memchr::memmem::find(&buf, b"/Version /2.0").is_some());
+    }
+
+    #[test]
+    fn test_finish_with_layout() {
+        let mut w = Pdf::new();
+        w.indirect(Ref::new(2)).primitive(2);
+        w.indirect(Ref::new(1)).primitive(1);
+        let finished = w.finish_with_layout();
+        assert_eq!(finished.object_count(), 2);
+        assert_eq!(finished.object(Ref::new(1)), Some(34..50));
+        assert_eq!(&finished.bytes[34..50], b"1 0 obj\n1\nendobj");
+        assert_eq!(finished.objects[1], (Ref::new(2), 16..32));
+        assert_eq!(finished.object(Ref::new(3)), None);
+        assert_eq!(finished.object(Ref::new(2).with_generation(1)), Some(16..32));
+        assert!(finished.bytes[finished.xref_offset..].starts_with(b"xref\n"));
+    }
+
+    #[test]
+    fn test_line_limit() {
+        let mut w = Pdf::new();
+        w.set_line_limit(Some(12));
+        w.indirect(Ref::new(2)).array().items([10, 20, 30, 40]);
+        w.indirect(Ref::new(1)).primitive(Str(b"long string"));
+        let finished = w.finish_with_layout();
+        assert_eq!(finished.object(Ref::new(1)), Some(46..76));
+        assert_eq!(&finished.bytes[46..76], b"1 0 obj\n(long strin\\\ng)\nendobj");
+        assert_eq!(finished.object(Ref::new(2)), Some(16..44));
+        assert_eq!(&finished.bytes[16..44], b"2 0 obj\n[10 20 30\n40]\nendobj");
+    }
+
+    #[test]
+    fn test_line_endings() {
+        let mut w = Pdf::new();
+        w.set_line_ending(LineEnding::CrLf);
+        w.set_xref_line_ending(XrefLineEnding::SpaceLf);
+        w.stream(Ref::new(1), b"a\nb");
+        test!(
+            w.finish(),
+            b"%PDF-1.7\r\n%\x80\x80\x80\x80\r\n\r",
+            b"1 0 obj\r\n<<\r\n  /Length 3\r\n>>\r\nstream\r\na",
+            b"b\r\nendstream\r\nendobj\r\n\r",
+            b"xref\r\n0 2\r",
+            b"0000000000 65535 f ",
+            b"0000000019 00000 n ",
+            b"trailer\r\n<<\r\n  /Size 2\r\n>>\r",
+            b"startxref\r\n83\r\n%%EOF",
+        );
+    }
+
+    #[test]
+    fn test_generation_numbers() {
+        let mut w = Pdf::new();
+        let id = Ref::new(1).with_generation(2);
+        w.indirect(id).primitive(1);
+        w.indirect(Ref::new(2)).primitive(id);
+        test!(
+            w.finish(),
+            b"%PDF-1.7\n%\x80\x80\x80\x80\n",
+            b"1 2 obj\n1\nendobj\n",
+            b"2 0 obj\n1 2 R\nendobj\n",
+            b"xref",
+            b"0 3",
+            b"0000000000 65535 f\r",
+            b"0000000016 00002 n\r",
+            b"0000000034 00000 n\r",
+            b"trailer",
+            b"<<\n  /Size 3\n>>",
+            b"startxref\n56\n%%EOF",
+        );
+    }
+
+    #[test]
+    fn test_xref_stream_hybrid() {
+        let mut w = Pdf::new();
+        w.set_xref_stream(Some(Ref::new(2)));
+        w.indirect(Ref::new(1)).primitive(1);
+        w.stream(Ref::new(2), b"").pair(Name(b"Type"), Name(b"XRef"));
+        let buf = w.finish();
+        assert!(memchr::memmem::find(&buf, b"  /XRefStm 34\n").is_some());
+        assert!(buf[34..].starts_with(b"2 0 obj"));
+    }
+
+    #[test]
+    #[should_panic(expected = "cross-reference stream was not written")]
+    fn test_xref_stream_missing() {
+        let mut w = Pdf::new();
+        w.set_xref_stream(Some(Ref::new(1)));
+        w.finish();
+    }
+
+    #[test]
+    fn test_xref_interleaved_chunks() {
+        let mut w = Pdf::new();
+        w.indirect(Ref::new(3)).primitive(3);
+        let mut chunk = Chunk::new();
+        let mut array = chunk.indirect(Ref::new(1)).array();
+        array.item(Ref::new(2));
+        w.indirect(Ref::new(2)).primitive(2);
+        array.item(Ref::new(3));
+        array.finish();
+        w.extend(&chunk);
+        test!(
+            w.finish(),
+            b"%PDF-1.7\n%\x80\x80\x80\x80\n",
+            b"3 0 obj\n3\nendobj\n",
+            b"2 0 obj\n2\nendobj\n",
+            b"1 0 obj\n[2 0 R 3 0 R]\nendobj\n",
+            b"xref",
+            b"0 4",
+            b"0000000000 65535 f\r",
+            b"0000000052 00000 n\r",
+            b"0000000034 00000 n\r",
+            b"0000000016 00000 n\r",
+            b"trailer",
+            b"<<\n  /Size 4\n>>",
+            b"startxref\n82\n%%EOF",
+        )
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_finish_async() {
+        let write = |crlf: bool| {
+            let mut w = Pdf::new();
+            if crlf {
+                w.set_line_ending(LineEnding::CrLf);
+            }
+            w.catalog(Ref::new(1)).pages(Ref::new(2));
+            w.pages(Ref::new(2)).count(0);
+            w
+        };
+        for crlf in [false, true] {
+            let mut out = vec![];
+            write(crlf).finish_async(&mut out).await.unwrap();
+            assert_eq!(out, write(crlf).finish());
+        }
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let write = || {
+            let mut w = Pdf::new();
+            w.document_info(Ref::new(2))
+                .title(TextStr("T"))
+                .creation_date(Date::new(2024));
+            w.catalog(Ref::new(1)).pages(Ref::new(3)).lang(TextStr("en"));
+            w.pages(Ref::new(3))
+                .count(0)
+                .media_box(Rect::new(0.0, 0.0, 0.1, 1e-7));
+            w.finish()
+        };
+        assert_eq!(write(), write());
+    }
+
+    #[test]
+    fn test_auto_producer() {
+        let mut w = Pdf::new();
+        w.document_info(Ref::new(1)).title(TextStr("A"));
+        w.document_info(Ref::new(2))
+            .producer(TextStr("B"))
+            .title(TextStr("C"));
+        w.metadata(Ref::new(3), b"<rdf:RDF></rdf:RDF>");
+        let buf = w.finish();
+        let version = env!("CARGO_PKG_VERSION");
+        let text = String::from_utf8_lossy(&buf);
+        let producer = format!("/Producer (pdf-writer {version})");
+        assert_eq!(text.matches(&producer).count(), 1);
+        assert_eq!(text.matches("/Producer").count(), 2);
+        assert!(text.contains("<<\n  /Producer (B)\n  /Title (C)\n>>"));
+        assert!(text.contains("<pdf:Producer>B</pdf:Producer>"));
+
+        let mut w = Pdf::new();
+        w.metadata(Ref::new(1), b"<rdf:RDF></rdf:RDF>");
+        let buf = w.finish();
+        let text = String::from_utf8_lossy(&buf);
+        assert!(text.contains(&format!("<pdf:Producer>pdf-writer {version}<")));
+
+        let mut w = Pdf::new();
+        w.document_info(Ref::new(1)).producer(TextStr("A & B"));
+        w.metadata(Ref::new(2), b"<rdf:RDF></rdf:RDF>");
+        let buf = w.finish();
+        let text = String::from_utf8_lossy(&buf);
+        assert!(text.contains("/Producer (A & B)"));
+        assert!(text.contains("<pdf:Producer>A &amp; B</pdf:Producer>"));
+
+        let mut w = Pdf::new();
+        w.set_auto_producer(false);
+        w.document_info(Ref::new(1)).title(TextStr("A"));
+        w.metadata(Ref::new(2), b"<rdf:RDF></rdf:RDF>");
+        let buf = w.finish();
+        assert!(!String::from_utf8_lossy(&buf).contains("Producer"));
+    }
+
     #[test]
     #[should_panic(expected = "duplicate indirect reference id: 3")]
     fn test_xref_free_list_duplicate() {
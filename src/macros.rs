@@ -50,7 +50,7 @@ macro_rules! writer {
 /// Implements `Deref` and `DerefMut` by delegating to a field of a struct.
 macro_rules! deref {
     ($a:lifetime, $from:ty => $to:ty, $field:ident) => {
-        impl<$a> std::ops::Deref for $from {
+        impl<$a> core::ops::Deref for $from {
             type Target = $to;
 
             #[inline]
@@ -59,7 +59,7 @@ macro_rules! deref {
             }
         }
 
-        impl<$a> std::ops::DerefMut for $from {
+        impl<$a> core::ops::DerefMut for $from {
             #[inline]
             fn deref_mut(&mut self) -> &mut Self::Target {
                 &mut self.$field
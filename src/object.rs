@@ -1,7 +1,7 @@
-use std::convert::TryFrom;
-use std::marker::PhantomData;
-use std::mem::ManuallyDrop;
-use std::num::NonZeroI32;
+use core::convert::TryFrom;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::num::NonZeroI32;
 
 use super::*;
 
@@ -48,11 +48,46 @@ impl Primitive for f32 {
 
 /// A string object (any byte sequence).
 ///
-/// This is written as `(Thing)`.
+/// This is written as `(Thing)`. Strings consisting only of ASCII are written
+/// as literal strings with escape sequences for delimiters and control
+/// characters. Anything else is written as a hexadecimal string like `<FFAA>`.
+/// Hence, arbitrary binary data can be written safely. Use [`LiteralStr`] to
+/// force a literal string instead.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Str<'a>(pub &'a [u8]);
 
-impl Str<'_> {
+impl Primitive for Str<'_> {
+    fn write(self, buf: &mut Vec<u8>) {
+        // We use:
+        // - Literal strings for ASCII with nice escape sequences to make it
+        //   also be represented fully in visible ASCII. We also escape
+        //   parentheses because they are delimiters.
+        // - Hex strings for anything non-ASCII.
+        if self.0.iter().all(|b| b.is_ascii()) {
+            LiteralStr(self.0).write(buf);
+        } else {
+            buf.reserve(2 + 2 * self.0.len());
+            buf.push(b'<');
+
+            for &byte in self.0 {
+                buf.push_hex(byte);
+            }
+
+            buf.push(b'>');
+        }
+    }
+}
+
+/// A string object that is always written as a literal string.
+///
+/// This is written as `(Thing)`. Unlike with [`Str`], non-ASCII bytes are
+/// written as octal escapes like `\377` instead of switching to a hexadecimal
+/// string. This is more compact for mostly-ASCII data and keeps the readable
+/// parts of the string visible.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct LiteralStr<'a>(pub &'a [u8]);
+
+impl LiteralStr<'_> {
     /// Whether the parentheses in the byte string are balanced.
     fn is_balanced(self) -> bool {
         let mut depth = 0;
@@ -68,53 +103,37 @@ impl Str<'_> {
     }
 }
 
-impl Primitive for Str<'_> {
+impl Primitive for LiteralStr<'_> {
     fn write(self, buf: &mut Vec<u8>) {
-        // We use:
-        // - Literal strings for ASCII with nice escape sequences to make it
-        //   also be represented fully in visible ASCII. We also escape
-        //   parentheses because they are delimiters.
-        // - Hex strings for anything non-ASCII.
-        if self.0.iter().all(|b| b.is_ascii()) {
-            buf.reserve(self.0.len());
-            buf.push(b'(');
+        buf.reserve(2 + self.0.len());
+        buf.push(b'(');
 
-            let mut balanced = None;
-            for &byte in self.0 {
-                match byte {
-                    b'(' | b')' => {
-                        if !*balanced
-                            .get_or_insert_with(|| byte != b')' && self.is_balanced())
-                        {
-                            buf.push(b'\\');
-                        }
-                        buf.push(byte);
-                    }
-                    b'\\' => buf.extend(br"\\"),
-                    b' '..=b'~' => buf.push(byte),
-                    b'\n' => buf.extend(br"\n"),
-                    b'\r' => buf.extend(br"\r"),
-                    b'\t' => buf.extend(br"\t"),
-                    b'\x08' => buf.extend(br"\b"),
-                    b'\x0c' => buf.extend(br"\f"),
-                    _ => {
+        let mut balanced = None;
+        for &byte in self.0 {
+            match byte {
+                b'(' | b')' => {
+                    if !*balanced
+                        .get_or_insert_with(|| byte != b')' && self.is_balanced())
+                    {
                         buf.push(b'\\');
-                        buf.push_octal(byte);
                     }
+                    buf.push(byte);
+                }
+                b'\\' => buf.extend(br"\\"),
+                b' '..=b'~' => buf.push(byte),
+                b'\n' => buf.extend(br"\n"),
+                b'\r' => buf.extend(br"\r"),
+                b'\t' => buf.extend(br"\t"),
+                b'\x08' => buf.extend(br"\b"),
+                b'\x0c' => buf.extend(br"\f"),
+                _ => {
+                    buf.push(b'\\');
+                    buf.push_octal(byte);
                 }
             }
-
-            buf.push(b')');
-        } else {
-            buf.reserve(2 + 2 * self.0.len());
-            buf.push(b'<');
-
-            for &byte in self.0 {
-                buf.push_hex(byte);
-            }
-
-            buf.push(b'>');
         }
+
+        buf.push(b')');
     }
 }
 
@@ -143,9 +162,48 @@ impl Primitive for TextStr<'_> {
     }
 }
 
+/// A unicode text string encoded as UTF-8. PDF 2.0+.
+///
+/// This is a more compact alternative to [`TextStr`] for non-ASCII text, but
+/// is only understood by PDF 2.0 readers. It is written as a literal string
+/// starting with the UTF-8 byte order mark.
+///
+/// Since writers take a [`TextStr`] for text attributes, this is written with
+/// [`Dict::pair`] or [`Array::item`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct TextStrUtf8<'a>(pub &'a str);
+
+impl Primitive for TextStrUtf8<'_> {
+    fn write(self, buf: &mut Vec<u8>) {
+        // ASCII and PDFDocEncoding match for 32 up to 126.
+        if self.0.bytes().all(|b| matches!(b, 32..=126)) {
+            Str(self.0.as_bytes()).write(buf);
+        } else {
+            buf.reserve(5 + self.0.len());
+            buf.extend(b"(\xEF\xBB\xBF");
+            for &byte in self.0.as_bytes() {
+                match byte {
+                    b'(' | b')' | b'\\' => {
+                        buf.push(b'\\');
+                        buf.push(byte);
+                    }
+                    b'\n' => buf.extend(br"\n"),
+                    b'\r' => buf.extend(br"\r"),
+                    _ => buf.push(byte),
+                }
+            }
+            buf.push(b')');
+        }
+    }
+}
+
 /// A name object.
 ///
-/// Written as `/Thing`.
+/// Written as `/Thing`. The bytes are the name without the leading slash and
+/// without any escapes: Delimiters, whitespace, the number sign and bytes
+/// outside of the printable ASCII range are escaped with the `#xx` notation
+/// automatically. Hence, arbitrary user-provided bytes always produce a
+/// well-formed name, e.g. `Name(b"A B")` is written as `/A#20B`.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Name<'a>(pub &'a [u8]);
 
@@ -202,8 +260,11 @@ impl Primitive for Null {
 }
 
 /// A reference to an indirect object.
+///
+/// A reference consists of an object number and a generation number, which is
+/// zero unless set with [`with_generation`](Self::with_generation).
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct Ref(NonZeroI32);
+pub struct Ref(NonZeroI32, u16);
 
 impl Ref {
     /// Create a new indirect reference.
@@ -216,7 +277,7 @@ impl Ref {
     pub const fn new(id: i32) -> Ref {
         let option = if id > 0 { NonZeroI32::new(id) } else { None };
         match option {
-            Some(val) => Self(val),
+            Some(val) => Self(val, 0),
             None => panic!("indirect reference out of valid range"),
         }
     }
@@ -227,7 +288,24 @@ impl Ref {
         self.0.get()
     }
 
-    /// The next consecutive ID.
+    /// Return the same reference with a different generation number.
+    ///
+    /// Objects in new files always have generation zero. When an object is
+    /// deleted in an incremental update, its id may be reused with a higher
+    /// generation. To update such an object faithfully, both the reference and
+    /// the object itself must carry that generation.
+    #[inline]
+    pub const fn with_generation(self, generation: u16) -> Self {
+        Self(self.0, generation)
+    }
+
+    /// Return the generation number.
+    #[inline]
+    pub const fn generation(self) -> u16 {
+        self.1
+    }
+
+    /// The next consecutive ID with generation zero.
     #[inline]
     pub const fn next(self) -> Self {
         Self::new(self.get() + 1)
@@ -247,7 +325,9 @@ impl Primitive for Ref {
     #[inline]
     fn write(self, buf: &mut Vec<u8>) {
         buf.push_int(self.0.get());
-        buf.extend(b" 0 R");
+        buf.push(b' ');
+        buf.push_int(i32::from(self.1));
+        buf.extend(b" R");
     }
 }
 
@@ -271,11 +351,131 @@ impl Rect {
         Self { x1, y1, x2, y2 }
     }
 
+    /// Create a new rectangle from its lower-left corner and its dimensions.
+    #[inline]
+    pub fn from_xywh(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self::new(x, y, x + width, y + height)
+    }
+
+    /// The smallest rectangle that contains both this and the other rectangle.
+    ///
+    /// The result is always specified by its lower-left and upper-right
+    /// corners.
+    #[inline]
+    pub fn union(self, other: Self) -> Self {
+        Self::new(
+            self.x1.min(self.x2).min(other.x1).min(other.x2),
+            self.y1.min(self.y2).min(other.y1).min(other.y2),
+            self.x1.max(self.x2).max(other.x1).max(other.x2),
+            self.y1.max(self.y2).max(other.y1).max(other.y2),
+        )
+    }
+
+    /// Grow the rectangle by `margin` on all four sides, assuming that the
+    /// first corner is the lower-left one. A negative margin shrinks it.
+    #[inline]
+    pub fn expand(self, margin: f32) -> Self {
+        Self::new(self.x1 - margin, self.y1 - margin, self.x2 + margin, self.y2 + margin)
+    }
+
     /// Convert this rectangle into 8 floats describing the four corners of the
     /// rectangle in counterclockwise order.
     #[inline]
     pub fn to_quad_points(self) -> [f32; 8] {
-        [self.x1, self.y1, self.x2, self.y1, self.x2, self.y2, self.x1, self.y2]
+        self.to_quad().to_array()
+    }
+
+    /// Convert this rectangle into a quadrilateral with the four corners in
+    /// counterclockwise order, starting at the first corner.
+    #[inline]
+    pub fn to_quad(self) -> Quad {
+        Quad::new(
+            Point::new(self.x1, self.y1),
+            Point::new(self.x2, self.y1),
+            Point::new(self.x2, self.y2),
+            Point::new(self.x1, self.y2),
+        )
+    }
+}
+
+/// A point, specified by its x- and y-coordinates.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Point {
+    /// The x-coordinate.
+    pub x: f32,
+    /// The y-coordinate.
+    pub y: f32,
+}
+
+impl Point {
+    /// Create a new point from its coordinates.
+    #[inline]
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+}
+
+/// A quadrilateral, specified by its four corners.
+///
+/// This is used for `/QuadPoints` arrays, for example in
+/// [markup annotations](crate::writers::Annotation::quad_points).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Quad {
+    /// The x-coordinate of the first corner.
+    pub x1: f32,
+    /// The y-coordinate of the first corner.
+    pub y1: f32,
+    /// The x-coordinate of the second corner.
+    pub x2: f32,
+    /// The y-coordinate of the second corner.
+    pub y2: f32,
+    /// The x-coordinate of the third corner.
+    pub x3: f32,
+    /// The y-coordinate of the third corner.
+    pub y3: f32,
+    /// The x-coordinate of the fourth corner.
+    pub x4: f32,
+    /// The y-coordinate of the fourth corner.
+    pub y4: f32,
+}
+
+impl Quad {
+    /// Create a new quadrilateral from its four corners.
+    #[inline]
+    pub fn new(p1: Point, p2: Point, p3: Point, p4: Point) -> Self {
+        Self {
+            x1: p1.x,
+            y1: p1.y,
+            x2: p2.x,
+            y2: p2.y,
+            x3: p3.x,
+            y3: p3.y,
+            x4: p4.x,
+            y4: p4.y,
+        }
+    }
+
+    /// The smallest axis-aligned rectangle containing all four corners.
+    #[inline]
+    pub fn bbox(self) -> Rect {
+        Rect::new(
+            self.x1.min(self.x2).min(self.x3).min(self.x4),
+            self.y1.min(self.y2).min(self.y3).min(self.y4),
+            self.x1.max(self.x2).max(self.x3).max(self.x4),
+            self.y1.max(self.y2).max(self.y3).max(self.y4),
+        )
+    }
+
+    /// Convert this quadrilateral into 8 floats describing its four corners.
+    #[inline]
+    pub fn to_array(self) -> [f32; 8] {
+        [self.x1, self.y1, self.x2, self.y2, self.x3, self.y3, self.x4, self.y4]
+    }
+}
+
+impl From<Rect> for Quad {
+    fn from(rect: Rect) -> Self {
+        rect.to_quad()
     }
 }
 
@@ -397,18 +597,20 @@ impl Primitive for Date {
         buf.extend(b"(D:");
 
         (|| {
-            write!(buf, "{:04}", self.year).unwrap();
-            write!(buf, "{:02}", self.month?).unwrap();
-            write!(buf, "{:02}", self.day?).unwrap();
-            write!(buf, "{:02}", self.hour?).unwrap();
-            write!(buf, "{:02}", self.minute?).unwrap();
-            write!(buf, "{:02}", self.second?).unwrap();
+            buf.push_fmt(format_args!("{:04}", self.year));
+            buf.push_fmt(format_args!("{:02}", self.month?));
+            buf.push_fmt(format_args!("{:02}", self.day?));
+            buf.push_fmt(format_args!("{:02}", self.hour?));
+            buf.push_fmt(format_args!("{:02}", self.minute?));
+            buf.push_fmt(format_args!("{:02}", self.second?));
             let utc_offset_hour = self.utc_offset_hour?;
             if utc_offset_hour == 0 && self.utc_offset_minute == 0 {
                 buf.push(b'Z');
             } else {
-                write!(buf, "{:+03}'{:02}", utc_offset_hour, self.utc_offset_minute)
-                    .unwrap();
+                buf.push_fmt(format_args!(
+                    "{:+03}'{:02}",
+                    utc_offset_hour, self.utc_offset_minute
+                ));
             }
             Some(())
         })();
@@ -436,7 +638,9 @@ impl<'a> Obj<'a> {
     #[inline]
     pub(crate) fn indirect(buf: &'a mut Vec<u8>, id: Ref) -> Self {
         buf.push_int(id.get());
-        buf.extend(b" 0 obj\n");
+        buf.push(b' ');
+        buf.push_int(i32::from(id.generation()));
+        buf.extend(b" obj\n");
         Self { buf, indirect: true, indent: 0 }
     }
 
@@ -449,6 +653,20 @@ impl<'a> Obj<'a> {
         }
     }
 
+    /// Write pre-serialized PDF syntax as this object.
+    ///
+    /// The bytes are written verbatim. This is an escape hatch for syntax that
+    /// isn't covered by the typed API, for example output from another
+    /// library. The caller is responsible for the bytes forming exactly one
+    /// valid PDF object.
+    #[inline]
+    pub fn raw(self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+        if self.indirect {
+            self.buf.extend(b"\nendobj\n\n");
+        }
+    }
+
     /// Start writing an array.
     #[inline]
     pub fn array(self) -> Array<'a> {
@@ -578,6 +796,10 @@ impl Drop for Array<'_> {
 }
 
 /// Writer for an array of items of a fixed type.
+///
+/// The items can be any [`Primitive`], e.g. numbers, [names](Name),
+/// [strings](Str), [references](Ref), [rectangles](Rect) and [dates](Date),
+/// or be written with a [`Writer`] through [`push`](Self::push).
 pub struct TypedArray<'a, T> {
     array: Array<'a>,
     phantom: PhantomData<fn() -> T>,
@@ -675,6 +897,18 @@ impl<'a> Dict<'a> {
         self.len == 0
     }
 
+    /// The current length of the underlying buffer.
+    pub(crate) fn buf_len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Remove a pair that was written at the given range of the underlying
+    /// buffer.
+    pub(crate) fn remove_pair(&mut self, range: Range<usize>) {
+        self.buf.drain(range);
+        self.len -= 1;
+    }
+
     /// Start writing a pair with an arbitrary value.
     #[inline]
     pub fn insert(&mut self, key: Name) -> Obj<'_> {
@@ -700,6 +934,15 @@ impl<'a> Dict<'a> {
         self
     }
 
+    /// Write a pair with pre-serialized PDF syntax as the value.
+    ///
+    /// This is a shorthand for `dict.insert(key).raw(bytes)`.
+    #[inline]
+    pub fn raw_pair(&mut self, key: Name, bytes: &[u8]) -> &mut Self {
+        self.insert(key).raw(bytes);
+        self
+    }
+
     /// Write a sequence of pairs with primitive values.
     pub fn pairs<'n, T: Primitive>(
         &mut self,
@@ -804,10 +1047,98 @@ impl<'a, T> TypedDict<'a, T> {
     }
 }
 
+/// A reusable set of dictionary pairs.
+///
+/// The values are serialized once when they are added to the preset and can
+/// then be written into any number of dictionaries with [`Dict::apply`]. This
+/// is useful when writing many similar objects, like annotations that all
+/// share the same flags and border style.
+///
+/// ```
+/// use pdf_writer::{Name, Pdf, Preset, Rect, Ref};
+///
+/// let mut border = Preset::new();
+/// border.pair(Name(b"W"), 0.5);
+/// let mut preset = Preset::new();
+/// preset.pair(Name(b"F"), 4).dict(Name(b"BS"), border);
+///
+/// let mut pdf = Pdf::new();
+/// for i in 1..=3 {
+///     let rect = Rect::new(0.0, 0.0, 10.0, 10.0);
+///     pdf.annotation(Ref::new(i)).rect(rect).apply(&preset);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Preset {
+    pairs: Vec<(Vec<u8>, PresetValue)>,
+}
+
+/// The value of a pair in a [`Preset`].
+#[derive(Debug, Clone)]
+enum PresetValue {
+    Primitive(Vec<u8>),
+    Dict(Preset),
+}
+
+impl Preset {
+    /// Create an empty preset.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a pair with a primitive value.
+    pub fn pair<T: Primitive>(&mut self, key: Name, value: T) -> &mut Self {
+        let mut buf = Vec::new();
+        value.write(&mut buf);
+        self.pairs.push((key.0.to_vec(), PresetValue::Primitive(buf)));
+        self
+    }
+
+    /// Add a pair with a nested dictionary as the value.
+    pub fn dict(&mut self, key: Name, preset: Preset) -> &mut Self {
+        self.pairs.push((key.0.to_vec(), PresetValue::Dict(preset)));
+        self
+    }
+
+    /// The number of pairs.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Whether the preset has no pairs.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+}
+
+impl Dict<'_> {
+    /// Write all pairs of a [`Preset`].
+    ///
+    /// The keys of the preset must not be written to this dictionary again.
+    pub fn apply(&mut self, preset: &Preset) -> &mut Self {
+        for (key, value) in &preset.pairs {
+            match value {
+                PresetValue::Primitive(bytes) => {
+                    self.raw_pair(Name(key), bytes);
+                }
+                PresetValue::Dict(nested) => {
+                    self.insert(Name(key)).dict().apply(nested);
+                }
+            }
+        }
+        self
+    }
+}
+
 /// Writer for an indirect stream object.
+///
+/// The stream dictionary is written first and the data is only appended when
+/// the writer is finished. Until then, arbitrary entries can be added to the
+/// dictionary through the [`Dict`] API the writer dereferences to.
 pub struct Stream<'a> {
     dict: ManuallyDrop<Dict<'a>>,
     data: &'a [u8],
+    preloaded: Option<(usize, usize)>,
 }
 
 impl<'a> Stream<'a> {
@@ -816,17 +1147,43 @@ impl<'a> Stream<'a> {
     /// Panics if the object writer is not indirect or the stream length exceeds
     /// `i32::MAX`.
     pub(crate) fn start(obj: Obj<'a>, data: &'a [u8]) -> Self {
+        let dict = Self::start_dict(obj, data.len());
+        Self {
+            dict: ManuallyDrop::new(dict),
+            data,
+            preloaded: None,
+        }
+    }
+
+    /// Start writing a stream whose data was already written into the
+    /// underlying buffer, immediately before the object.
+    ///
+    /// The data occupies `len` bytes starting at `start`. When the stream is
+    /// finished, the data is moved in-place behind the stream dictionary.
+    ///
+    /// Panics if the object writer is not indirect or the stream length exceeds
+    /// `i32::MAX`.
+    pub(crate) fn start_preloaded(obj: Obj<'a>, start: usize, len: usize) -> Self {
+        let dict = Self::start_dict(obj, len);
+        Self {
+            dict: ManuallyDrop::new(dict),
+            data: &[],
+            preloaded: Some((start, len)),
+        }
+    }
+
+    fn start_dict(obj: Obj<'a>, len: usize) -> Dict<'a> {
         assert!(obj.indirect);
 
         let mut dict = obj.dict();
         dict.pair(
             Name(b"Length"),
-            i32::try_from(data.len()).unwrap_or_else(|_| {
-                panic!("data length (is `{}`) must be <= i32::MAX", data.len());
+            i32::try_from(len).unwrap_or_else(|_| {
+                panic!("data length (is `{}`) must be <= i32::MAX", len);
             }),
         );
 
-        Self { dict: ManuallyDrop::new(dict), data }
+        dict
     }
 
     /// Write the `/Filter` attribute.
@@ -843,13 +1200,24 @@ impl<'a> Stream<'a> {
     pub fn decode_parms(&mut self) -> DecodeParms<'_> {
         self.insert(Name(b"DecodeParms")).start()
     }
+
+    /// Write the `/DL` attribute to hint at the number of bytes of the decoded
+    /// stream data. PDF 1.5+.
+    pub fn decoded_length(&mut self, len: i32) -> &mut Self {
+        self.pair(Name(b"DL"), len);
+        self
+    }
 }
 
 impl Drop for Stream<'_> {
     fn drop(&mut self) {
         self.dict.buf.extend(b"\n>>");
         self.dict.buf.extend(b"\nstream\n");
-        self.dict.buf.extend(self.data.as_ref());
+        if let Some((start, len)) = self.preloaded {
+            self.dict.buf[start..].rotate_left(len);
+        } else {
+            self.dict.buf.extend(self.data.as_ref());
+        }
         self.dict.buf.extend(b"\nendstream");
         self.dict.buf.extend(b"\nendobj\n\n");
     }
@@ -909,16 +1277,33 @@ impl DecodeParms<'_> {
         self
     }
 
+    /// Write the `/Predictor`, `/Colors`, `/BitsPerComponent` and `/Columns`
+    /// attributes for image samples that were encoded with PNG predictors
+    /// before compressing them with `FlateDecode` or `LzwDecode`.
+    ///
+    /// The predictor is written as [`PngOptimum`](Predictor::PngOptimum), so
+    /// each row of the samples must start with a byte choosing its PNG filter
+    /// type. The `Up` filter (type 2) shrinks most photographic and synthetic
+    /// images substantially. The other parameters must match the image's
+    /// number of color components, bits per component and width.
+    pub fn png_predictor(&mut self, colors: i32, bits: i32, columns: i32) -> &mut Self {
+        self.predictor(Predictor::PngOptimum);
+        self.colors(colors);
+        self.bits_per_component(bits);
+        self.columns(columns);
+        self
+    }
+
     /// Write the `/Colors` attribute for `FlateDecode` and `LzwDecode`.
     ///
     /// Must be greater than 0. [`/Predictor`](Self::predictor) must be set.
     /// Defaults to 1.
     pub fn colors(&mut self, colors: i32) -> &mut Self {
         if colors <= 0 {
-            panic!("`Columns` must be greater than 0");
+            panic!("`Colors` must be greater than 0");
         }
 
-        self.pair(Name(b"Columns"), colors);
+        self.pair(Name(b"Colors"), colors);
         self
     }
 
@@ -1288,6 +1673,23 @@ impl<T> Finish for T {}
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_rect_geometry() {
+        let a = Rect::from_xywh(10.0, 20.0, 30.0, 40.0);
+        assert_eq!(a, Rect::new(10.0, 20.0, 40.0, 60.0));
+        assert_eq!(a.expand(5.0), Rect::new(5.0, 15.0, 45.0, 65.0));
+        assert_eq!(
+            a.union(Rect::new(50.0, 0.0, 45.0, 30.0)),
+            Rect::new(10.0, 0.0, 50.0, 60.0)
+        );
+        assert_eq!(
+            Quad::from(a).to_array(),
+            [10.0, 20.0, 40.0, 20.0, 40.0, 60.0, 10.0, 60.0]
+        );
+        assert_eq!(a.to_quad_points(), Quad::from(a).to_array());
+        assert_eq!(Quad::from(a).bbox(), a);
+    }
+
     #[test]
     fn test_primitive_objects() {
         // Test really simple objects.
@@ -1312,10 +1714,26 @@ mod tests {
         test_primitive!(Str(b"a\x14b"), br"(a\024b)");
         test_primitive!(Str(b"\xFF\xAA"), b"<FFAA>");
         test_primitive!(Str(b"\x0A\x7F\x1F"), br"(\n\177\037)");
+        test_primitive!(LiteralStr(b"a\xFF(b"), br"(a\377\(b)");
+        test_primitive!(LiteralStr(b"(\x80)"), br"((\200))");
+
+        // Every byte sequence must be written as a single well-formed string.
+        let all: Vec<u8> = (0..=255).collect();
+        for bytes in [&all[..128], &all[..]] {
+            for string in [Str(bytes), Str(&bytes[40..])] {
+                let mut buf = vec![];
+                string.write(&mut buf);
+                let valid =
+                    crate::validate::validate_object(&[&buf[..], b" endobj"].concat());
+                assert_eq!(valid, Ok(()));
+            }
+        }
 
         // Test text strings.
         test_primitive!(TextStr("Hallo"), b"(Hallo)");
         test_primitive!(TextStr("😀!"), b"<FEFFD83DDE000021>");
+        test_primitive!(TextStrUtf8("Hallo"), b"(Hallo)");
+        test_primitive!(TextStrUtf8("ä (\\)"), b"(\xEF\xBB\xBF\xC3\xA4 \\(\\\\\\))");
 
         // Test names.
         test_primitive!(Name(b"Filter"), b"/Filter");
@@ -1325,6 +1743,19 @@ mod tests {
         test_primitive!(Name(b"<A>"), br"/#3CA#3E");
         test_primitive!(Name(b"#"), br"/#23");
         test_primitive!(Name(b"\n"), br"/#0A");
+        test_primitive!(Name(b""), b"/");
+        test_primitive!(Name("é".as_bytes()), br"/#C3#A9");
+
+        // Every byte sequence must be written as a single well-formed name.
+        let all: Vec<u8> = (0..=255).collect();
+        let mut buf = vec![];
+        Name(&all).write(&mut buf);
+        assert_eq!(buf[0], b'/');
+        assert!(buf[1..]
+            .iter()
+            .all(|&b| b.is_ascii_graphic() && is_regular_character(b) && b != b'/'));
+        // 94 printable characters minus 10 delimiters and the number sign.
+        assert_eq!(buf.len(), 1 + 83 + 3 * (256 - 83));
     }
 
     #[test]
@@ -1357,6 +1788,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_preset() {
+        let mut inner = Preset::new();
+        inner.pair(Name(b"W"), 2);
+        let mut preset = Preset::new();
+        preset.pair(Name(b"F"), 4).dict(Name(b"BS"), inner);
+        assert_eq!(preset.len(), 2);
+        test_obj!(
+            |obj| {
+                obj.dict().pair(Name(b"Type"), Name(b"Annot")).apply(&preset);
+            },
+            b"<<",
+            b"  /Type /Annot",
+            b"  /F 4",
+            b"  /BS <<",
+            b"    /W 2",
+            b"  >>",
+            b">>",
+        );
+    }
+
+    #[test]
+    fn test_typed_arrays() {
+        test_obj!(|obj| obj.array().typed().items([Name(b"A"), Name(b"B")]), b"[/A /B]",);
+        test_obj!(|obj| obj.array().typed().item(Str(b"x")), b"[(x)]");
+        test_obj!(
+            |obj| obj.array().typed().items([Ref::new(2), Ref::new(3)]),
+            b"[2 0 R 3 0 R]",
+        );
+        test_obj!(
+            |obj| obj.array().typed().item(Rect::new(0.0, 0.0, 1.0, 2.0)),
+            b"[[0 0 1 2]]",
+        );
+        test_obj!(|obj| obj.array().typed().item(Date::new(2024)), b"[(D:2024)]",);
+    }
+
     #[test]
     fn test_dicts() {
         test_obj!(|obj| obj.dict(), b"<<>>");
@@ -1370,6 +1837,44 @@ mod tests {
             },
             b"<<\n  /A 1\n  /B 2\n>>",
         );
+        test_obj!(
+            |obj| {
+                obj.dict().raw_pair(Name(b"A"), b"<< /B [1 2] >>").pair(Name(b"C"), 3);
+            },
+            b"<<\n  /A << /B [1 2] >>\n  /C 3\n>>",
+        );
+    }
+
+    #[test]
+    fn test_stream_extra_entries() {
+        test!(
+            crate::tests::slice(|w| {
+                w.stream(Ref::new(1), b"xyz")
+                    .filter(Filter::FlateDecode)
+                    .decoded_length(10)
+                    .pair(Name(b"Custom"), Ref::new(2));
+            }),
+            b"1 0 obj",
+            b"<<\n  /Length 3\n  /Filter /FlateDecode\n  /DL 10\n  /Custom 2 0 R\n>>",
+            b"stream",
+            b"xyz",
+            b"endstream",
+            b"endobj\n\n",
+        );
+    }
+
+    #[test]
+    fn test_png_predictor() {
+        let mut w = Pdf::new();
+        let mut stream = w.stream(Ref::new(1), &[2, 0, 255]);
+        stream.filter(Filter::FlateDecode);
+        stream.decode_parms().png_predictor(3, 8, 1);
+        stream.finish();
+        let text = String::from_utf8_lossy(w.as_bytes());
+        assert!(text.contains(
+            "/DecodeParms <<\n    /Predictor 15\n    /Colors 3\n    \
+             /BitsPerComponent 8\n    /Columns 1\n  >>"
+        ));
     }
 
     #[test]
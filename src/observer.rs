@@ -0,0 +1,133 @@
+use super::*;
+use crate::stats::top_level_value;
+
+/// Receives notifications about the indirect objects written into a
+/// [`Chunk`].
+///
+/// An observer is registered with [`Chunk::set_observer`]. It can be used to
+/// log the written objects, to report progress for huge exports or to check
+/// the objects with an external validator, without wrapping every writer.
+///
+/// Since objects are written in a streaming fashion, a chunk only notices
+/// that an object is complete when the next one is started. The last object
+/// is therefore reported when the observer is taken out with
+/// [`Chunk::take_observer`] or when the [`Pdf`] is finished.
+///
+/// ```
+/// use pdf_writer::{Chunk, Observer, Ref};
+///
+/// struct Log;
+///
+/// impl Observer for Log {
+///     fn object_finished(&mut self, id: Ref, kind: Option<&str>, bytes: &[u8]) {
+///         println!("{} 0 obj: {:?}, {} bytes", id.get(), kind, bytes.len());
+///     }
+/// }
+///
+/// let mut chunk = Chunk::new();
+/// chunk.set_observer(Log);
+/// chunk.page(Ref::new(1));
+/// chunk.take_observer();
+/// ```
+pub trait Observer: Send + Sync {
+    /// Called when an indirect object is started at the given byte offset in
+    /// the chunk.
+    fn object_started(&mut self, id: Ref, offset: usize) {
+        let _ = (id, offset);
+    }
+
+    /// Called when an indirect object is complete with the value of its
+    /// `/Type` entry, if any, and its bytes from `obj` to `endobj`.
+    fn object_finished(&mut self, id: Ref, kind: Option<&str>, bytes: &[u8]) {
+        let _ = (id, kind, bytes);
+    }
+}
+
+/// An observer along with the object that was started last.
+pub(crate) struct Observing {
+    observer: Box<dyn Observer>,
+    current: Option<(Ref, usize)>,
+}
+
+impl Observing {
+    pub(crate) fn new(observer: Box<dyn Observer>) -> Self {
+        Self { observer, current: None }
+    }
+
+    /// Report that an object starts at the given offset. This finishes the
+    /// previous object, which must end before the offset.
+    pub(crate) fn start(&mut self, buf: &[u8], id: Ref, offset: usize) {
+        self.finish_current(buf, offset);
+        self.current = Some((id, offset));
+        self.observer.object_started(id, offset);
+    }
+
+    /// Report the current object as finished, considering only the bytes up
+    /// to `end`.
+    pub(crate) fn finish_current(&mut self, buf: &[u8], end: usize) {
+        let Some((id, start)) = self.current.take() else { return };
+        let bytes = &buf[start..end];
+        let bytes = &bytes[..chunk::object_len(bytes)];
+        let head = match memchr::memmem::find(bytes, b"\nstream\n") {
+            Some(pos) => &bytes[..pos],
+            None => bytes,
+        };
+        let kind = top_level_value(head, b"Type");
+        self.observer.object_finished(id, kind.as_deref(), bytes);
+    }
+
+    /// Return the observer.
+    pub(crate) fn into_inner(self) -> Box<dyn Observer> {
+        self.observer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Events(Arc<Mutex<Vec<String>>>);
+
+    impl Observer for Events {
+        fn object_started(&mut self, id: Ref, offset: usize) {
+            self.0.lock().unwrap().push(format!("start {} at {offset}", id.get()));
+        }
+
+        fn object_finished(&mut self, id: Ref, kind: Option<&str>, bytes: &[u8]) {
+            let events = &mut self.0.lock().unwrap();
+            events.push(format!("finish {} {kind:?} {}", id.get(), bytes.len()));
+        }
+    }
+
+    #[test]
+    fn test_observer() {
+        let events = Events::default();
+        let log = events.0.clone();
+
+        let mut other = Chunk::new();
+        other.stream(Ref::new(3), b"abc");
+
+        let mut pdf = Pdf::new();
+        pdf.set_observer(events);
+        pdf.indirect(Ref::new(1)).primitive(1);
+        pdf.comment("Pages");
+        pdf.pages(Ref::new(2));
+        pdf.extend(&other);
+        pdf.finish();
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                "start 1 at 16",
+                "finish 1 None 16",
+                "start 2 at 42",
+                "finish 2 Some(\"Pages\") 35",
+                "start 3 at 79",
+                "finish 3 None 53",
+            ]
+        );
+    }
+}
@@ -0,0 +1,555 @@
+use super::*;
+
+/// Writer for an _optional content group dictionary_. PDF 1.5+.
+///
+/// This struct is created by [`Chunk::optional_content_group`]. Content that
+/// belongs to the group can be turned on and off by the viewer.
+pub struct OptionalContentGroup<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(OptionalContentGroup: |obj| {
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Type"), Name(b"OCG"));
+    Self { dict }
+});
+
+impl<'a> OptionalContentGroup<'a> {
+    /// Write the `/Name` attribute to set the name of the group as shown in the
+    /// viewer's user interface. Required.
+    pub fn name(&mut self, name: TextStr) -> &mut Self {
+        self.pair(Name(b"Name"), name);
+        self
+    }
+
+    /// Write the `/Intent` attribute to set the intended uses of the group.
+    /// Commonly used intents are `View` and `Design`.
+    ///
+    /// _Default value_: `View`.
+    pub fn intent<'n>(
+        &mut self,
+        intents: impl IntoIterator<Item = Name<'n>>,
+    ) -> &mut Self {
+        self.insert(Name(b"Intent")).array().items(intents);
+        self
+    }
+
+    /// Start writing the `/Usage` dictionary to describe the nature of the
+    /// group's content. Viewers can use it to automatically set the group's
+    /// state through [usage applications](OcConfig::auto_state).
+    pub fn usage(&mut self) -> OcUsage<'_> {
+        self.insert(Name(b"Usage")).start()
+    }
+}
+
+deref!('a, OptionalContentGroup<'a> => Dict<'a>, dict);
+
+/// Writer for an _optional content usage dictionary_. PDF 1.5+.
+///
+/// This struct is created by [`OptionalContentGroup::usage`].
+pub struct OcUsage<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(OcUsage: |obj| Self { dict: obj.dict() });
+
+impl<'a> OcUsage<'a> {
+    /// Write the `/CreatorInfo` dictionary to name the application that
+    /// created the group and the kind of its content, e.g. `Artwork`.
+    pub fn creator_info(&mut self, creator: TextStr, subtype: Name) -> &mut Self {
+        self.insert(Name(b"CreatorInfo"))
+            .dict()
+            .pair(Name(b"Creator"), creator)
+            .pair(Name(b"Subtype"), subtype);
+        self
+    }
+
+    /// Write the `/View` dictionary to set whether the group should be on
+    /// when the document is first opened in a viewer.
+    pub fn view(&mut self, on: bool) -> &mut Self {
+        self.insert(Name(b"View")).dict().pair(Name(b"ViewState"), state(on));
+        self
+    }
+
+    /// Write the `/Print` dictionary to set whether the group should be on
+    /// when the document is printed. The optional subtype names the kind of
+    /// content, e.g. `Trapping`, `PrintersMarks` or `Watermark`.
+    pub fn print(&mut self, on: bool, subtype: Option<Name>) -> &mut Self {
+        let mut dict = self.insert(Name(b"Print")).dict();
+        if let Some(subtype) = subtype {
+            dict.pair(Name(b"Subtype"), subtype);
+        }
+        dict.pair(Name(b"PrintState"), state(on));
+        dict.finish();
+        self
+    }
+
+    /// Write the `/Export` dictionary to set whether the group should be on
+    /// when the document is exported to a format without optional content.
+    pub fn export(&mut self, on: bool) -> &mut Self {
+        self.insert(Name(b"Export"))
+            .dict()
+            .pair(Name(b"ExportState"), state(on));
+        self
+    }
+
+    /// Write the `/Zoom` dictionary to set the range of magnifications in
+    /// which the group should be on. The magnifications are factors where
+    /// `1.0` means 100%.
+    pub fn zoom(&mut self, min: Option<f32>, max: Option<f32>) -> &mut Self {
+        let mut dict = self.insert(Name(b"Zoom")).dict();
+        if let Some(min) = min {
+            dict.pair(Name(b"min"), min);
+        }
+        if let Some(max) = max {
+            dict.pair(Name(b"max"), max);
+        }
+        dict.finish();
+        self
+    }
+
+    /// Write the `/Language` dictionary to set the language of the group's
+    /// content. If `preferred` is true, the group is preferred when no group
+    /// exactly matches the viewer's language.
+    pub fn language(&mut self, lang: TextStr, preferred: bool) -> &mut Self {
+        self.insert(Name(b"Language"))
+            .dict()
+            .pair(Name(b"Lang"), lang)
+            .pair(Name(b"Preferred"), state(preferred));
+        self
+    }
+}
+
+deref!('a, OcUsage<'a> => Dict<'a>, dict);
+
+/// The name for an on or off state of an optional content group.
+fn state(on: bool) -> Name<'static> {
+    if on {
+        Name(b"ON")
+    } else {
+        Name(b"OFF")
+    }
+}
+
+/// Writer for an _optional content properties dictionary_. PDF 1.5+.
+///
+/// This struct is created by [`Catalog::optional_content`].
+pub struct OcProperties<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(OcProperties: |obj| Self { dict: obj.dict() });
+
+impl<'a> OcProperties<'a> {
+    /// Write the `/OCGs` attribute to reference all optional content groups in
+    /// the document. Required.
+    pub fn groups(&mut self, groups: impl IntoIterator<Item = Ref>) -> &mut Self {
+        self.insert(Name(b"OCGs")).array().items(groups);
+        self
+    }
+
+    /// Start writing the `/D` dictionary to set the default configuration.
+    /// Required.
+    pub fn default_config(&mut self) -> OcConfig<'_> {
+        self.insert(Name(b"D")).start()
+    }
+
+    /// Start writing the `/Configs` array to offer alternate configurations.
+    pub fn configs(&mut self) -> TypedArray<'_, OcConfig<'_>> {
+        self.insert(Name(b"Configs")).array().typed()
+    }
+}
+
+deref!('a, OcProperties<'a> => Dict<'a>, dict);
+
+/// Writer for an _optional content configuration dictionary_. PDF 1.5+.
+///
+/// This struct is created by [`OcProperties::default_config`] and
+/// [`OcProperties::configs`].
+pub struct OcConfig<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(OcConfig: |obj| Self { dict: obj.dict() });
+
+impl<'a> OcConfig<'a> {
+    /// Write the `/Name` attribute to set the name of the configuration.
+    pub fn name(&mut self, name: TextStr) -> &mut Self {
+        self.pair(Name(b"Name"), name);
+        self
+    }
+
+    /// Write the `/Creator` attribute to name the application or feature that
+    /// created the configuration.
+    pub fn creator(&mut self, creator: TextStr) -> &mut Self {
+        self.pair(Name(b"Creator"), creator);
+        self
+    }
+
+    /// Write the `/BaseState` attribute to set the initial state of all groups
+    /// before the [`on`](Self::on) and [`off`](Self::off) lists are applied.
+    /// `None` keeps the states unchanged and is not permissible for the
+    /// default configuration.
+    ///
+    /// _Default value_: `ON`.
+    pub fn base_state(&mut self, on: Option<bool>) -> &mut Self {
+        self.pair(Name(b"BaseState"), on.map_or(Name(b"Unchanged"), state));
+        self
+    }
+
+    /// Write the `/ON` attribute to list the groups that are turned on.
+    pub fn on(&mut self, groups: impl IntoIterator<Item = Ref>) -> &mut Self {
+        self.insert(Name(b"ON")).array().items(groups);
+        self
+    }
+
+    /// Write the `/OFF` attribute to list the groups that are turned off.
+    pub fn off(&mut self, groups: impl IntoIterator<Item = Ref>) -> &mut Self {
+        self.insert(Name(b"OFF")).array().items(groups);
+        self
+    }
+
+    /// Write the `/Intent` attribute to set which groups are considered by the
+    /// viewer. Groups whose intent does not match are always visible.
+    ///
+    /// _Default value_: `View`.
+    pub fn intent<'n>(
+        &mut self,
+        intents: impl IntoIterator<Item = Name<'n>>,
+    ) -> &mut Self {
+        self.insert(Name(b"Intent")).array().items(intents);
+        self
+    }
+
+    /// Start writing the `/AS` array to automatically set the states of groups
+    /// based on their [usage](OptionalContentGroup::usage) when an event
+    /// occurs, e.g. to show different layers on screen and in print.
+    pub fn auto_state(&mut self) -> TypedArray<'_, OcUsageApplication<'_>> {
+        self.insert(Name(b"AS")).array().typed()
+    }
+
+    /// Write the `/Locked` attribute to list the groups whose state cannot be
+    /// changed through the user interface. PDF 1.6+.
+    pub fn locked(&mut self, groups: impl IntoIterator<Item = Ref>) -> &mut Self {
+        self.insert(Name(b"Locked")).array().items(groups);
+        self
+    }
+}
+
+deref!('a, OcConfig<'a> => Dict<'a>, dict);
+
+/// Writer for an _optional content usage application dictionary_. PDF 1.5+.
+///
+/// An array of this struct is created by [`OcConfig::auto_state`].
+pub struct OcUsageApplication<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(OcUsageApplication: |obj| Self { dict: obj.dict() });
+
+impl<'a> OcUsageApplication<'a> {
+    /// Write the `/Event` attribute to set when the usage is applied.
+    /// Required.
+    pub fn event(&mut self, event: OcEvent) -> &mut Self {
+        self.pair(Name(b"Event"), event.to_name());
+        self
+    }
+
+    /// Write the `/OCGs` attribute to reference the groups whose states are
+    /// set.
+    pub fn groups(&mut self, groups: impl IntoIterator<Item = Ref>) -> &mut Self {
+        self.insert(Name(b"OCGs")).array().items(groups);
+        self
+    }
+
+    /// Write the `/Category` attribute to set which entries of the groups'
+    /// usage dictionaries determine their states. Required.
+    pub fn category(
+        &mut self,
+        categories: impl IntoIterator<Item = OcUsageCategory>,
+    ) -> &mut Self {
+        self.insert(Name(b"Category"))
+            .array()
+            .items(categories.into_iter().map(OcUsageCategory::to_name));
+        self
+    }
+}
+
+deref!('a, OcUsageApplication<'a> => Dict<'a>, dict);
+
+/// When a [usage application](OcUsageApplication) sets the states of optional
+/// content groups.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum OcEvent {
+    /// When the document is viewed.
+    View,
+    /// When the document is printed.
+    Print,
+    /// When the document is exported.
+    Export,
+}
+
+impl OcEvent {
+    pub(crate) fn to_name(self) -> Name<'static> {
+        match self {
+            Self::View => Name(b"View"),
+            Self::Print => Name(b"Print"),
+            Self::Export => Name(b"Export"),
+        }
+    }
+}
+
+/// An entry of an [optional content usage dictionary](OcUsage) considered
+/// by a [usage application](OcUsageApplication).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum OcUsageCategory {
+    /// The [`/View`](OcUsage::view) entry.
+    View,
+    /// The [`/Print`](OcUsage::print) entry.
+    Print,
+    /// The [`/Export`](OcUsage::export) entry.
+    Export,
+    /// The [`/Zoom`](OcUsage::zoom) entry.
+    Zoom,
+    /// The [`/Language`](OcUsage::language) entry.
+    Language,
+    /// The `/User` entry.
+    User,
+}
+
+impl OcUsageCategory {
+    pub(crate) fn to_name(self) -> Name<'static> {
+        match self {
+            Self::View => Name(b"View"),
+            Self::Print => Name(b"Print"),
+            Self::Export => Name(b"Export"),
+            Self::Zoom => Name(b"Zoom"),
+            Self::Language => Name(b"Language"),
+            Self::User => Name(b"User"),
+        }
+    }
+}
+
+/// Writer for an _optional content membership dictionary_. PDF 1.5+.
+///
+/// This struct is created by [`Chunk::oc_membership`]. It makes content
+/// visible depending on the state of one or more optional content groups and
+/// can be used wherever a single group could be used.
+pub struct OcMembership<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(OcMembership: |obj| {
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Type"), Name(b"OCMD"));
+    Self { dict }
+});
+
+impl<'a> OcMembership<'a> {
+    /// Write the `/OCGs` attribute to reference the optional content groups
+    /// that are evaluated with the [visibility policy](Self::policy).
+    pub fn groups(&mut self, groups: impl IntoIterator<Item = Ref>) -> &mut Self {
+        self.insert(Name(b"OCGs")).array().items(groups);
+        self
+    }
+
+    /// Write the `/P` attribute to set how the states of the groups determine
+    /// the visibility. Ignored if a [visibility
+    /// expression](Self::visibility_expression) is present.
+    ///
+    /// _Default value_: `AnyOn`.
+    pub fn policy(&mut self, policy: VisibilityPolicy) -> &mut Self {
+        self.pair(Name(b"P"), policy.to_name());
+        self
+    }
+
+    /// Start writing the `/VE` array to determine the visibility with a
+    /// boolean expression of groups. PDF 1.6+.
+    pub fn visibility_expression(
+        &mut self,
+        operator: VisibilityOperator,
+    ) -> VisibilityExpression<'_> {
+        VisibilityExpression::start(self.insert(Name(b"VE")), operator)
+    }
+}
+
+deref!('a, OcMembership<'a> => Dict<'a>, dict);
+
+/// How the states of optional content groups determine the visibility of
+/// content in an [optional content membership dictionary](OcMembership).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum VisibilityPolicy {
+    /// Visible if all groups are on.
+    AllOn,
+    /// Visible if any group is on.
+    AnyOn,
+    /// Visible if any group is off.
+    AnyOff,
+    /// Visible if all groups are off.
+    AllOff,
+}
+
+impl VisibilityPolicy {
+    pub(crate) fn to_name(self) -> Name<'static> {
+        match self {
+            Self::AllOn => Name(b"AllOn"),
+            Self::AnyOn => Name(b"AnyOn"),
+            Self::AnyOff => Name(b"AnyOff"),
+            Self::AllOff => Name(b"AllOff"),
+        }
+    }
+}
+
+/// Writer for a _visibility expression array_. PDF 1.6+.
+///
+/// This struct is created by [`OcMembership::visibility_expression`]. The
+/// operands of the expression are optional content groups and nested
+/// expressions.
+pub struct VisibilityExpression<'a> {
+    array: Array<'a>,
+}
+
+impl<'a> VisibilityExpression<'a> {
+    /// Start writing an expression with the given operator.
+    pub(crate) fn start(obj: Obj<'a>, operator: VisibilityOperator) -> Self {
+        let mut array = obj.array();
+        array.item(operator.to_name());
+        Self { array }
+    }
+
+    /// Add an optional content group as an operand.
+    pub fn group(&mut self, id: Ref) -> &mut Self {
+        self.array.item(id);
+        self
+    }
+
+    /// Start writing a nested expression as an operand.
+    pub fn expression(
+        &mut self,
+        operator: VisibilityOperator,
+    ) -> VisibilityExpression<'_> {
+        VisibilityExpression::start(self.array.push(), operator)
+    }
+}
+
+deref!('a, VisibilityExpression<'a> => Array<'a>, array);
+
+/// The operator of a [visibility expression](VisibilityExpression).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum VisibilityOperator {
+    /// All operands must be visible.
+    And,
+    /// At least one operand must be visible.
+    Or,
+    /// The single operand must not be visible.
+    Not,
+}
+
+impl VisibilityOperator {
+    pub(crate) fn to_name(self) -> Name<'static> {
+        match self {
+            Self::And => Name(b"And"),
+            Self::Or => Name(b"Or"),
+            Self::Not => Name(b"Not"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oc_membership() {
+        let mut c = Chunk::new();
+        let mut ocmd = c.oc_membership(Ref::new(1));
+        ocmd.groups([Ref::new(2), Ref::new(3)])
+            .policy(VisibilityPolicy::AllOn);
+        let mut expr = ocmd.visibility_expression(VisibilityOperator::And);
+        expr.group(Ref::new(2));
+        expr.expression(VisibilityOperator::Not).group(Ref::new(3));
+        expr.finish();
+        ocmd.finish();
+        test!(
+            c.as_bytes(),
+            b"1 0 obj",
+            b"<<",
+            b"  /Type /OCMD",
+            b"  /OCGs [2 0 R 3 0 R]",
+            b"  /P /AllOn",
+            b"  /VE [/And 2 0 R [/Not 3 0 R]]",
+            b">>",
+            b"endobj\n\n",
+        );
+    }
+
+    #[test]
+    fn test_oc_usage() {
+        let mut c = Chunk::new();
+        let mut ocg = c.optional_content_group(Ref::new(1));
+        ocg.name(TextStr("Watermark"));
+        ocg.usage()
+            .view(false)
+            .print(true, Some(Name(b"Watermark")))
+            .zoom(Some(0.5), None)
+            .language(TextStr("de"), true);
+        ocg.finish();
+
+        let mut catalog = c.indirect(Ref::new(2)).start::<Catalog>();
+        let mut props = catalog.optional_content();
+        props.groups([Ref::new(1)]);
+        let mut config = props.default_config();
+        config.base_state(Some(true));
+        config
+            .auto_state()
+            .push()
+            .event(OcEvent::Print)
+            .groups([Ref::new(1)])
+            .category([OcUsageCategory::Print]);
+        config.finish();
+        props.finish();
+        catalog.finish();
+
+        test!(
+            c.as_bytes(),
+            b"1 0 obj",
+            b"<<",
+            b"  /Type /OCG",
+            b"  /Name (Watermark)",
+            b"  /Usage <<",
+            b"    /View <<",
+            b"      /ViewState /OFF",
+            b"    >>",
+            b"    /Print <<",
+            b"      /Subtype /Watermark",
+            b"      /PrintState /ON",
+            b"    >>",
+            b"    /Zoom <<",
+            b"      /min 0.5",
+            b"    >>",
+            b"    /Language <<",
+            b"      /Lang (de)",
+            b"      /Preferred /ON",
+            b"    >>",
+            b"  >>",
+            b">>",
+            b"endobj\n",
+            b"2 0 obj",
+            b"<<",
+            b"  /Type /Catalog",
+            b"  /OCProperties <<",
+            b"    /OCGs [1 0 R]",
+            b"    /D <<",
+            b"      /BaseState /ON",
+            b"      /AS [<<",
+            b"        /Event /Print",
+            b"        /OCGs [1 0 R]",
+            b"        /Category [/Print]",
+            b"      >>]",
+            b"    >>",
+            b"  >>",
+            b">>",
+            b"endobj\n\n",
+        );
+    }
+}
@@ -215,6 +215,88 @@ impl RenditionType {
     }
 }
 
+/// Writer for a _sound object_. PDF 1.2+.
+///
+/// This struct is created by [`Chunk::sound`]. The stream contains the
+/// samples, which are played by [sound annotations](Annotation::sound) and
+/// sound actions. Deprecated in PDF 2.0.
+pub struct Sound<'a> {
+    stream: Stream<'a>,
+}
+
+impl<'a> Sound<'a> {
+    /// Create a new sound writer.
+    pub(crate) fn start(mut stream: Stream<'a>) -> Self {
+        stream.pair(Name(b"Type"), Name(b"Sound"));
+        Self { stream }
+    }
+
+    /// Write the `/R` attribute to set the sampling rate in samples per
+    /// second. Required.
+    pub fn rate(&mut self, rate: f32) -> &mut Self {
+        self.pair(Name(b"R"), rate);
+        self
+    }
+
+    /// Write the `/C` attribute to set the number of channels.
+    ///
+    /// _Default value_: 1.
+    pub fn channels(&mut self, channels: i32) -> &mut Self {
+        self.pair(Name(b"C"), channels);
+        self
+    }
+
+    /// Write the `/B` attribute to set the number of bits per sample and
+    /// channel.
+    ///
+    /// _Default value_: 8.
+    pub fn bits_per_sample(&mut self, bits: i32) -> &mut Self {
+        self.pair(Name(b"B"), bits);
+        self
+    }
+
+    /// Write the `/E` attribute to set how the samples are encoded.
+    ///
+    /// _Default value_: `Raw`.
+    pub fn encoding(&mut self, encoding: SoundEncoding) -> &mut Self {
+        self.pair(Name(b"E"), encoding.to_name());
+        self
+    }
+
+    /// Write the `/CO` attribute to name the sound compression format, in
+    /// addition to the stream's filters.
+    pub fn compression(&mut self, format: Name) -> &mut Self {
+        self.pair(Name(b"CO"), format);
+        self
+    }
+}
+
+deref!('a, Sound<'a> => Stream<'a>, stream);
+
+/// How the samples of a [sound](Sound) are encoded.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum SoundEncoding {
+    /// Unsigned values in the range from 0 to 2<sup>B</sup> − 1.
+    Raw,
+    /// Two's complement values.
+    Signed,
+    /// µ-law encoded samples.
+    MuLaw,
+    /// A-law encoded samples.
+    ALaw,
+}
+
+impl SoundEncoding {
+    pub(crate) fn to_name(self) -> Name<'static> {
+        match self {
+            Self::Raw => Name(b"Raw"),
+            Self::Signed => Name(b"Signed"),
+            Self::MuLaw => Name(b"muLaw"),
+            Self::ALaw => Name(b"ALaw"),
+        }
+    }
+}
+
 /// Type of media clip objects.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum MediaClipType {
@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 use crate::{BufExt, Chunk, Ref};
 
 /// Renumbers a chunk of objects.
@@ -6,11 +8,12 @@ use crate::{BufExt, Chunk, Ref};
 pub fn renumber(source: &Chunk, target: &mut Chunk, mapping: &mut dyn FnMut(Ref) -> Ref) {
     let mut iter = source.offsets.iter().copied().peekable();
     while let Some((id, offset)) = iter.next() {
-        let new = mapping(id);
+        let new = mapping(Ref::new(id.get()));
         let end = iter.peek().map_or(source.buf.len(), |&(_, offset)| offset);
         let slice = &source.buf[offset..end];
         let Some((gen, slice)) = extract_object(slice) else { continue };
-        target.offsets.push((new, target.buf.len()));
+        let gen_id = new.with_generation(u16::try_from(gen).unwrap_or(0));
+        target.record(gen_id, target.buf.len());
         target.buf.push_int(new.get());
         target.buf.push(b' ');
         target.buf.push_int(gen);
@@ -138,7 +141,7 @@ fn require_whitespace_rev(slice: &mut &[u8]) -> Option<()> {
 /// Eat an ASCII number, in reverse.
 fn eat_number_rev(slice: &mut &[u8]) -> Option<i32> {
     let tail = eat_suffix(slice, |byte| byte.is_ascii_digit());
-    let string = std::str::from_utf8(tail).ok()?;
+    let string = core::str::from_utf8(tail).ok()?;
     string.parse::<i32>().ok()
 }
 
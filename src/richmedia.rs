@@ -0,0 +1,360 @@
+use super::*;
+
+/// Writer for a _rich media content dictionary_. PDF 2.0+.
+///
+/// This struct is created by [`Annotation::rich_media_content`].
+pub struct RichMediaContent<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(RichMediaContent: |obj| {
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Type"), Name(b"RichMediaContent"));
+    Self { dict }
+});
+
+impl<'a> RichMediaContent<'a> {
+    /// Start writing the `/Assets` name tree. It maps the names of the assets
+    /// to [file specifications](FileSpec) with embedded files.
+    pub fn assets(&mut self) -> NameTree<'_, Ref> {
+        self.insert(Name(b"Assets")).start()
+    }
+
+    /// Start writing the `/Configurations` array. The first configuration is
+    /// the default one. Required.
+    pub fn configurations(&mut self) -> TypedArray<'_, RichMediaConfiguration<'_>> {
+        self.insert(Name(b"Configurations")).array().typed()
+    }
+
+    /// Write the `/Views` attribute to reference the 3D view dictionaries of
+    /// the content. The first view is the default one.
+    pub fn views(&mut self, views: impl IntoIterator<Item = Ref>) -> &mut Self {
+        self.insert(Name(b"Views")).array().items(views);
+        self
+    }
+}
+
+deref!('a, RichMediaContent<'a> => Dict<'a>, dict);
+
+/// Writer for a _rich media configuration dictionary_. PDF 2.0+.
+///
+/// An array of this struct is created by [`RichMediaContent::configurations`].
+pub struct RichMediaConfiguration<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(RichMediaConfiguration: |obj| {
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Type"), Name(b"RichMediaConfiguration"));
+    Self { dict }
+});
+
+impl<'a> RichMediaConfiguration<'a> {
+    /// Write the `/Subtype` attribute to set the primary kind of the
+    /// configuration's content.
+    pub fn subtype(&mut self, kind: RichMediaType) -> &mut Self {
+        self.pair(Name(b"Subtype"), kind.to_name());
+        self
+    }
+
+    /// Write the `/Name` attribute to set a unique name for the configuration.
+    pub fn name(&mut self, name: TextStr) -> &mut Self {
+        self.pair(Name(b"Name"), name);
+        self
+    }
+
+    /// Start writing the `/Instances` array. Required.
+    pub fn instances(&mut self) -> TypedArray<'_, RichMediaInstance<'_>> {
+        self.insert(Name(b"Instances")).array().typed()
+    }
+}
+
+deref!('a, RichMediaConfiguration<'a> => Dict<'a>, dict);
+
+/// Writer for a _rich media instance dictionary_. PDF 2.0+.
+///
+/// An array of this struct is created by [`RichMediaConfiguration::instances`].
+pub struct RichMediaInstance<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(RichMediaInstance: |obj| {
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Type"), Name(b"RichMediaInstance"));
+    Self { dict }
+});
+
+impl<'a> RichMediaInstance<'a> {
+    /// Write the `/Subtype` attribute to set the kind of the instance's asset.
+    pub fn subtype(&mut self, kind: RichMediaType) -> &mut Self {
+        self.pair(Name(b"Subtype"), kind.to_name());
+        self
+    }
+
+    /// Write the `/Asset` attribute to reference the file specification of the
+    /// asset. It must also be listed in the [assets](RichMediaContent::assets)
+    /// name tree. Required.
+    pub fn asset(&mut self, id: Ref) -> &mut Self {
+        self.pair(Name(b"Asset"), id);
+        self
+    }
+
+    /// Start writing the `/Params` dictionary. Only permissible for the
+    /// subtypes `Flash`, `Sound` and `Video`.
+    pub fn params(&mut self) -> RichMediaParams<'_> {
+        self.insert(Name(b"Params")).start()
+    }
+}
+
+deref!('a, RichMediaInstance<'a> => Dict<'a>, dict);
+
+/// Writer for a _rich media parameters dictionary_. PDF 2.0+.
+///
+/// This struct is created by [`RichMediaInstance::params`].
+pub struct RichMediaParams<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(RichMediaParams: |obj| {
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Type"), Name(b"RichMediaParams"));
+    Self { dict }
+});
+
+impl<'a> RichMediaParams<'a> {
+    /// Write the `/FlashVars` attribute to set the variables passed to the
+    /// content on startup.
+    pub fn flash_vars(&mut self, vars: TextStr) -> &mut Self {
+        self.pair(Name(b"FlashVars"), vars);
+        self
+    }
+
+    /// Write the `/Settings` attribute to pass arbitrary implementation
+    /// specific settings to the content.
+    pub fn settings(&mut self, settings: TextStr) -> &mut Self {
+        self.pair(Name(b"Settings"), settings);
+        self
+    }
+}
+
+deref!('a, RichMediaParams<'a> => Dict<'a>, dict);
+
+/// Writer for a _rich media settings dictionary_. PDF 2.0+.
+///
+/// This struct is created by [`Annotation::rich_media_settings`].
+pub struct RichMediaSettings<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(RichMediaSettings: |obj| {
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Type"), Name(b"RichMediaSettings"));
+    Self { dict }
+});
+
+impl<'a> RichMediaSettings<'a> {
+    /// Start writing the `/Activation` dictionary to specify when and how the
+    /// content is activated.
+    pub fn activation(&mut self) -> RichMediaActivation<'_> {
+        self.insert(Name(b"Activation")).start()
+    }
+
+    /// Start writing the `/Deactivation` dictionary to specify when the
+    /// content is deactivated.
+    pub fn deactivation(&mut self) -> RichMediaDeactivation<'_> {
+        self.insert(Name(b"Deactivation")).start()
+    }
+}
+
+deref!('a, RichMediaSettings<'a> => Dict<'a>, dict);
+
+/// Writer for a _rich media activation dictionary_. PDF 2.0+.
+///
+/// This struct is created by [`RichMediaSettings::activation`].
+pub struct RichMediaActivation<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(RichMediaActivation: |obj| {
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Type"), Name(b"RichMediaActivation"));
+    Self { dict }
+});
+
+impl<'a> RichMediaActivation<'a> {
+    /// Write the `/Condition` attribute to set when the content is activated.
+    ///
+    /// _Default value_: `ExplicitActivation`.
+    pub fn condition(&mut self, condition: RichMediaActivationCondition) -> &mut Self {
+        self.pair(Name(b"Condition"), condition.to_name());
+        self
+    }
+
+    /// Write the `/Configuration` attribute to reference the configuration
+    /// that is loaded on activation. If absent, the first configuration is
+    /// used.
+    pub fn configuration(&mut self, id: Ref) -> &mut Self {
+        self.pair(Name(b"Configuration"), id);
+        self
+    }
+
+    /// Write the `/Scripts` attribute to reference the file specifications of
+    /// scripts that are run before the content is loaded.
+    pub fn scripts(&mut self, scripts: impl IntoIterator<Item = Ref>) -> &mut Self {
+        self.insert(Name(b"Scripts")).array().items(scripts);
+        self
+    }
+}
+
+deref!('a, RichMediaActivation<'a> => Dict<'a>, dict);
+
+/// Writer for a _rich media deactivation dictionary_. PDF 2.0+.
+///
+/// This struct is created by [`RichMediaSettings::deactivation`].
+pub struct RichMediaDeactivation<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(RichMediaDeactivation: |obj| {
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Type"), Name(b"RichMediaDeactivation"));
+    Self { dict }
+});
+
+impl<'a> RichMediaDeactivation<'a> {
+    /// Write the `/Condition` attribute to set when the content is
+    /// deactivated.
+    ///
+    /// _Default value_: `ExplicitDeactivation`.
+    pub fn condition(&mut self, condition: RichMediaDeactivationCondition) -> &mut Self {
+        self.pair(Name(b"Condition"), condition.to_name());
+        self
+    }
+}
+
+deref!('a, RichMediaDeactivation<'a> => Dict<'a>, dict);
+
+/// The kind of content in a rich media configuration or instance.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum RichMediaType {
+    /// A 3D model.
+    ThreeD,
+    /// A Flash application.
+    Flash,
+    /// A sound.
+    Sound,
+    /// A video.
+    Video,
+}
+
+impl RichMediaType {
+    pub(crate) fn to_name(self) -> Name<'static> {
+        match self {
+            Self::ThreeD => Name(b"3D"),
+            Self::Flash => Name(b"Flash"),
+            Self::Sound => Name(b"Sound"),
+            Self::Video => Name(b"Video"),
+        }
+    }
+}
+
+/// When rich media content is activated.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum RichMediaActivationCondition {
+    /// When the user interacts with the annotation.
+    ExplicitActivation,
+    /// When the page containing the annotation is opened.
+    PageOpen,
+    /// When the page containing the annotation becomes visible.
+    PageVisible,
+}
+
+impl RichMediaActivationCondition {
+    pub(crate) fn to_name(self) -> Name<'static> {
+        match self {
+            Self::ExplicitActivation => Name(b"XA"),
+            Self::PageOpen => Name(b"PO"),
+            Self::PageVisible => Name(b"PV"),
+        }
+    }
+}
+
+/// When rich media content is deactivated.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum RichMediaDeactivationCondition {
+    /// When the user deactivates the content, e.g. through a context menu.
+    ExplicitDeactivation,
+    /// When the page containing the annotation is closed.
+    PageClose,
+    /// When the page containing the annotation becomes invisible.
+    PageInvisible,
+}
+
+impl RichMediaDeactivationCondition {
+    pub(crate) fn to_name(self) -> Name<'static> {
+        match self {
+            Self::ExplicitDeactivation => Name(b"XD"),
+            Self::PageClose => Name(b"PC"),
+            Self::PageInvisible => Name(b"PI"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::AnnotationType;
+
+    #[test]
+    fn test_rich_media() {
+        test_obj!(
+            |obj| {
+                let mut annot = obj.start::<Annotation>();
+                annot.subtype(AnnotationType::RichMedia);
+                let mut content = annot.rich_media_content();
+                content.assets().names().insert(Str(b"clip.mp4"), Ref::new(2));
+                let mut configs = content.configurations();
+                let mut config = configs.push();
+                config.subtype(RichMediaType::Video);
+                config
+                    .instances()
+                    .push()
+                    .subtype(RichMediaType::Video)
+                    .asset(Ref::new(2));
+                config.finish();
+                configs.finish();
+                content.finish();
+                annot
+                    .rich_media_settings()
+                    .activation()
+                    .condition(RichMediaActivationCondition::PageVisible);
+            },
+            b"<<",
+            b"  /Type /Annot",
+            b"  /Subtype /RichMedia",
+            b"  /RichMediaContent <<",
+            b"    /Type /RichMediaContent",
+            b"    /Assets <<",
+            b"      /Names [(clip.mp4) 2 0 R]",
+            b"    >>",
+            b"    /Configurations [<<",
+            b"      /Type /RichMediaConfiguration",
+            b"      /Subtype /Video",
+            b"      /Instances [<<",
+            b"        /Type /RichMediaInstance",
+            b"        /Subtype /Video",
+            b"        /Asset 2 0 R",
+            b"      >>]",
+            b"    >>]",
+            b"  >>",
+            b"  /RichMediaSettings <<",
+            b"    /Type /RichMediaSettings",
+            b"    /Activation <<",
+            b"      /Type /RichMediaActivation",
+            b"      /Condition /PV",
+            b"    >>",
+            b"  >>",
+            b">>",
+        );
+    }
+}
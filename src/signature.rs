@@ -0,0 +1,448 @@
+use super::*;
+
+/// Writer for a _signature dictionary_. PDF 1.3+.
+///
+/// This struct is created by [`Chunk::signature`]. This crate does not
+/// compute signatures. The `/Contents` and `/ByteRange` entries are usually
+/// written with placeholders and patched once the file is
+/// [finished](Pdf::finish_with_layout).
+pub struct Signature<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(Signature: |obj| {
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Type"), Name(b"Sig"));
+    Self { dict }
+});
+
+impl<'a> Signature<'a> {
+    /// Write the `/Filter` attribute to name the preferred signature handler,
+    /// e.g. `Adobe.PPKLite`. Required.
+    pub fn filter(&mut self, filter: Name) -> &mut Self {
+        self.pair(Name(b"Filter"), filter);
+        self
+    }
+
+    /// Write the `/SubFilter` attribute to name the encoding of the signature
+    /// value, e.g. `adbe.pkcs7.detached` or `ETSI.CAdES.detached`.
+    pub fn sub_filter(&mut self, sub_filter: Name) -> &mut Self {
+        self.pair(Name(b"SubFilter"), sub_filter);
+        self
+    }
+
+    /// Write the `/Contents` attribute with the signature value. Required.
+    ///
+    /// To sign the finished file, write a placeholder with
+    /// [`contents_placeholder`](Self::contents_placeholder) instead.
+    pub fn contents(&mut self, contents: Str) -> &mut Self {
+        self.pair(Name(b"Contents"), contents);
+        self
+    }
+
+    /// Write the `/Contents` attribute as a placeholder for a signature value
+    /// of up to `len` bytes, i.e. a hexadecimal string of `2 * len` zeros.
+    ///
+    /// Returns the byte range of the hexadecimal string including its angle
+    /// brackets in the buffer of the chunk this is written to. This range is
+    /// excluded from the `/ByteRange` and overwritten with the hex-encoded
+    /// signature, padded with zeros. It only stays valid in the finished file
+    /// if the signature is written directly into the [`Pdf`] and no [line
+    /// limit](Pdf::set_line_limit) or [line ending](Pdf::set_line_ending)
+    /// rewrites the objects.
+    pub fn contents_placeholder(&mut self, len: usize) -> Range<usize> {
+        let mut hex = vec![b'0'; 2 * len + 2];
+        hex[0] = b'<';
+        hex[2 * len + 1] = b'>';
+        self.raw_pair(Name(b"Contents"), &hex);
+        let end = self.dict.buf_len();
+        end - hex.len()..end
+    }
+
+    /// Write the `/ByteRange` attribute to set the signed ranges of the file
+    /// as pairs of offsets and lengths.
+    pub fn byte_range(
+        &mut self,
+        ranges: impl IntoIterator<Item = (i32, i32)>,
+    ) -> &mut Self {
+        self.insert(Name(b"ByteRange"))
+            .array()
+            .items(ranges.into_iter().flat_map(|(offset, len)| [offset, len]));
+        self
+    }
+
+    /// Start writing the `/Reference` array of [signature
+    /// references](SignatureReference) to specify what the signature applies
+    /// to. PDF 1.5+.
+    pub fn reference(&mut self) -> TypedArray<'_, SignatureReference<'_>> {
+        self.insert(Name(b"Reference")).array().typed()
+    }
+
+    /// Write the `/Name` attribute to set the name of the signer.
+    pub fn name(&mut self, name: TextStr) -> &mut Self {
+        self.pair(Name(b"Name"), name);
+        self
+    }
+
+    /// Write the `/M` attribute to set the time of signing.
+    pub fn date(&mut self, date: Date) -> &mut Self {
+        self.pair(Name(b"M"), date);
+        self
+    }
+
+    /// Write the `/Location` attribute to set where the document was signed.
+    pub fn location(&mut self, location: TextStr) -> &mut Self {
+        self.pair(Name(b"Location"), location);
+        self
+    }
+
+    /// Write the `/Reason` attribute to set why the document was signed.
+    pub fn reason(&mut self, reason: TextStr) -> &mut Self {
+        self.pair(Name(b"Reason"), reason);
+        self
+    }
+
+    /// Write the `/ContactInfo` attribute to set how to contact the signer.
+    pub fn contact_info(&mut self, info: TextStr) -> &mut Self {
+        self.pair(Name(b"ContactInfo"), info);
+        self
+    }
+}
+
+deref!('a, Signature<'a> => Dict<'a>, dict);
+
+/// Writer for a _signature reference dictionary_. PDF 1.5+.
+///
+/// An array of this struct is created by [`Signature::reference`]. Each
+/// reference writes the `/TransformMethod` along with the matching
+/// `/TransformParams` through one of its methods.
+pub struct SignatureReference<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(SignatureReference: |obj| {
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Type"), Name(b"SigRef"));
+    Self { dict }
+});
+
+impl<'a> SignatureReference<'a> {
+    /// Write the `DocMDP` transform method and start writing its parameters
+    /// to detect changes to the whole document. Used by certification
+    /// signatures, which must also be referenced by the
+    /// [`/Perms`](Catalog::permissions) dictionary.
+    pub fn doc_mdp(&mut self) -> DocMdpParams<'_> {
+        self.pair(Name(b"TransformMethod"), Name(b"DocMDP"));
+        self.insert(Name(b"TransformParams")).start()
+    }
+
+    /// Write the `FieldMDP` transform method and start writing its parameters
+    /// to detect changes to a set of form fields.
+    pub fn field_mdp(&mut self) -> FieldMdpParams<'_> {
+        self.pair(Name(b"TransformMethod"), Name(b"FieldMDP"));
+        self.insert(Name(b"TransformParams")).start()
+    }
+
+    /// Write the `UR3` transform method and start writing its parameters to
+    /// enable additional usage rights in a viewer. PDF 1.6+. Deprecated in
+    /// PDF 2.0.
+    pub fn usage_rights(&mut self) -> UrParams<'_> {
+        self.pair(Name(b"TransformMethod"), Name(b"UR3"));
+        self.insert(Name(b"TransformParams")).start()
+    }
+
+    /// Write the `/Data` attribute to reference the object the transform
+    /// method is applied to. Required for `FieldMDP` if the
+    /// signature is not a certification signature.
+    pub fn data(&mut self, id: Ref) -> &mut Self {
+        self.pair(Name(b"Data"), id);
+        self
+    }
+
+    /// Write the `/DigestMethod` attribute to name the algorithm used to
+    /// compute the digest, e.g. `SHA256`. PDF 1.7+.
+    pub fn digest_method(&mut self, method: Name) -> &mut Self {
+        self.pair(Name(b"DigestMethod"), method);
+        self
+    }
+}
+
+deref!('a, SignatureReference<'a> => Dict<'a>, dict);
+
+/// Writer for the _transform parameters of the DocMDP transform method_.
+/// PDF 1.5+.
+///
+/// This struct is created by [`SignatureReference::doc_mdp`].
+pub struct DocMdpParams<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(DocMdpParams: |obj| {
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Type"), Name(b"TransformParams"));
+    dict.pair(Name(b"V"), Name(b"1.2"));
+    Self { dict }
+});
+
+impl<'a> DocMdpParams<'a> {
+    /// Write the `/P` attribute to set which changes are permitted without
+    /// invalidating the signature.
+    ///
+    /// _Default value_: `FormFilling`.
+    pub fn permissions(&mut self, permissions: MdpPermissions) -> &mut Self {
+        self.pair(Name(b"P"), permissions.to_int());
+        self
+    }
+}
+
+deref!('a, DocMdpParams<'a> => Dict<'a>, dict);
+
+/// Which changes to a certified document are permitted.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum MdpPermissions {
+    /// No changes are permitted.
+    NoChanges,
+    /// Filling in forms, instantiating page templates and signing are
+    /// permitted.
+    FormFilling,
+    /// Like `FormFilling`, but also creating, deleting and modifying
+    /// annotations is permitted.
+    FormFillingAndAnnotations,
+}
+
+impl MdpPermissions {
+    pub(crate) fn to_int(self) -> i32 {
+        match self {
+            Self::NoChanges => 1,
+            Self::FormFilling => 2,
+            Self::FormFillingAndAnnotations => 3,
+        }
+    }
+}
+
+/// Writer for the _transform parameters of the FieldMDP transform method_.
+/// PDF 1.5+.
+///
+/// This struct is created by [`SignatureReference::field_mdp`].
+pub struct FieldMdpParams<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(FieldMdpParams: |obj| {
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Type"), Name(b"TransformParams"));
+    dict.pair(Name(b"V"), Name(b"1.2"));
+    Self { dict }
+});
+
+impl<'a> FieldMdpParams<'a> {
+    /// Write the `/Action` attribute to set which fields are locked by the
+    /// signature. Required.
+    pub fn action(&mut self, action: FieldMdpAction) -> &mut Self {
+        self.pair(Name(b"Action"), action.to_name());
+        self
+    }
+
+    /// Write the `/Fields` attribute to list the fully qualified names of the
+    /// fields to include or exclude. Required unless the action is `All`.
+    pub fn fields<'b>(
+        &mut self,
+        fields: impl IntoIterator<Item = TextStr<'b>>,
+    ) -> &mut Self {
+        self.insert(Name(b"Fields")).array().items(fields);
+        self
+    }
+}
+
+deref!('a, FieldMdpParams<'a> => Dict<'a>, dict);
+
+/// Which fields are locked by a [FieldMDP](FieldMdpParams) signature.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FieldMdpAction {
+    /// All fields in the document.
+    All,
+    /// Only the listed fields.
+    Include,
+    /// All fields except the listed ones.
+    Exclude,
+}
+
+impl FieldMdpAction {
+    pub(crate) fn to_name(self) -> Name<'static> {
+        match self {
+            Self::All => Name(b"All"),
+            Self::Include => Name(b"Include"),
+            Self::Exclude => Name(b"Exclude"),
+        }
+    }
+}
+
+/// Writer for the _transform parameters of the UR3 transform method_. PDF
+/// 1.6+.
+///
+/// This struct is created by [`SignatureReference::usage_rights`]. Each method
+/// grants a list of rights, like `FullSave` for the document, `Create` and
+/// `Delete` for annotations or `FillIn` and `Import` for forms.
+pub struct UrParams<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(UrParams: |obj| {
+    let mut dict = obj.dict();
+    dict.pair(Name(b"Type"), Name(b"TransformParams"));
+    dict.pair(Name(b"V"), Name(b"2.2"));
+    Self { dict }
+});
+
+impl<'a> UrParams<'a> {
+    /// Write the `/Document` attribute to grant rights for the whole document.
+    pub fn document<'b>(
+        &mut self,
+        rights: impl IntoIterator<Item = Name<'b>>,
+    ) -> &mut Self {
+        self.insert(Name(b"Document")).array().items(rights);
+        self
+    }
+
+    /// Write the `/Msg` attribute to set a message the viewer displays when
+    /// granting the rights.
+    pub fn message(&mut self, message: TextStr) -> &mut Self {
+        self.pair(Name(b"Msg"), message);
+        self
+    }
+
+    /// Write the `/Annots` attribute to grant rights for annotations.
+    pub fn annotations<'b>(
+        &mut self,
+        rights: impl IntoIterator<Item = Name<'b>>,
+    ) -> &mut Self {
+        self.insert(Name(b"Annots")).array().items(rights);
+        self
+    }
+
+    /// Write the `/Form` attribute to grant rights for interactive forms.
+    pub fn form<'b>(&mut self, rights: impl IntoIterator<Item = Name<'b>>) -> &mut Self {
+        self.insert(Name(b"Form")).array().items(rights);
+        self
+    }
+
+    /// Write the `/Signature` attribute to grant rights for signatures.
+    pub fn signature<'b>(
+        &mut self,
+        rights: impl IntoIterator<Item = Name<'b>>,
+    ) -> &mut Self {
+        self.insert(Name(b"Signature")).array().items(rights);
+        self
+    }
+
+    /// Write the `/EF` attribute to grant rights for embedded files.
+    pub fn embedded_files<'b>(
+        &mut self,
+        rights: impl IntoIterator<Item = Name<'b>>,
+    ) -> &mut Self {
+        self.insert(Name(b"EF")).array().items(rights);
+        self
+    }
+
+    /// Write the `/P` attribute to set whether the granted rights are the only
+    /// ones enabled in the viewer.
+    ///
+    /// _Default value_: `false`.
+    pub fn restrict_others(&mut self, restrict: bool) -> &mut Self {
+        self.pair(Name(b"P"), restrict);
+        self
+    }
+}
+
+deref!('a, UrParams<'a> => Dict<'a>, dict);
+
+/// Writer for a _permissions dictionary_. PDF 1.5+.
+///
+/// This struct is created by [`Catalog::permissions`].
+pub struct DocumentPermissions<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(DocumentPermissions: |obj| Self { dict: obj.dict() });
+
+impl<'a> DocumentPermissions<'a> {
+    /// Write the `/DocMDP` attribute to reference the [signature](Signature)
+    /// that certifies the document.
+    pub fn doc_mdp(&mut self, id: Ref) -> &mut Self {
+        self.pair(Name(b"DocMDP"), id);
+        self
+    }
+
+    /// Write the `/UR3` attribute to reference the [signature](Signature)
+    /// that grants usage rights. Deprecated in PDF 2.0.
+    pub fn ur3(&mut self, id: Ref) -> &mut Self {
+        self.pair(Name(b"UR3"), id);
+        self
+    }
+}
+
+deref!('a, DocumentPermissions<'a> => Dict<'a>, dict);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_certification_signature() {
+        let mut c = Chunk::new();
+        let mut sig = c.signature(Ref::new(1));
+        sig.filter(Name(b"Adobe.PPKLite"))
+            .sub_filter(Name(b"adbe.pkcs7.detached"))
+            .byte_range([(0, 100), (200, 50)]);
+        let mut refs = sig.reference();
+        refs.push().doc_mdp().permissions(MdpPermissions::NoChanges);
+        refs.push()
+            .field_mdp()
+            .action(FieldMdpAction::Include)
+            .fields([TextStr("total")]);
+        refs.finish();
+        sig.finish();
+        test!(
+            c.as_bytes(),
+            b"1 0 obj",
+            b"<<",
+            b"  /Type /Sig",
+            b"  /Filter /Adobe.PPKLite",
+            b"  /SubFilter /adbe.pkcs7.detached",
+            b"  /ByteRange [0 100 200 50]",
+            b"  /Reference [<<",
+            b"    /Type /SigRef",
+            b"    /TransformMethod /DocMDP",
+            b"    /TransformParams <<",
+            b"      /Type /TransformParams",
+            b"      /V /1.2",
+            b"      /P 1",
+            b"    >>",
+            b"  >> <<",
+            b"    /Type /SigRef",
+            b"    /TransformMethod /FieldMDP",
+            b"    /TransformParams <<",
+            b"      /Type /TransformParams",
+            b"      /V /1.2",
+            b"      /Action /Include",
+            b"      /Fields [(total)]",
+            b"    >>",
+            b"  >>]",
+            b">>",
+            b"endobj\n\n",
+        );
+    }
+
+    #[test]
+    fn test_signature_placeholder() {
+        let mut c = Chunk::new();
+        let range = c.signature(Ref::new(1)).contents_placeholder(4);
+        assert_eq!(&c.as_bytes()[range], b"<00000000>");
+        test!(
+            c.as_bytes(),
+            b"1 0 obj",
+            b"<<\n  /Type /Sig\n  /Contents <00000000>\n>>",
+            b"endobj\n\n",
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use alloc::collections::BTreeMap;
+
+use super::*;
+
+/// Size statistics for the objects in a chunk.
+///
+/// This is useful to diagnose why a PDF is larger than expected. It is created
+/// by [`Chunk::stats`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    /// Statistics for each indirect object, sorted by descending size.
+    pub objects: Vec<ObjectStats>,
+}
+
+/// Size statistics for a single indirect object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectStats {
+    /// The object's id.
+    pub id: Ref,
+    /// The number of bytes the object takes up, including its header and any
+    /// whitespace or comments up to the next object.
+    pub size: usize,
+    /// The value of the object's `/Type` entry, if it is a dictionary or
+    /// stream with such an entry.
+    pub kind: Option<String>,
+    /// The number of bytes of stream data, if the object is a stream.
+    pub stream_len: Option<usize>,
+    /// The value of the stream's `/Filter` entry, if any.
+    pub filter: Option<String>,
+}
+
+impl Stats {
+    /// Compute the statistics for a chunk.
+    pub(crate) fn new(chunk: &Chunk) -> Self {
+        let mut offsets = chunk.offsets.clone();
+        offsets.sort_by_key(|&(_, offset)| offset);
+
+        let mut objects = vec![];
+        let mut iter = offsets.iter().copied().peekable();
+        while let Some((id, offset)) = iter.next() {
+            let end = iter.peek().map_or(chunk.buf.len(), |&(_, offset)| offset);
+            let slice = &chunk.buf[offset..end];
+
+            let (head, stream_len) = match split_stream(slice) {
+                Some((head, data)) => (head, Some(data.len())),
+                None => (slice, None),
+            };
+
+            objects.push(ObjectStats {
+                id,
+                size: slice.len(),
+                kind: top_level_value(head, b"Type"),
+                stream_len,
+                filter: stream_len.and_then(|_| top_level_value(head, b"Filter")),
+            });
+        }
+
+        objects.sort_by(|a, b| b.size.cmp(&a.size).then(a.id.cmp(&b.id)));
+        Self { objects }
+    }
+
+    /// The total number of bytes taken up by the objects.
+    pub fn total_size(&self) -> usize {
+        self.objects.iter().map(|object| object.size).sum()
+    }
+
+    /// The `n` largest objects.
+    pub fn largest(&self, n: usize) -> &[ObjectStats] {
+        &self.objects[..n.min(self.objects.len())]
+    }
+
+    /// The total number of stream data bytes per filter. Unfiltered streams are
+    /// listed under `None`.
+    pub fn stream_bytes_by_filter(&self) -> BTreeMap<Option<String>, usize> {
+        let mut map = BTreeMap::new();
+        for object in &self.objects {
+            if let Some(len) = object.stream_len {
+                *map.entry(object.filter.clone()).or_default() += len;
+            }
+        }
+        map
+    }
+
+    /// The number of objects per `/Type`. Objects without a type are listed
+    /// under `None`.
+    pub fn count_by_type(&self) -> BTreeMap<Option<String>, usize> {
+        let mut map = BTreeMap::new();
+        for object in &self.objects {
+            *map.entry(object.kind.clone()).or_default() += 1;
+        }
+        map
+    }
+}
+
+/// Split an indirect stream object into the part up to the stream data and the
+/// data itself.
+fn split_stream(slice: &[u8]) -> Option<(&[u8], &[u8])> {
+    let marker = b"\n>>\nstream\n";
+    let head = memchr::memmem::find(slice, marker)?;
+    let start = head + marker.len();
+    let end = memchr::memmem::rfind(slice, b"\nendstream")?;
+    Some((&slice[..head], slice.get(start..end)?))
+}
+
+/// Find the value of a key in the top-level dictionary of an object, as
+/// formatted by this crate.
+pub(crate) fn top_level_value(head: &[u8], key: &[u8]) -> Option<String> {
+    let mut needle = b"\n  /".to_vec();
+    needle.extend(key);
+    needle.push(b' ');
+    let start = memchr::memmem::find(head, &needle)? + needle.len();
+    let rest = &head[start..];
+    let end = memchr::memchr(b'\n', rest).unwrap_or(rest.len());
+    let value = core::str::from_utf8(&rest[..end]).ok()?;
+    Some(value.strip_prefix('/').unwrap_or(value).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let mut c = Chunk::new();
+        c.font_descriptor(Ref::new(1)).name(Name(b"MyFont"));
+        c.stream(Ref::new(2), b"abcdefgh").filter(Filter::FlateDecode);
+        c.stream(Ref::new(3), b"abc");
+        c.indirect(Ref::new(4)).primitive(1);
+
+        let stats = c.stats();
+        assert_eq!(stats.total_size(), c.len());
+        assert_eq!(stats.largest(1)[0].id, Ref::new(2));
+        assert_eq!(
+            stats.largest(1)[0],
+            ObjectStats {
+                id: Ref::new(2),
+                size: 83,
+                kind: None,
+                stream_len: Some(8),
+                filter: Some("FlateDecode".into()),
+            }
+        );
+        assert_eq!(
+            stats.stream_bytes_by_filter().into_iter().collect::<Vec<_>>(),
+            [(None, 3), (Some("FlateDecode".into()), 8)]
+        );
+        assert_eq!(
+            stats.count_by_type().into_iter().collect::<Vec<_>>(),
+            [(None, 3), (Some("FontDescriptor".into()), 1)]
+        );
+    }
+}
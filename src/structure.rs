@@ -1,4 +1,5 @@
 use crate::color::SeparationInfo;
+use crate::types::{Color, DashPattern};
 
 use super::*;
 
@@ -41,6 +42,37 @@ impl<'a> Catalog<'a> {
         self
     }
 
+    /// Start writing the `/OpenAction` attribute as a destination to show when
+    /// the document is opened. Mutually exclusive with
+    /// [`open_action`](Self::open_action) and [`open_at`](Self::open_at).
+    /// PDF 1.1+.
+    pub fn open_destination(&mut self) -> Destination<'_> {
+        self.insert(Name(b"OpenAction")).start()
+    }
+
+    /// Start writing the `/OpenAction` attribute as an action to perform when
+    /// the document is opened. Mutually exclusive with
+    /// [`open_destination`](Self::open_destination) and
+    /// [`open_at`](Self::open_at). PDF 1.1+.
+    pub fn open_action(&mut self) -> Action<'_> {
+        self.insert(Name(b"OpenAction")).start()
+    }
+
+    /// Write the `/OpenAction` attribute to open the document at the given
+    /// page. With a zoom factor, the page is shown at that magnification.
+    /// Otherwise, the whole page is fit into the window. PDF 1.1+.
+    pub fn open_at(&mut self, page: Ref, zoom: Option<f32>) -> &mut Self {
+        let mut destination = self.open_destination().page(page);
+        match zoom {
+            Some(zoom) => {
+                destination.item(Name(b"XYZ")).item(Null).item(Null).item(zoom);
+                destination.finish();
+            }
+            None => destination.fit(),
+        }
+        self
+    }
+
     /// Start writing the `/ViewerPreferences` dictionary. PDF 1.2+.
     pub fn viewer_preferences(&mut self) -> ViewerPreferences<'_> {
         self.insert(Name(b"ViewerPreferences")).start()
@@ -91,6 +123,20 @@ impl<'a> Catalog<'a> {
         self
     }
 
+    /// Write the `/Version` attribute from a [`PdfVersion`]. This is useful to
+    /// upgrade an existing file to PDF 2.0 in an incremental update. PDF 1.4+.
+    pub fn pdf_version(&mut self, version: PdfVersion) -> &mut Self {
+        self.version(version.major(), version.minor())
+    }
+
+    /// Write the `/URI` dictionary with the `/Base` attribute. This is the base
+    /// URI against which relative URIs in [URI actions](Action::uri) are
+    /// resolved. PDF 1.1+.
+    pub fn uri_base(&mut self, base: Str) -> &mut Self {
+        self.insert(Name(b"URI")).dict().pair(Name(b"Base"), base);
+        self
+    }
+
     /// Start writing the `/AA` dictionary. This sets the additional actions for
     /// the whole document. PDF 1.4+.
     pub fn additional_actions(&mut self) -> AdditionalActions<'_> {
@@ -103,6 +149,14 @@ impl<'a> Catalog<'a> {
         self.insert(Name(b"AcroForm")).start()
     }
 
+    /// Write the `/NeedsRendering` attribute to set whether the viewer must
+    /// regenerate the document from the [XFA](Form::xfa) form when opening
+    /// it, e.g. for dynamic forms. PDF 1.7+. Deprecated in PDF 2.0.
+    pub fn needs_rendering(&mut self, needs: bool) -> &mut Self {
+        self.pair(Name(b"NeedsRendering"), needs);
+        self
+    }
+
     /// Write the `/Metadata` attribute to specify the document's metadata. PDF
     /// 1.4+.
     ///
@@ -128,6 +182,67 @@ impl<'a> Catalog<'a> {
         self.insert(Name(b"SeparationInfo")).start()
     }
 
+    /// Start writing the `/PieceInfo` dictionary to store application-specific
+    /// data about the document. PDF 1.3+.
+    ///
+    /// The dictionary maps application names to their data dictionaries. If
+    /// this is set, the document information's `/ModDate` is required.
+    pub fn piece_info(&mut self) -> TypedDict<'_, PieceInfo<'_>> {
+        self.insert(Name(b"PieceInfo")).dict().typed()
+    }
+
+    /// Write the `/AF` attribute to reference the file specifications of the
+    /// files associated with the whole document. PDF 2.0+.
+    pub fn associated_files(&mut self, ids: impl IntoIterator<Item = Ref>) -> &mut Self {
+        self.insert(Name(b"AF")).array().items(ids);
+        self
+    }
+
+    /// Write the entries that accessibility validators check first: the
+    /// document's `/Lang`, a `/MarkInfo` dictionary marking it as tagged and
+    /// `/ViewerPreferences` that display the document title instead of the
+    /// file name. If `associated_files` is not empty, the `/AF` array is
+    /// written, too.
+    ///
+    /// Since this writes the `/MarkInfo` and `/ViewerPreferences`
+    /// dictionaries, it must not be combined with [`Self::mark_info`] and
+    /// [`Self::viewer_preferences`]. The document must additionally have a
+    /// [structure tree](Self::struct_tree_root) and a `/Title` in its
+    /// [document information](crate::Pdf::document_info) or metadata.
+    pub fn accessibility_basics(
+        &mut self,
+        lang: TextStr,
+        associated_files: impl IntoIterator<Item = Ref>,
+    ) -> &mut Self {
+        self.lang(lang);
+        self.mark_info().marked(true);
+        self.viewer_preferences().display_doc_title(true);
+        let mut files = associated_files.into_iter().peekable();
+        if files.peek().is_some() {
+            self.associated_files(files);
+        }
+        self
+    }
+
+    /// Start writing the `/Perms` dictionary to reference the signatures that
+    /// certify the document or grant usage rights. PDF 1.5+.
+    pub fn permissions(&mut self) -> DocumentPermissions<'_> {
+        self.insert(Name(b"Perms")).start()
+    }
+
+    /// Start writing the `/SpiderInfo` dictionary to record how the document
+    /// was captured from the web. PDF 1.3+.
+    pub fn spider_info(&mut self) -> WebCaptureInfo<'_> {
+        self.insert(Name(b"SpiderInfo")).start()
+    }
+
+    /// Start writing the `/OCProperties` dictionary to list the document's
+    /// optional content groups and their configurations. Required if the
+    /// document contains optional content. PDF 1.5+.
+    pub fn optional_content(&mut self) -> OcProperties<'_> {
+        self.insert(Name(b"OCProperties")).start()
+    }
+
     /// Start writing the `/OutputIntents` array to specify the output
     /// destinations for the document. PDF 1.4+.
     ///
@@ -140,6 +255,58 @@ impl<'a> Catalog<'a> {
 
 deref!('a, Catalog<'a> => Dict<'a>, dict);
 
+/// A version of the PDF specification.
+///
+/// Starting with [`V2_0`](Self::V2_0), the document may use constructs from
+/// ISO 32000-2, like [UTF-8 text strings](crate::TextStrUtf8), blend modes on
+/// annotations and [unencrypted wrapper
+/// documents](FileSpec::encrypted_payload).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum PdfVersion {
+    /// PDF 1.0.
+    V1_0,
+    /// PDF 1.1.
+    V1_1,
+    /// PDF 1.2.
+    V1_2,
+    /// PDF 1.3.
+    V1_3,
+    /// PDF 1.4.
+    V1_4,
+    /// PDF 1.5.
+    V1_5,
+    /// PDF 1.6.
+    V1_6,
+    /// PDF 1.7 (ISO 32000-1).
+    V1_7,
+    /// PDF 2.0 (ISO 32000-2).
+    V2_0,
+}
+
+impl PdfVersion {
+    /// The major version number.
+    pub fn major(self) -> u8 {
+        match self {
+            Self::V2_0 => 2,
+            _ => 1,
+        }
+    }
+
+    /// The minor version number.
+    pub fn minor(self) -> u8 {
+        match self {
+            Self::V1_0 | Self::V2_0 => 0,
+            Self::V1_1 => 1,
+            Self::V1_2 => 2,
+            Self::V1_3 => 3,
+            Self::V1_4 => 4,
+            Self::V1_5 => 5,
+            Self::V1_6 => 6,
+            Self::V1_7 => 7,
+        }
+    }
+}
+
 /// How the viewer should lay out the pages in the document.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum PageLayout {
@@ -281,6 +448,16 @@ impl<'a> ViewerPreferences<'a> {
         self
     }
 
+    /// Write the `/DisplayDocTitle` attribute to specify whether the viewer
+    /// shows the document's title from the document information or metadata
+    /// in its title bar instead of the file name. PDF 1.4+.
+    ///
+    /// _Default value_: `false`.
+    pub fn display_doc_title(&mut self, display: bool) -> &mut Self {
+        self.pair(Name(b"DisplayDocTitle"), display);
+        self
+    }
+
     /// Write the `/Direction` attribute to aid the viewer in how to lay out the
     /// pages visually. PDF 1.3+.
     pub fn direction(&mut self, dir: Direction) -> &mut Self {
@@ -320,7 +497,10 @@ impl<'a> StructTreeRoot<'a> {
 
     /// Start writing the `/IDTree` attribute to map element identifiers to
     /// their corresponding structure element objects. Required if any elements
-    /// have element identifiers.
+    /// have [element identifiers](StructElement::id).
+    ///
+    /// The names in the tree must be sorted. The identifiers returned by
+    /// [`Chunk::tag_table`] already are.
     pub fn id_tree(&mut self) -> NameTree<'_, Ref> {
         self.dict.insert(Name(b"IDTree")).start()
     }
@@ -389,6 +569,15 @@ impl<'a> StructElement<'a> {
         self
     }
 
+    /// Write the `/ID` attribute to set a unique identifier for the element.
+    /// The identifier must also be listed in the structure tree root's
+    /// [ID tree](StructTreeRoot::id_tree). Table cells use it to refer to their
+    /// header cells.
+    pub fn id(&mut self, id: Str) -> &mut Self {
+        self.dict.pair(Name(b"ID"), id);
+        self
+    }
+
     /// Write the `/Pg` attribute to specify the page some or all of this
     /// structure element is located on.
     pub fn page(&mut self, page: Ref) -> &mut Self {
@@ -914,11 +1103,25 @@ impl NumberingStyle {
 /// This struct is created by [`Pdf::document_info`].
 pub struct DocumentInfo<'a> {
     dict: Dict<'a>,
+    auto_producer: Option<Range<usize>>,
+    pub(crate) producer: Option<&'a mut Option<String>>,
 }
 
-writer!(DocumentInfo: |obj| Self { dict: obj.dict() });
+writer!(DocumentInfo: |obj| Self {
+    dict: obj.dict(),
+    auto_producer: None,
+    producer: None,
+});
 
 impl<'a> DocumentInfo<'a> {
+    /// Write this crate's name and version as the `/Producer`, remembering
+    /// where it went so that an explicit producer can replace it.
+    pub(crate) fn auto_producer(&mut self) {
+        let start = self.dict.buf_len();
+        self.pair(Name(b"Producer"), TextStr(AUTO_PRODUCER));
+        self.auto_producer = Some(start..self.dict.buf_len());
+    }
+
     /// Write the `/Title` attribute to set the document's title. PDF 1.1+.
     pub fn title(&mut self, title: TextStr) -> &mut Self {
         self.pair(Name(b"Title"), title);
@@ -953,7 +1156,16 @@ impl<'a> DocumentInfo<'a> {
 
     /// Write the `/Producer` attribute to set the name of the product that
     /// converted or wrote this PDF.
+    ///
+    /// This replaces the automatic producer written by default, see
+    /// [`Pdf::set_auto_producer`].
     pub fn producer(&mut self, producer: TextStr) -> &mut Self {
+        if let Some(range) = self.auto_producer.take() {
+            self.dict.remove_pair(range);
+        }
+        if let Some(slot) = &mut self.producer {
+            **slot = Some(producer.0.into());
+        }
         self.pair(Name(b"Producer"), producer);
         self
     }
@@ -984,6 +1196,10 @@ impl<'a> DocumentInfo<'a> {
 
 deref!('a, DocumentInfo<'a> => Dict<'a>, dict);
 
+/// The producer written by [`Pdf::set_auto_producer`].
+pub(crate) const AUTO_PRODUCER: &str =
+    concat!(env!("CARGO_PKG_NAME"), " ", env!("CARGO_PKG_VERSION"));
+
 /// Whether a document has been adjusted with traps.
 ///
 /// Those account for colorant misregistration during the printing process.
@@ -1007,6 +1223,59 @@ impl TrappingStatus {
     }
 }
 
+bitflags::bitflags! {
+    /// User access permissions for an encrypted document.
+    ///
+    /// These are the permission bits of the `/P` entry in the standard security
+    /// handler's encryption dictionary. This crate does not encrypt documents,
+    /// but [`Pdf::restrict_permissions`] applies the permissions to a document
+    /// that opens without a password. Otherwise, they are only effective
+    /// together with an encryption implementation that computes the `/O` and
+    /// `/U` entries and encrypts the document's strings and streams.
+    ///
+    /// For a print-only document, use `PRINT | PRINT_HIGH_QUALITY`:
+    /// ```
+    /// use pdf_writer::{Pdf, Ref};
+    /// use pdf_writer::types::Permissions;
+    ///
+    /// let print_only = Permissions::PRINT | Permissions::PRINT_HIGH_QUALITY;
+    /// assert_eq!(print_only.to_p_value(), -1852);
+    ///
+    /// let mut pdf = Pdf::new();
+    /// pdf.restrict_permissions(Ref::new(1), b"owner", print_only);
+    /// ```
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+    pub struct Permissions: u32 {
+        /// Print the document, possibly in degraded quality.
+        const PRINT = 1 << 2;
+        /// Modify the document contents.
+        const MODIFY = 1 << 3;
+        /// Copy or otherwise extract text and graphics.
+        const COPY = 1 << 4;
+        /// Add or modify annotations and fill in form fields.
+        const ANNOTATE = 1 << 5;
+        /// Fill in existing form fields, even if `ANNOTATE` is not set.
+        const FILL_FORMS = 1 << 8;
+        /// Extract text and graphics for accessibility purposes.
+        const EXTRACT_FOR_ACCESSIBILITY = 1 << 9;
+        /// Assemble the document, i.e. insert, rotate or delete pages.
+        const ASSEMBLE = 1 << 10;
+        /// Print the document in full quality.
+        const PRINT_HIGH_QUALITY = 1 << 11;
+    }
+}
+
+impl Permissions {
+    /// The value of the `/P` entry for these permissions.
+    ///
+    /// All reserved bits are set as required by the specification, so the
+    /// value is negative.
+    pub fn to_p_value(self) -> i32 {
+        // Bits 1-2 must be zero, bits 7-8 and 13-32 must be one.
+        (self.bits() | 0xFFFF_F0C0) as i32
+    }
+}
+
 /// Writer for a _page tree dictionary_.
 ///
 /// This struct is created by [`Chunk::pages`].
@@ -1070,13 +1339,37 @@ writer!(Page: |obj| {
 });
 
 impl<'a> Page<'a> {
+    /// Start writing a template page, which has the type `Template` instead
+    /// of `Page`.
+    pub(crate) fn start_template(obj: Obj<'a>) -> Self {
+        let mut dict = obj.dict();
+        dict.pair(Name(b"Type"), Name(b"Template"));
+        Self { dict }
+    }
+
     /// Write the `/Parent` attribute. Required.
     pub fn parent(&mut self, parent: Ref) -> &mut Self {
         self.pair(Name(b"Parent"), parent);
         self
     }
 
-    /// Write the `/LastModified` attribute. PDF 1.3+.
+    /// Write the `/ID` attribute to set the digital identifier of the page's
+    /// [web capture content set](ContentSet). PDF 1.3+.
+    pub fn capture_id(&mut self, id: Str) -> &mut Self {
+        self.pair(Name(b"ID"), id);
+        self
+    }
+
+    /// Write the `/PZ` attribute to set the preferred magnification for
+    /// viewing a page that was captured from the web. PDF 1.3+.
+    pub fn preferred_zoom(&mut self, zoom: f32) -> &mut Self {
+        self.pair(Name(b"PZ"), zoom);
+        self
+    }
+
+    /// Write the `/LastModified` attribute to set when the page's contents
+    /// were last changed. Required if the page has a
+    /// [`/PieceInfo`](Self::piece_info) dictionary. PDF 1.3+.
     pub fn last_modified(&mut self, date: Date) -> &mut Self {
         self.pair(Name(b"LastModified"), date);
         self
@@ -1139,13 +1432,46 @@ impl<'a> Page<'a> {
     }
 
     /// Write the `/Rotate` attribute. This is the number of degrees the page
-    /// should be rotated clockwise when displayed. This should be a multiple
-    /// of 90.
+    /// should be rotated clockwise when displayed.
+    ///
+    /// The written value is normalized to the range `0..360`, for example
+    /// `-90` becomes `270`.
+    ///
+    /// Panics if `degrees` is not a multiple of 90.
     pub fn rotate(&mut self, degrees: i32) -> &mut Self {
+        assert!(
+            degrees % 90 == 0,
+            "rotation must be a multiple of 90 degrees (is {degrees})"
+        );
+        let degrees = degrees.rem_euclid(360);
         self.pair(Name(b"Rotate"), degrees);
         self
     }
 
+    /// Set up the page to show landscape content on paper that is given in
+    /// portrait orientation.
+    ///
+    /// If `rotate` is false, the `/MediaBox` is simply `paper` with its axes
+    /// swapped. Otherwise, the `/MediaBox` is `paper` itself and the page is
+    /// displayed rotated by 90 degrees, which keeps the paper's orientation
+    /// for printing.
+    ///
+    /// Returns the matrix that must be applied to the page's content (for
+    /// example with [`Content::transform`]) so that it can be drawn in
+    /// landscape coordinates in both cases. Without rotation, that is the
+    /// identity matrix.
+    pub fn landscape(&mut self, paper: Rect, rotate: bool) -> [f32; 6] {
+        let Rect { x1, y1, x2, y2 } = paper;
+        if rotate {
+            self.media_box(paper);
+            self.rotate(90);
+            [0.0, 1.0, -1.0, 0.0, x2, y1]
+        } else {
+            self.media_box(Rect::new(y1, x1, y2, x2));
+            [1.0, 0.0, 0.0, 1.0, 0.0, 0.0]
+        }
+    }
+
     /// Start writing the `/Group` dictionary to set the transparency settings
     /// for the page. PDF 1.4+.
     pub fn group(&mut self) -> Group<'_> {
@@ -1154,7 +1480,8 @@ impl<'a> Page<'a> {
 
     /// Write the `/Thumb` attribute to set an [image][ImageXObject] as the page
     /// thumbnail. Must be RGB, Grayscale, or an indexed color space based on
-    /// the former two.
+    /// the former two. Use [`Chunk::thumbnail`] to write an image that adheres
+    /// to these restrictions.
     pub fn thumbnail(&mut self, id: Ref) -> &mut Self {
         self.pair(Name(b"Thumb"), id);
         self
@@ -1179,6 +1506,29 @@ impl<'a> Page<'a> {
         self
     }
 
+    /// Write the `/Annots` (annotations) array, leaving out the widgets that
+    /// were flattened into the page content with
+    /// [`Content::draw_appearance`]. Nothing is written if no annotation
+    /// remains.
+    pub fn annotations_flattened(
+        &mut self,
+        ids: impl IntoIterator<Item = Ref>,
+        flattened: &[Ref],
+    ) -> &mut Self {
+        let mut ids = ids.into_iter().filter(|id| !flattened.contains(id)).peekable();
+        if ids.peek().is_some() {
+            self.annotations(ids);
+        }
+        self
+    }
+
+    /// Write the `/Annots` attribute as a reference to an [annotations
+    /// array](Chunk::annotations_array) that is written separately.
+    pub fn annotations_ref(&mut self, id: Ref) -> &mut Self {
+        self.pair(Name(b"Annots"), id);
+        self
+    }
+
     /// Write the `/StructParents` attribute to indicate the [structure tree
     /// elements][StructElement] the contents of this XObject may belong to. PDF 1.3+.
     pub fn struct_parents(&mut self, key: i32) -> &mut Self {
@@ -1209,15 +1559,165 @@ impl<'a> Page<'a> {
     /// Write the `/Metadata` attribute to specify the page's metadata. PDF
     /// 1.4+.
     ///
-    /// The reference shall point to a [metadata stream](Metadata).
+    /// The reference shall point to a [metadata stream](Metadata) with XMP
+    /// data that only describes this page, e.g. for page-level provenance.
     pub fn metadata(&mut self, id: Ref) -> &mut Self {
         self.pair(Name(b"Metadata"), id);
         self
     }
+
+    /// Start writing the `/SeparationInfo` dictionary to specify which
+    /// separation colors are in use on the page and how it relates to other
+    /// pages in the document. PDF 1.3+.
+    pub fn separation_info(&mut self) -> SeparationInfo<'_> {
+        self.insert(Name(b"SeparationInfo")).start()
+    }
+
+    /// Start writing the `/BoxColorInfo` dictionary to specify how the page's
+    /// boundaries should be displayed as guidelines. PDF 1.4+.
+    pub fn box_color_info(&mut self) -> BoxColorInfo<'_> {
+        self.insert(Name(b"BoxColorInfo")).start()
+    }
+
+    /// Start writing the `/PieceInfo` dictionary to store application-specific
+    /// data about the page. PDF 1.3+.
+    ///
+    /// The dictionary maps application names to their data dictionaries. If
+    /// this is set, the page's `/LastModified` attribute is required.
+    pub fn piece_info(&mut self) -> TypedDict<'_, PieceInfo<'_>> {
+        self.insert(Name(b"PieceInfo")).dict().typed()
+    }
 }
 
 deref!('a, Page<'a> => Dict<'a>, dict);
 
+/// Writer for a _data dictionary_ in a page-piece dictionary. PDF 1.3+.
+///
+/// This struct is created by [`Page::piece_info`], [`Catalog::piece_info`] and
+/// [`FormXObject::piece_info`].
+pub struct PieceInfo<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(PieceInfo: |obj| Self { dict: obj.dict() });
+
+impl PieceInfo<'_> {
+    /// Write the `/LastModified` attribute to set when the data was last
+    /// modified. Required.
+    pub fn last_modified(&mut self, date: Date) -> &mut Self {
+        self.pair(Name(b"LastModified"), date);
+        self
+    }
+
+    /// Start writing the `/Private` attribute with arbitrary data that is only
+    /// meaningful to the application that wrote it.
+    pub fn private(&mut self) -> Obj<'_> {
+        self.insert(Name(b"Private"))
+    }
+}
+
+deref!('a, PieceInfo<'a> => Dict<'a>, dict);
+
+/// Writer for a _box color information dictionary_. PDF 1.4+.
+///
+/// This struct is created by [`Page::box_color_info`].
+pub struct BoxColorInfo<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(BoxColorInfo: |obj| Self { dict: obj.dict() });
+
+impl BoxColorInfo<'_> {
+    /// Start writing the `/CropBox` attribute to set how the crop box guides
+    /// are displayed.
+    pub fn crop_box(&mut self) -> BoxStyle<'_> {
+        self.insert(Name(b"CropBox")).start()
+    }
+
+    /// Start writing the `/BleedBox` attribute to set how the bleed box guides
+    /// are displayed.
+    pub fn bleed_box(&mut self) -> BoxStyle<'_> {
+        self.insert(Name(b"BleedBox")).start()
+    }
+
+    /// Start writing the `/TrimBox` attribute to set how the trim box guides
+    /// are displayed.
+    pub fn trim_box(&mut self) -> BoxStyle<'_> {
+        self.insert(Name(b"TrimBox")).start()
+    }
+
+    /// Start writing the `/ArtBox` attribute to set how the art box guides are
+    /// displayed.
+    pub fn art_box(&mut self) -> BoxStyle<'_> {
+        self.insert(Name(b"ArtBox")).start()
+    }
+}
+
+deref!('a, BoxColorInfo<'a> => Dict<'a>, dict);
+
+/// Writer for a _box style dictionary_. PDF 1.4+.
+///
+/// This struct is created by the methods of [`BoxColorInfo`].
+pub struct BoxStyle<'a> {
+    dict: Dict<'a>,
+}
+
+writer!(BoxStyle: |obj| Self { dict: obj.dict() });
+
+impl BoxStyle<'_> {
+    /// Write the `/C` attribute to set the color of the guides in `DeviceRGB`.
+    /// Defaults to black.
+    pub fn color(&mut self, r: f32, g: f32, b: f32) -> &mut Self {
+        self.insert(Name(b"C")).array().items([r, g, b]);
+        self
+    }
+
+    /// Write the `/W` attribute to set the width of the guides in default user
+    /// space units. Defaults to `1.0`.
+    pub fn width(&mut self, width: f32) -> &mut Self {
+        self.pair(Name(b"W"), width);
+        self
+    }
+
+    /// Write the `/S` attribute to set the line style of the guides. Defaults
+    /// to solid.
+    pub fn style(&mut self, style: GuideStyle) -> &mut Self {
+        self.pair(Name(b"S"), style.to_name());
+        self
+    }
+
+    /// Write the `/D` attribute to set the dash pattern of dashed guides as
+    /// alternating dash and gap lengths. Defaults to `[3]`.
+    ///
+    /// Guide dash patterns always start at the beginning, so the phase is
+    /// ignored.
+    pub fn dash_pattern(&mut self, pattern: DashPattern) -> &mut Self {
+        self.insert(Name(b"D")).array().items(pattern.array.iter().copied());
+        self
+    }
+}
+
+deref!('a, BoxStyle<'a> => Dict<'a>, dict);
+
+/// The line style of page boundary guides.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum GuideStyle {
+    /// A solid line.
+    Solid,
+    /// A dashed line with the dash pattern given by
+    /// [`BoxStyle::dash_pattern`].
+    Dashed,
+}
+
+impl GuideStyle {
+    pub(crate) fn to_name(self) -> Name<'static> {
+        match self {
+            Self::Solid => Name(b"S"),
+            Self::Dashed => Name(b"D"),
+        }
+    }
+}
+
 /// Writer for an _outline dictionary_.
 ///
 /// This struct is created by [`Chunk::outline`].
@@ -1323,6 +1823,13 @@ impl<'a> OutlineItem<'a> {
         self.insert(Name(b"Dest")).start()
     }
 
+    /// Write the `/Dest` attribute to set the destination of this outline item
+    /// to a [destination](Chunk::destination) written as an indirect object.
+    pub fn dest_ref(&mut self, id: Ref) -> &mut Self {
+        self.pair(Name(b"Dest"), id);
+        self
+    }
+
     /// Write the `/Dest` attribute to set the destination of this
     /// outline item to a named destination.
     pub fn dest_name(&mut self, name: Name) -> &mut Self {
@@ -1337,6 +1844,14 @@ impl<'a> OutlineItem<'a> {
         self
     }
 
+    /// Write the `/C` attribute using a typed color. Since outline item colors
+    /// must be RGB, the color is [converted](Color::to_rgb) if necessary.
+    /// PDF 1.4+.
+    pub fn color(&mut self, color: Color) -> &mut Self {
+        self.insert(Name(b"C")).array().items(color.to_rgb());
+        self
+    }
+
     /// Write the `/F` attribute. PDF 1.4+.
     pub fn flags(&mut self, flags: OutlineItemFlags) -> &mut Self {
         self.pair(Name(b"F"), flags.bits() as i32);
@@ -1390,20 +1905,21 @@ impl Names<'_> {
         self.dict.insert(Name(b"Pages")).start()
     }
 
-    /// Start writing the `/Template` attribute to name [pages](Pages) outside
-    /// of the page tree as templates for interactive forms. PDF 1.3+.
+    /// Start writing the `/Templates` attribute to name [pages](Page) outside
+    /// of the page tree as templates for interactive forms. The pages are
+    /// written with [`Chunk::template_page`]. PDF 1.3+.
     pub fn templates(&mut self) -> NameTree<'_, Ref> {
         self.dict.insert(Name(b"Templates")).start()
     }
 
     /// Start writing the `/IDS` attribute to map identifiers to Web Capture
-    /// content sets. PDF 1.3+.
+    /// [content sets](ContentSet). PDF 1.3+.
     pub fn capture_ids(&mut self) -> NameTree<'_, Ref> {
         self.dict.insert(Name(b"IDS")).start()
     }
 
-    /// Start writing the `/URLS` attribute to map URLs to Web Capture content
-    /// sets. PDF 1.3+.
+    /// Start writing the `/URLS` attribute to map URLs to Web Capture
+    /// [content sets](ContentSet). PDF 1.3+.
     pub fn capture_urls(&mut self) -> NameTree<'_, Ref> {
         self.dict.insert(Name(b"URLS")).start()
     }
@@ -1433,7 +1949,8 @@ deref!('a, Names<'a> => Dict<'a>, dict);
 ///
 /// A dictionary mapping to this struct is created by
 /// [`Chunk::destinations`]. This struct is also created by
-/// [`Action::destination`].
+/// [`Action::destination`] and as an indirect object by
+/// [`Chunk::destination`].
 pub struct Destination<'a> {
     array: Array<'a>,
 }
@@ -1447,6 +1964,14 @@ impl<'a> Destination<'a> {
         self
     }
 
+    /// The target page as a zero-based page index. Required for destinations
+    /// in other documents, e.g. of a `RemoteGoTo` or an embedded GoTo
+    /// [action](Action), instead of [`page`](Self::page).
+    pub fn page_index(mut self, index: i32) -> Self {
+        self.item(index);
+        self
+    }
+
     /// Write the `/XYZ` command which skips to the specified coordinated.
     pub fn xyz(mut self, left: f32, top: f32, zoom: Option<f32>) {
         self.item(Name(b"XYZ"));
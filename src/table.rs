@@ -0,0 +1,306 @@
+use super::*;
+use crate::types::{StructRole, TableHeaderScope};
+
+/// A table whose structure elements can be written with [`Chunk::tag_table`].
+///
+/// The table is described as rows of cells. Cells that span multiple rows or
+/// columns take up the space in the following rows, which then list fewer
+/// cells, just like in HTML.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TableTagger {
+    /// The rows of the table.
+    pub rows: Vec<Vec<TableCell>>,
+}
+
+impl TableTagger {
+    /// Create a new table without rows.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a row of cells.
+    pub fn row(mut self, cells: impl IntoIterator<Item = TableCell>) -> Self {
+        self.rows.push(cells.into_iter().collect());
+        self
+    }
+}
+
+/// A cell in a [`TableTagger`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableCell {
+    /// The scope of the cell if it is a header cell.
+    pub header: Option<TableHeaderScope>,
+    /// The number of rows the cell spans.
+    pub row_span: i32,
+    /// The number of columns the cell spans.
+    pub col_span: i32,
+    /// The page the cell's content is located on.
+    pub page: Option<Ref>,
+    /// The marked content identifiers of the cell's content on its page.
+    pub marked_content: Vec<i32>,
+}
+
+impl TableCell {
+    /// Create a new data cell spanning a single row and column.
+    pub fn data() -> Self {
+        Self {
+            header: None,
+            row_span: 1,
+            col_span: 1,
+            page: None,
+            marked_content: vec![],
+        }
+    }
+
+    /// Create a new header cell spanning a single row and column.
+    pub fn header(scope: TableHeaderScope) -> Self {
+        Self { header: Some(scope), ..Self::data() }
+    }
+
+    /// Set the number of rows and columns the cell spans.
+    pub fn span(mut self, rows: i32, cols: i32) -> Self {
+        self.row_span = rows;
+        self.col_span = cols;
+        self
+    }
+
+    /// Set the page and add a marked content identifier of the cell's
+    /// content.
+    pub fn content(mut self, page: Ref, mcid: i32) -> Self {
+        self.page = Some(page);
+        self.marked_content.push(mcid);
+        self
+    }
+}
+
+/// A cell with its position in the table grid.
+struct Placed<'a> {
+    cell: &'a TableCell,
+    id: Ref,
+    row: i32,
+    col: i32,
+}
+
+impl Placed<'_> {
+    /// Whether this cell is a header that applies to the other cell.
+    fn heads(&self, other: &Placed) -> bool {
+        let overlaps =
+            |a: i32, a_len: i32, b: i32, b_len: i32| a < b + b_len && b < a + a_len;
+        let above = self.row + self.cell.row_span <= other.row
+            && overlaps(self.col, self.cell.col_span, other.col, other.cell.col_span);
+        let left = self.col + self.cell.col_span <= other.col
+            && overlaps(self.row, self.cell.row_span, other.row, other.cell.row_span);
+        match self.cell.header {
+            Some(TableHeaderScope::Column) => above,
+            Some(TableHeaderScope::Row) => left,
+            Some(TableHeaderScope::Both) => above || left,
+            None => false,
+        }
+    }
+}
+
+/// The `/ID` of a header cell's structure element.
+fn header_id(id: Ref) -> String {
+    format!("TH{}", id.get())
+}
+
+/// Write the structure elements for the table, its rows and its cells.
+pub(crate) fn write_table(
+    chunk: &mut Chunk,
+    id: Ref,
+    parent: Ref,
+    alloc: &mut Ref,
+    table: &TableTagger,
+) -> Vec<(String, Ref)> {
+    let row_ids: Vec<Ref> = table.rows.iter().map(|_| alloc.bump()).collect();
+
+    // Lay out the cells on a grid, skipping positions that are taken up by
+    // cells spanning from previous rows.
+    let mut taken: Vec<Vec<bool>> = vec![];
+    let mut rows: Vec<Vec<Placed>> = vec![];
+    for (r, row) in table.rows.iter().enumerate() {
+        let mut placed = vec![];
+        let mut col = 0;
+        for cell in row {
+            let is_taken = |taken: &Vec<Vec<bool>>, col: usize| {
+                taken.get(r).and_then(|row| row.get(col)).copied().unwrap_or(false)
+            };
+            while is_taken(&taken, col) {
+                col += 1;
+            }
+
+            let rows_spanned = cell.row_span.max(1) as usize;
+            let cols_spanned = cell.col_span.max(1) as usize;
+            for grid_row in r..r + rows_spanned {
+                if taken.len() <= grid_row {
+                    taken.resize(grid_row + 1, vec![]);
+                }
+                let grid_row = &mut taken[grid_row];
+                if grid_row.len() < col + cols_spanned {
+                    grid_row.resize(col + cols_spanned, false);
+                }
+                grid_row[col..col + cols_spanned].fill(true);
+            }
+
+            placed.push(Placed {
+                cell,
+                id: alloc.bump(),
+                row: r as i32,
+                col: col as i32,
+            });
+            col += cols_spanned;
+        }
+        rows.push(placed);
+    }
+
+    let mut elem = chunk.struct_element(id);
+    elem.kind(StructRole::Table);
+    elem.parent(parent);
+    elem.children().items(row_ids.iter().copied());
+    elem.finish();
+
+    let mut ids = vec![];
+    for (placed, &row_id) in rows.iter().zip(&row_ids) {
+        let mut elem = chunk.struct_element(row_id);
+        elem.kind(StructRole::TR);
+        elem.parent(id);
+        elem.children().items(placed.iter().map(|cell| cell.id));
+        elem.finish();
+
+        for cell in placed {
+            let headers: Vec<String> = rows
+                .iter()
+                .flatten()
+                .filter(|other| other.id != cell.id && other.heads(cell))
+                .map(|other| header_id(other.id))
+                .collect();
+
+            let mut elem = chunk.struct_element(cell.id);
+            elem.kind(if cell.cell.header.is_some() {
+                StructRole::TH
+            } else {
+                StructRole::TD
+            });
+            elem.parent(row_id);
+            if cell.cell.header.is_some() {
+                let header_id = header_id(cell.id);
+                elem.id(Str(header_id.as_bytes()));
+                ids.push((header_id, cell.id));
+            }
+            if let Some(page) = cell.cell.page {
+                elem.page(page);
+            }
+            if !cell.cell.marked_content.is_empty() {
+                elem.children().items(cell.cell.marked_content.iter().copied());
+            }
+
+            let spans = cell.cell.row_span > 1 || cell.cell.col_span > 1;
+            if cell.cell.header.is_some() || spans || !headers.is_empty() {
+                let mut attributes = elem.attributes();
+                let mut attrs = attributes.push().table();
+                if let Some(scope) = cell.cell.header {
+                    attrs.scope(scope);
+                }
+                if cell.cell.row_span > 1 {
+                    attrs.row_span(cell.cell.row_span);
+                }
+                if cell.cell.col_span > 1 {
+                    attrs.col_span(cell.cell.col_span);
+                }
+                if !headers.is_empty() {
+                    attrs.headers().items(headers.iter().map(|h| Str(h.as_bytes())));
+                }
+            }
+        }
+    }
+
+    ids.sort();
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_table() {
+        let page = Ref::new(20);
+        let table = TableTagger::new()
+            .row([
+                TableCell::header(TableHeaderScope::Column).content(page, 0),
+                TableCell::data().span(2, 1).content(page, 1),
+            ])
+            .row([TableCell::data().content(page, 2)]);
+
+        let mut c = Chunk::new();
+        let ids = c.tag_table(Ref::new(1), Ref::new(10), &mut Ref::new(2), &table);
+        assert_eq!(ids, [("TH4".to_string(), Ref::new(4))]);
+        test!(
+            c.as_bytes(),
+            b"1 0 obj",
+            b"<<",
+            b"  /Type /StructElem",
+            b"  /S /Table",
+            b"  /P 10 0 R",
+            b"  /K [2 0 R 3 0 R]",
+            b">>",
+            b"endobj\n",
+            b"2 0 obj",
+            b"<<",
+            b"  /Type /StructElem",
+            b"  /S /TR",
+            b"  /P 1 0 R",
+            b"  /K [4 0 R 5 0 R]",
+            b">>",
+            b"endobj\n",
+            b"4 0 obj",
+            b"<<",
+            b"  /Type /StructElem",
+            b"  /S /TH",
+            b"  /P 2 0 R",
+            b"  /ID (TH4)",
+            b"  /Pg 20 0 R",
+            b"  /K [0]",
+            b"  /A [<<",
+            b"    /O /Table",
+            b"    /Scope /Column",
+            b"  >>]",
+            b">>",
+            b"endobj\n",
+            b"5 0 obj",
+            b"<<",
+            b"  /Type /StructElem",
+            b"  /S /TD",
+            b"  /P 2 0 R",
+            b"  /Pg 20 0 R",
+            b"  /K [1]",
+            b"  /A [<<",
+            b"    /O /Table",
+            b"    /RowSpan 2",
+            b"  >>]",
+            b">>",
+            b"endobj\n",
+            b"3 0 obj",
+            b"<<",
+            b"  /Type /StructElem",
+            b"  /S /TR",
+            b"  /P 1 0 R",
+            b"  /K [6 0 R]",
+            b">>",
+            b"endobj\n",
+            b"6 0 obj",
+            b"<<",
+            b"  /Type /StructElem",
+            b"  /S /TD",
+            b"  /P 3 0 R",
+            b"  /Pg 20 0 R",
+            b"  /K [2]",
+            b"  /A [<<",
+            b"    /O /Table",
+            b"    /Headers [(TH4)]",
+            b"  >>]",
+            b">>",
+            b"endobj\n\n",
+        );
+    }
+}
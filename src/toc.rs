@@ -0,0 +1,184 @@
+use super::*;
+
+/// An entry in a table of contents.
+///
+/// A tree of these is turned into a document outline by [`Chunk::toc`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TocEntry {
+    /// The title displayed in the outline.
+    pub title: String,
+    /// The page the entry links to.
+    pub page: Ref,
+    /// The vertical position on the page the entry links to, in default user
+    /// space units.
+    pub y: f32,
+    /// Whether the entry's children are visible initially.
+    pub open: bool,
+    /// The nested entries.
+    pub children: Vec<TocEntry>,
+}
+
+impl TocEntry {
+    /// Create a new, closed entry without children.
+    pub fn new(title: impl Into<String>, page: Ref, y: f32) -> Self {
+        Self {
+            title: title.into(),
+            page,
+            y,
+            open: false,
+            children: vec![],
+        }
+    }
+
+    /// Set whether the entry's children are visible initially.
+    pub fn open(mut self, open: bool) -> Self {
+        self.open = open;
+        self
+    }
+
+    /// Add a nested entry.
+    pub fn child(mut self, child: TocEntry) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// The number of descendants that are visible if this entry is open.
+    fn descendants(&self) -> i32 {
+        self.children.iter().map(|child| 1 + child.visible()).sum()
+    }
+
+    /// The number of descendants that are visible given the entry's state.
+    fn visible(&self) -> i32 {
+        if self.open {
+            self.descendants()
+        } else {
+            0
+        }
+    }
+}
+
+/// Write the outline dictionary and all outline items for the entries.
+pub(crate) fn write_toc(
+    chunk: &mut Chunk,
+    id: Ref,
+    alloc: &mut Ref,
+    entries: &[TocEntry],
+) {
+    let ids: Vec<Ref> = entries.iter().map(|_| alloc.bump()).collect();
+
+    let mut outline = chunk.outline(id);
+    if let (Some(&first), Some(&last)) = (ids.first(), ids.last()) {
+        outline.first(first);
+        outline.last(last);
+    }
+    outline.count(entries.iter().map(|entry| 1 + entry.visible()).sum());
+    outline.finish();
+
+    write_level(chunk, id, entries, &ids, alloc);
+}
+
+/// Write the outline items for one level of the tree and recurse into
+/// their children.
+fn write_level(
+    chunk: &mut Chunk,
+    parent: Ref,
+    entries: &[TocEntry],
+    ids: &[Ref],
+    alloc: &mut Ref,
+) {
+    for (i, (entry, &id)) in entries.iter().zip(ids).enumerate() {
+        let child_ids: Vec<Ref> = entry.children.iter().map(|_| alloc.bump()).collect();
+
+        let mut item = chunk.outline_item(id);
+        item.title(TextStr(&entry.title));
+        item.parent(parent);
+        if let Some(&prev) = i.checked_sub(1).and_then(|i| ids.get(i)) {
+            item.prev(prev);
+        }
+        if let Some(&next) = ids.get(i + 1) {
+            item.next(next);
+        }
+        if let (Some(&first), Some(&last)) = (child_ids.first(), child_ids.last()) {
+            item.first(first);
+            item.last(last);
+            item.count(if entry.open {
+                entry.descendants()
+            } else {
+                -entry.descendants()
+            });
+        }
+        item.dest().page(entry.page).xyz(0.0, entry.y, None);
+        item.finish();
+
+        write_level(chunk, id, &entry.children, &child_ids, alloc);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toc() {
+        let page = Ref::new(10);
+        let entries = [
+            TocEntry::new("Intro", page, 800.0).open(true).child(TocEntry::new(
+                "Motivation",
+                page,
+                700.0,
+            )),
+            TocEntry::new("Methods", page, 600.0)
+                .child(TocEntry::new("Setup", page, 500.0)),
+        ];
+
+        let mut c = Chunk::new();
+        c.toc(Ref::new(1), &mut Ref::new(2), &entries);
+        test!(
+            c.as_bytes(),
+            b"1 0 obj",
+            b"<<",
+            b"  /Type /Outlines",
+            b"  /First 2 0 R",
+            b"  /Last 3 0 R",
+            b"  /Count 3",
+            b">>",
+            b"endobj\n",
+            b"2 0 obj",
+            b"<<",
+            b"  /Title (Intro)",
+            b"  /Parent 1 0 R",
+            b"  /Next 3 0 R",
+            b"  /First 4 0 R",
+            b"  /Last 4 0 R",
+            b"  /Count 1",
+            b"  /Dest [10 0 R /XYZ 0 800 0]",
+            b">>",
+            b"endobj\n",
+            b"4 0 obj",
+            b"<<",
+            b"  /Title (Motivation)",
+            b"  /Parent 2 0 R",
+            b"  /Dest [10 0 R /XYZ 0 700 0]",
+            b">>",
+            b"endobj\n",
+            b"3 0 obj",
+            b"<<",
+            b"  /Title (Methods)",
+            b"  /Parent 1 0 R",
+            b"  /Prev 2 0 R",
+            b"  /First 5 0 R",
+            b"  /Last 5 0 R",
+            b"  /Count -1",
+            b"  /Dest [10 0 R /XYZ 0 600 0]",
+            b">>",
+            b"endobj\n",
+            b"5 0 obj",
+            b"<<",
+            b"  /Title (Setup)",
+            b"  /Parent 3 0 R",
+            b"  /Dest [10 0 R /XYZ 0 500 0]",
+            b">>",
+            b"endobj\n\n",
+        );
+    }
+}
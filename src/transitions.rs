@@ -2,7 +2,7 @@ use super::*;
 
 /// Writer for a _transition dictionary_.
 ///
-/// This struct is created by [`Page::transition`].
+/// This struct is created by [`Page::transition`] and [`Action::transition`].
 pub struct Transition<'a> {
     dict: Dict<'a>,
 }
@@ -0,0 +1,204 @@
+//! A minimal re-parser that checks the structure of a finished PDF.
+
+use alloc::string::String;
+use alloc::{format, vec};
+
+/// Check the cross-reference table, object headers, stream lengths and
+/// delimiter balance of a finished PDF file.
+///
+/// This only understands the subset of PDF syntax produced by this crate.
+pub fn validate(buf: &[u8]) -> Result<(), String> {
+    let marker = b"startxref";
+    let pos = memchr::memmem::rfind(buf, marker).ok_or("missing startxref")?;
+    let rest = strip_eol(&buf[pos + marker.len()..]).ok_or("missing startxref")?;
+    let xref = parse_int(rest).ok_or("invalid startxref")?;
+
+    let mut rest = buf.get(xref..).ok_or("startxref out of bounds")?;
+    rest = rest
+        .strip_prefix(b"xref")
+        .and_then(strip_eol)
+        .ok_or("startxref does not point to xref")?;
+    rest = rest.strip_prefix(b"0 ").ok_or("invalid xref subsection")?;
+    let len = parse_int(rest).ok_or("invalid xref subsection length")?;
+    let digits = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+    rest = strip_eol(&rest[digits..]).ok_or("invalid xref subsection")?;
+
+    for id in 0..len {
+        let entry = rest.get(id * 20..id * 20 + 20).ok_or("truncated xref table")?;
+        let eol = [&b"\r\n"[..], b" \n", b" \r"].contains(&&entry[18..]);
+        if !eol || ![b'n', b'f'].contains(&entry[17]) {
+            return Err(format!("invalid xref entry for object {id}"));
+        }
+
+        if entry[17] == b'n' {
+            let offset = parse_int(entry).ok_or("invalid xref offset")?;
+            let object = buf.get(offset..).ok_or("xref offset out of bounds")?;
+            let gen = parse_int(&entry[11..]).ok_or("invalid xref generation")?;
+            let header = format!("{id} {gen} obj");
+            let body = object
+                .strip_prefix(header.as_bytes())
+                .and_then(strip_eol)
+                .ok_or_else(|| format!("xref entry for object {id} has wrong offset"))?;
+
+            validate_object(body).map_err(|err| format!("object {id}: {err}"))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Check the body of an indirect object, up to and including `endobj`.
+pub(crate) fn validate_object(body: &[u8]) -> Result<(), String> {
+    let mut stack = vec![];
+    let mut i = 0;
+    while i < body.len() {
+        let rest = &body[i..];
+        match body[i] {
+            b'%' => {
+                i += memchr::memchr2(b'\n', b'\r', rest).unwrap_or(rest.len());
+            }
+            b'(' => {
+                let mut depth = 0;
+                loop {
+                    match body.get(i) {
+                        Some(b'(') => depth += 1,
+                        Some(b')') if depth == 1 => break,
+                        Some(b')') => depth -= 1,
+                        Some(b'\\') => i += 1,
+                        Some(_) => {}
+                        None => return Err("unterminated string".into()),
+                    }
+                    i += 1;
+                }
+                i += 1;
+            }
+            b'<' if rest.starts_with(b"<<") => {
+                stack.push(b'<');
+                i += 2;
+            }
+            b'<' => {
+                i += memchr::memchr(b'>', rest).ok_or("unterminated hex string")? + 1;
+            }
+            b'>' if rest.starts_with(b">>") => {
+                if stack.pop() != Some(b'<') {
+                    return Err("unbalanced dictionary".into());
+                }
+                i += 2;
+            }
+            b'[' => {
+                stack.push(b'[');
+                i += 1;
+            }
+            b']' => {
+                if stack.pop() != Some(b'[') {
+                    return Err("unbalanced array".into());
+                }
+                i += 1;
+            }
+            b'e' if rest.starts_with(b"endobj") => {
+                return if stack.is_empty() {
+                    Ok(())
+                } else {
+                    Err("unclosed delimiter".into())
+                };
+            }
+            b's' if stack.is_empty() && is_stream(rest) => {
+                let len = stream_length(&body[..i])?;
+                let data = body.len() - strip_eol(&rest[6..]).unwrap().len();
+                let end = body.get(data + len..).ok_or("stream data out of bounds")?;
+                let end = strip_eol(end)
+                    .and_then(|end| end.strip_prefix(b"endstream"))
+                    .ok_or("stream length does not match data")?;
+                i = body.len() - end.len();
+            }
+            _ => i += 1,
+        }
+    }
+
+    Err("missing endobj".into())
+}
+
+/// Whether `rest` starts with the `stream` keyword and an end-of-line marker.
+fn is_stream(rest: &[u8]) -> bool {
+    rest.strip_prefix(b"stream").and_then(strip_eol).is_some()
+}
+
+/// Strip a leading end-of-line marker.
+fn strip_eol(buf: &[u8]) -> Option<&[u8]> {
+    buf.strip_prefix(b"\r\n")
+        .or_else(|| buf.strip_prefix(b"\n"))
+        .or_else(|| buf.strip_prefix(b"\r"))
+}
+
+/// Extract the value of the direct `/Length` entry from a stream dictionary.
+fn stream_length(dict: &[u8]) -> Result<usize, String> {
+    let needle = b"\n  /Length ";
+    let pos = memchr::memmem::find(dict, needle).ok_or("stream without /Length")?;
+    parse_int(&dict[pos + needle.len()..]).ok_or_else(|| "invalid stream length".into())
+}
+
+/// Parse a leading non-negative integer.
+fn parse_int(buf: &[u8]) -> Option<usize> {
+    let len = buf.iter().take_while(|b| b.is_ascii_digit()).count();
+    core::str::from_utf8(&buf[..len]).ok()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Name, Pdf, Rect, Ref, Str};
+
+    fn sample() -> Vec<u8> {
+        sample_with_limit(None)
+    }
+
+    fn sample_with_limit(limit: Option<usize>) -> Vec<u8> {
+        let mut pdf = Pdf::new();
+        pdf.set_line_limit(limit);
+        pdf.catalog(Ref::new(1)).pages(Ref::new(2));
+        pdf.pages(Ref::new(2)).kids([Ref::new(3)]).count(1);
+        pdf.page(Ref::new(3))
+            .parent(Ref::new(2))
+            .media_box(Rect::new(0.0, 0.0, 10.0, 10.0))
+            .contents(Ref::new(5));
+        pdf.stream(Ref::new(5), b"endobj ] >> (");
+        pdf.indirect(Ref::new(6)).array().item(Str(b")[<")).item(Name(b"]"));
+        pdf.finish()
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(validate(&sample()), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_wrapped() {
+        for limit in 2..=12 {
+            assert_eq!(validate(&sample_with_limit(Some(limit))), Ok(()), "{limit}");
+        }
+    }
+
+    #[test]
+    fn test_validate_errors() {
+        let buf = sample();
+        let patched = |from: &[u8], to: &[u8]| {
+            let pos = memchr::memmem::find(&buf, from).unwrap();
+            let mut patched = buf.clone();
+            patched.splice(pos..pos + from.len(), to.iter().copied());
+            validate(&patched)
+        };
+
+        assert_eq!(
+            patched(b"/Length 13", b"/Length 12"),
+            Err("object 5: stream length does not match data".into())
+        );
+        assert_eq!(
+            patched(b"[3 0 R]", b"[3 0 R "),
+            Err("object 2: unbalanced dictionary".into())
+        );
+        assert_eq!(
+            patched(b"0000000016 00000 n", b"0000000017 00000 n"),
+            Err("xref entry for object 1 has wrong offset".into())
+        );
+    }
+}
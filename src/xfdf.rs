@@ -0,0 +1,500 @@
+use alloc::collections::BTreeMap;
+use core::fmt::Write;
+
+use super::*;
+
+/// Serialize the annotations in a chunk as an XFDF file.
+pub(crate) fn write_xfdf(chunk: &Chunk, pages: &[Ref]) -> String {
+    let mut offsets = chunk.offsets.clone();
+    offsets.sort_by_key(|&(_, offset)| offset);
+
+    let mut objects = BTreeMap::new();
+    let mut iter = offsets.iter().copied().peekable();
+    while let Some((id, offset)) = iter.next() {
+        let end = iter.peek().map_or(chunk.buf.len(), |&(_, offset)| offset);
+        if let Some(value) = Parser::new(&chunk.buf[offset..end]).object() {
+            objects.insert(id, value);
+        }
+    }
+
+    // Annotations are assigned to pages through the pages' `/Annots` arrays
+    // and, as a fallback, through their own `/P` entry.
+    let mut page_of = BTreeMap::new();
+    for (i, page) in pages.iter().enumerate() {
+        let mut annots = objects.get(page).and_then(|page| page.get(b"Annots"));
+
+        // The array may also be written as a separate indirect object.
+        if let Some(Value::Ref(array)) = annots {
+            annots = objects.get(array);
+        }
+
+        let Some(Value::Array(annots)) = annots else { continue };
+        for annot in annots {
+            if let Value::Ref(annot) = annot {
+                page_of.entry(*annot).or_insert(i);
+            }
+        }
+    }
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<xfdf xmlns=\"http://ns.adobe.com/xfdf/\" xml:space=\"preserve\">\n");
+    out.push_str("<annots>\n");
+
+    for &(id, _) in &offsets {
+        let Some(annot) = objects.get(&id) else { continue };
+
+        // The `/Type` entry is optional, so annotations in some page's
+        // `/Annots` array count as well.
+        if annot.name(b"Type") != Some(b"Annot") && !page_of.contains_key(&id) {
+            continue;
+        }
+
+        let Some(subtype) = annot.name(b"Subtype") else { continue };
+        if subtype == b"Widget" || subtype == b"Popup" {
+            continue;
+        }
+
+        let page = page_of.get(&id).copied().or_else(|| match annot.get(b"P")? {
+            Value::Ref(parent) => pages.iter().position(|page| page == parent),
+            _ => None,
+        });
+
+        let element = escape(&String::from_utf8_lossy(subtype).to_lowercase());
+        write!(out, "<{element}").unwrap();
+        if let Some(page) = page {
+            write!(out, " page=\"{page}\"").unwrap();
+        }
+        if let Some(rect) = annot.get(b"Rect").and_then(Value::numbers) {
+            write!(out, " rect=\"{}\"", rect.join(",")).unwrap();
+        }
+        if let Some(color) = annot.get(b"C").and_then(Value::numbers) {
+            if let Some(color) = hex_color(&color) {
+                write!(out, " color=\"{color}\"").unwrap();
+            }
+        }
+        if let Some(Value::Number(opacity)) = annot.get(b"CA") {
+            write!(out, " opacity=\"{opacity}\"").unwrap();
+        }
+        for (key, attr) in [(&b"NM"[..], "name"), (b"T", "title"), (b"M", "date")] {
+            if let Some(text) = annot.text(key) {
+                write!(out, " {attr}=\"{}\"", escape(&text)).unwrap();
+            }
+        }
+        if let Some(quads) = annot.get(b"QuadPoints").and_then(Value::numbers) {
+            write!(out, " coords=\"{}\"", quads.join(",")).unwrap();
+        }
+
+        match annot.text(b"Contents") {
+            Some(text) => {
+                write!(out, ">\n<contents>{}</contents>\n</{element}>\n", escape(&text))
+                    .unwrap();
+            }
+            None => out.push_str("/>\n"),
+        }
+    }
+
+    out.push_str("</annots>\n");
+    out.push_str("</xfdf>\n");
+    out
+}
+
+/// A parsed PDF object, as far as the export needs to understand it.
+#[derive(Debug, PartialEq)]
+enum Value<'a> {
+    /// An integer or real number in its written form.
+    Number(&'a str),
+    /// A name without the leading slash.
+    Name(&'a [u8]),
+    /// The decoded bytes of a literal or hexadecimal string.
+    Str(Vec<u8>),
+    /// An indirect reference.
+    Ref(Ref),
+    /// An array.
+    Array(Vec<Value<'a>>),
+    /// A dictionary.
+    Dict(Vec<(&'a [u8], Value<'a>)>),
+    /// A boolean, null or other keyword.
+    Other,
+}
+
+impl<'a> Value<'a> {
+    /// The value of a key if this is a dictionary.
+    fn get(&self, key: &[u8]) -> Option<&Value<'a>> {
+        match self {
+            Self::Dict(pairs) => pairs.iter().find(|(k, _)| *k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// The name value of a key if this is a dictionary.
+    fn name(&self, key: &[u8]) -> Option<&'a [u8]> {
+        match self.get(key)? {
+            Self::Name(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// The decoded text string value of a key if this is a dictionary.
+    fn text(&self, key: &[u8]) -> Option<String> {
+        match self.get(key)? {
+            Self::Str(bytes) => decode_text(bytes),
+            _ => None,
+        }
+    }
+
+    /// The items if this is an array of numbers.
+    fn numbers(&self) -> Option<Vec<&'a str>> {
+        match self {
+            Self::Array(items) => items
+                .iter()
+                .map(|item| match item {
+                    Self::Number(number) => Some(*number),
+                    _ => None,
+                })
+                .collect(),
+            _ => None,
+        }
+    }
+}
+
+/// A tokenizer and parser for the objects in a chunk.
+struct Parser<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    /// Parse an indirect object, starting at its `N G obj` header.
+    fn object(&mut self) -> Option<Value<'a>> {
+        self.int()?;
+        self.int()?;
+        self.skip_whitespace();
+        if self.regular() != b"obj" {
+            return None;
+        }
+        self.value()
+    }
+
+    /// Parse a direct object.
+    fn value(&mut self) -> Option<Value<'a>> {
+        self.skip_whitespace();
+        let rest = &self.buf[self.pos..];
+        match *rest.first()? {
+            b'<' if rest.starts_with(b"<<") => {
+                self.pos += 2;
+                let mut pairs = vec![];
+                loop {
+                    self.skip_whitespace();
+                    if self.buf[self.pos..].starts_with(b">>") {
+                        self.pos += 2;
+                        return Some(Value::Dict(pairs));
+                    }
+                    let Value::Name(key) = self.value()? else { return None };
+                    pairs.push((key, self.value()?));
+                }
+            }
+            b'[' => {
+                self.pos += 1;
+                let mut items = vec![];
+                loop {
+                    self.skip_whitespace();
+                    if self.buf.get(self.pos) == Some(&b']') {
+                        self.pos += 1;
+                        return Some(Value::Array(items));
+                    }
+                    items.push(self.value()?);
+                }
+            }
+            b'(' => self.literal().map(Value::Str),
+            b'<' => self.hex().map(Value::Str),
+            b'/' => {
+                self.pos += 1;
+                Some(Value::Name(self.regular()))
+            }
+            b'0'..=b'9' | b'+' | b'-' | b'.' => {
+                let number = self.regular();
+                let start = self.pos;
+                if let Some(id) = self.reference(number) {
+                    return Some(Value::Ref(id));
+                }
+                self.pos = start;
+                core::str::from_utf8(number).ok().map(Value::Number)
+            }
+            _ if is_regular(rest[0]) => {
+                self.regular();
+                Some(Value::Other)
+            }
+            _ => None,
+        }
+    }
+
+    /// Parse the rest of an indirect reference whose object number has
+    /// already been read.
+    fn reference(&mut self, number: &[u8]) -> Option<Ref> {
+        let id = core::str::from_utf8(number).ok()?.parse().ok()?;
+        let generation = self.int()?;
+        self.skip_whitespace();
+        if self.regular() != b"R" || id <= 0 {
+            return None;
+        }
+        Some(Ref::new(id).with_generation(u16::try_from(generation).ok()?))
+    }
+
+    /// Parse a non-negative integer after optional whitespace.
+    fn int(&mut self) -> Option<i32> {
+        self.skip_whitespace();
+        let token = self.regular();
+        if !token.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        core::str::from_utf8(token).ok()?.parse().ok()
+    }
+
+    /// Parse a literal string, starting at the opening parenthesis.
+    fn literal(&mut self) -> Option<Vec<u8>> {
+        self.pos += 1;
+        let mut bytes = vec![];
+        let mut depth = 1;
+        loop {
+            let byte = *self.buf.get(self.pos)?;
+            self.pos += 1;
+            match byte {
+                b'(' => depth += 1,
+                b')' if depth == 1 => return Some(bytes),
+                b')' => depth -= 1,
+                b'\\' => {
+                    let escaped = *self.buf.get(self.pos)?;
+                    self.pos += 1;
+                    match escaped {
+                        b'n' => bytes.push(b'\n'),
+                        b'r' => bytes.push(b'\r'),
+                        b't' => bytes.push(b'\t'),
+                        b'b' => bytes.push(b'\x08'),
+                        b'f' => bytes.push(b'\x0c'),
+                        b'0'..=b'7' => {
+                            let mut code = u32::from(escaped - b'0');
+                            for _ in 0..2 {
+                                match self.buf.get(self.pos) {
+                                    Some(&d @ b'0'..=b'7') => {
+                                        code = code * 8 + u32::from(d - b'0');
+                                        self.pos += 1;
+                                    }
+                                    _ => break,
+                                }
+                            }
+                            bytes.push(code as u8);
+                        }
+                        // A backslash at the end of a line continues the
+                        // string on the next one.
+                        b'\r' => {
+                            if self.buf.get(self.pos) == Some(&b'\n') {
+                                self.pos += 1;
+                            }
+                        }
+                        b'\n' => {}
+                        other => bytes.push(other),
+                    }
+                    continue;
+                }
+                // An unescaped end-of-line marker is read as a line feed.
+                b'\r' => {
+                    if self.buf.get(self.pos) == Some(&b'\n') {
+                        self.pos += 1;
+                    }
+                    bytes.push(b'\n');
+                    continue;
+                }
+                _ => {}
+            }
+            bytes.push(byte);
+        }
+    }
+
+    /// Parse a hexadecimal string, starting at the opening angle bracket.
+    fn hex(&mut self) -> Option<Vec<u8>> {
+        self.pos += 1;
+        let end = self.pos + memchr::memchr(b'>', &self.buf[self.pos..])?;
+        let digits: Vec<u8> = self.buf[self.pos..end]
+            .iter()
+            .filter(|b| !is_whitespace(**b))
+            .map(|&b| char::from(b).to_digit(16).map(|d| d as u8))
+            .collect::<Option<_>>()?;
+        self.pos = end + 1;
+        Some(
+            digits
+                .chunks(2)
+                .map(|pair| pair[0] << 4 | pair.get(1).unwrap_or(&0))
+                .collect(),
+        )
+    }
+
+    /// Read a run of regular characters.
+    fn regular(&mut self) -> &'a [u8] {
+        let start = self.pos;
+        while self.buf.get(self.pos).copied().is_some_and(is_regular) {
+            self.pos += 1;
+        }
+        &self.buf[start..self.pos]
+    }
+
+    /// Skip whitespace and comments.
+    fn skip_whitespace(&mut self) {
+        while let Some(&byte) = self.buf.get(self.pos) {
+            if byte == b'%' {
+                while self.buf.get(self.pos).is_some_and(|&b| b != b'\n' && b != b'\r') {
+                    self.pos += 1;
+                }
+            } else if is_whitespace(byte) {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+/// Whether a byte is PDF whitespace.
+fn is_whitespace(byte: u8) -> bool {
+    matches!(byte, b'\0' | b'\t' | b'\n' | b'\x0C' | b'\r' | b' ')
+}
+
+/// Whether a byte is neither whitespace nor a delimiter.
+fn is_regular(byte: u8) -> bool {
+    !is_whitespace(byte) && !b"()<>[]{}/%".contains(&byte)
+}
+
+/// Convert the components of a gray or RGB color to a hex color.
+fn hex_color(components: &[&str]) -> Option<String> {
+    let components: Vec<f32> =
+        components.iter().map(|c| c.parse().ok()).collect::<Option<_>>()?;
+    let [r, g, b] = match components[..] {
+        [gray] => [gray; 3],
+        [r, g, b] => [r, g, b],
+        _ => return None,
+    };
+    let hex = |c: f32| (c.clamp(0.0, 1.0) * 255.0 + 0.5) as u8;
+    Some(format!("#{:02X}{:02X}{:02X}", hex(r), hex(g), hex(b)))
+}
+
+/// Decode the bytes of a text string.
+fn decode_text(bytes: &[u8]) -> Option<String> {
+    if let Some(utf16) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        let units: Vec<u16> = utf16
+            .chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16(&units).ok()
+    } else if let Some(utf8) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        String::from_utf8(utf8.to_vec()).ok()
+    } else {
+        Some(bytes.iter().map(|&b| b as char).collect())
+    }
+}
+
+/// Escape special characters for use in XML text and attributes.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AnnotationType, Color};
+
+    #[test]
+    fn test_parse_object() {
+        let obj =
+            b"4 0 obj\r\n<< /A [1 2 0 R -3.5]\r\n/B (x\\\r\ny) % note\r\n/C <4 1>/D/E>>";
+        let value = Parser::new(obj).object().unwrap();
+        assert_eq!(
+            value.get(b"A"),
+            Some(&Value::Array(vec![
+                Value::Number("1"),
+                Value::Ref(Ref::new(2)),
+                Value::Number("-3.5"),
+            ]))
+        );
+        assert_eq!(value.get(b"B"), Some(&Value::Str(b"xy".to_vec())));
+        assert_eq!(value.get(b"C"), Some(&Value::Str(vec![0x41])));
+        assert_eq!(value.name(b"D"), Some(&b"E"[..]));
+    }
+
+    #[test]
+    fn test_xfdf() {
+        let mut c = Chunk::new();
+        c.page(Ref::new(1)).annotations([Ref::new(3)]);
+        c.page(Ref::new(2)).annotations_ref(Ref::new(6));
+        c.annotations_array(Ref::new(6)).item(Ref::new(4));
+        c.annotation(Ref::new(3))
+            .subtype(AnnotationType::Highlight)
+            .rect(Rect::new(10.0, 10.0, 50.0, 26.0))
+            .quad_points([Rect::new(10.0, 10.0, 50.0, 26.0)])
+            .color(Color::Rgb(1.0, 1.0, 0.0))
+            .author(TextStr("Jane & Joe"))
+            .contents(TextStr("Check (this) 😀"));
+        c.annotation(Ref::new(4))
+            .subtype(AnnotationType::Text)
+            .rect(Rect::new(0.0, 0.0, 20.0, 20.0));
+        c.annotation(Ref::new(5)).subtype(AnnotationType::Widget);
+        c.annotation(Ref::new(7))
+            .subtype(AnnotationType::Square)
+            .rect(Rect::new(0.0, 0.0, 5.0, 5.0))
+            .page(Ref::new(1));
+
+        assert_eq!(
+            c.xfdf(&[Ref::new(1), Ref::new(2)]),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <xfdf xmlns=\"http://ns.adobe.com/xfdf/\" xml:space=\"preserve\">\n\
+             <annots>\n\
+             <highlight page=\"0\" rect=\"10,10,50,26\" color=\"#FFFF00\" \
+             title=\"Jane &amp; Joe\" coords=\"10,10,50,10,50,26,10,26\">\n\
+             <contents>Check (this) 😀</contents>\n\
+             </highlight>\n\
+             <text page=\"1\" rect=\"0,0,20,20\"/>\n\
+             <square page=\"0\" rect=\"0,0,5,5\"/>\n\
+             </annots>\n\
+             </xfdf>\n"
+        );
+    }
+
+    #[test]
+    fn test_xfdf_annotation_without_type() {
+        let mut c = Chunk::new();
+        c.page(Ref::new(1)).annotations([Ref::new(2)]);
+        c.indirect(Ref::new(2))
+            .dict()
+            .pair(Name(b"Subtype"), Name(b"Text"))
+            .pair(Name(b"Rect"), Rect::new(0.0, 0.0, 1.0, 1.0));
+        assert!(c.xfdf(&[Ref::new(1)]).contains("<text page=\"0\" rect=\"0,0,1,1\"/>"));
+    }
+
+    #[test]
+    fn test_xfdf_generations() {
+        let annot = Ref::new(3).with_generation(1);
+        let mut c = Chunk::new();
+        c.page(Ref::new(1)).annotations([annot]);
+        c.page(Ref::new(2).with_generation(2));
+        c.annotation(annot).subtype(AnnotationType::Text);
+        c.annotation(Ref::new(4))
+            .subtype(AnnotationType::Square)
+            .page(Ref::new(2).with_generation(2));
+        let xfdf = c.xfdf(&[Ref::new(1), Ref::new(2).with_generation(2)]);
+        assert!(xfdf.contains("<text page=\"0\"/>"));
+        assert!(xfdf.contains("<square page=\"1\"/>"));
+    }
+}
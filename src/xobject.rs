@@ -151,6 +151,78 @@ impl<'a> ImageXObject<'a> {
 
 deref!('a, ImageXObject<'a> => Stream<'a>, stream);
 
+/// Writer for a _thumbnail image stream_.
+///
+/// This is an image XObject restricted to the color spaces allowed for page
+/// thumbnails: `DeviceRGB`, `DeviceGray` and indexed color spaces based on
+/// them. This struct is created by [`Chunk::thumbnail`].
+pub struct Thumbnail<'a> {
+    image: ImageXObject<'a>,
+}
+
+impl<'a> Thumbnail<'a> {
+    /// Create a new thumbnail stream writer.
+    pub(crate) fn start(stream: Stream<'a>) -> Self {
+        Self { image: ImageXObject::start(stream) }
+    }
+
+    /// Write the `/Width` attribute.
+    pub fn width(&mut self, width: i32) -> &mut Self {
+        self.image.width(width);
+        self
+    }
+
+    /// Write the `/Height` attribute.
+    pub fn height(&mut self, height: i32) -> &mut Self {
+        self.image.height(height);
+        self
+    }
+
+    /// Write the `/BitsPerComponent` attribute. Required.
+    pub fn bits_per_component(&mut self, bits: i32) -> &mut Self {
+        self.image.bits_per_component(bits);
+        self
+    }
+
+    /// Write the `/ColorSpace` attribute as `DeviceRGB`.
+    pub fn device_rgb(&mut self) -> &mut Self {
+        self.image.color_space().device_rgb();
+        self
+    }
+
+    /// Write the `/ColorSpace` attribute as `DeviceGray`.
+    pub fn device_gray(&mut self) -> &mut Self {
+        self.image.color_space().device_gray();
+        self
+    }
+
+    /// Write the `/ColorSpace` attribute as an indexed color space based on
+    /// `DeviceRGB`. The `lookup` table must contain three bytes for each of the
+    /// `hival + 1` colors.
+    pub fn indexed_rgb(&mut self, hival: i32, lookup: &[u8]) -> &mut Self {
+        self.image.color_space().indexed(Name(b"DeviceRGB"), hival, lookup);
+        self
+    }
+
+    /// Write the `/ColorSpace` attribute as an indexed color space based on
+    /// `DeviceGray`. The `lookup` table must contain one byte for each of the
+    /// `hival + 1` colors.
+    pub fn indexed_gray(&mut self, hival: i32, lookup: &[u8]) -> &mut Self {
+        self.image.color_space().indexed(Name(b"DeviceGray"), hival, lookup);
+        self
+    }
+
+    /// Write the `/Decode` attribute to set the decoding of the image sample
+    /// colors to the specified color space. Must have twice the amount of
+    /// elements as the color space.
+    pub fn decode(&mut self, decode: impl IntoIterator<Item = f32>) -> &mut Self {
+        self.image.decode(decode);
+        self
+    }
+}
+
+deref!('a, Thumbnail<'a> => Stream<'a>, image);
+
 /// What to do with in-data mask information in `JPXDecode` images.
 pub enum SMaskInData {
     /// Discard the mask data.
@@ -253,6 +325,15 @@ impl<'a> FormXObject<'a> {
         self.pair(Name(b"LastModified"), last_modified);
         self
     }
+
+    /// Start writing the `/PieceInfo` dictionary to store application-specific
+    /// data about the XObject. PDF 1.3+.
+    ///
+    /// The dictionary maps application names to their data dictionaries. If
+    /// this is set, the `/LastModified` attribute is required.
+    pub fn piece_info(&mut self) -> TypedDict<'_, PieceInfo<'_>> {
+        self.insert(Name(b"PieceInfo")).dict().typed()
+    }
 }
 
 deref!('a, FormXObject<'a> => Stream<'a>, stream);